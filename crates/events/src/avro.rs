@@ -0,0 +1,171 @@
+use crate::database::TableEvent;
+use introspect_types::{TableSchema, TypeDef, avro_can_read};
+use starknet_types_core::felt::Felt;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AvroEvolutionError {
+    #[error("unknown column {column:#x} in table {table:#x}")]
+    UnknownColumn { table: Felt, column: Felt },
+
+    #[error("column {0} cannot be retyped without breaking Avro readers")]
+    IncompatibleRetype(String),
+
+    #[error("renaming {0} breaks Avro readers, records and fields are matched by name")]
+    Rename(String),
+}
+
+pub type AvroEvolutionResult<T> = Result<T, AvroEvolutionError>;
+
+/// Checks that records written with the current Avro schema of `table` can
+/// still be read once `event` is applied.
+///
+/// Added columns always carry a default and dropped columns are ignored by
+/// readers. Retypes must follow the Avro resolution rules (see `avro_can_read`)
+/// and renames are rejected as records and fields are matched by name.
+pub fn check_avro_evolution(table: &TableSchema, event: &TableEvent) -> AvroEvolutionResult<()> {
+    match event {
+        TableEvent::Create(_)
+        | TableEvent::Drop(_)
+        | TableEvent::AddColumn(_)
+        | TableEvent::AddColumns(_) => Ok(()),
+        TableEvent::DropColumn(event) => column_type(table, event.id).map(|_| ()),
+        TableEvent::DropColumns(event) => event
+            .ids
+            .iter()
+            .try_for_each(|id| column_type(table, *id).map(|_| ())),
+        TableEvent::Rename(_) => Err(AvroEvolutionError::Rename(table.name.clone())),
+        TableEvent::RenamePrimary(_) => Err(AvroEvolutionError::Rename(table.primary.name.clone())),
+        TableEvent::RenameColumn(event) => Err(rename(table, event.id)),
+        TableEvent::RenameColumns(event) => match event.columns.first() {
+            Some(column) => Err(rename(table, column.id)),
+            None => Ok(()),
+        },
+        TableEvent::RetypePrimary(event) => {
+            let old = TypeDef::from(&table.primary.type_def);
+            match avro_can_read(&old, &TypeDef::from(&event.type_def)) {
+                true => Ok(()),
+                false => Err(AvroEvolutionError::IncompatibleRetype(
+                    table.primary.name.clone(),
+                )),
+            }
+        }
+        TableEvent::RetypeColumn(event) => check_retype(table, event.id, &event.type_def),
+        TableEvent::RetypeColumns(event) => event
+            .columns
+            .iter()
+            .try_for_each(|column| check_retype(table, column.id, &column.type_def)),
+    }
+}
+
+fn column_type(table: &TableSchema, id: Felt) -> AvroEvolutionResult<(&str, &TypeDef)> {
+    table
+        .columns
+        .iter()
+        .find(|column| column.id == id)
+        .map(|column| (column.name.as_str(), &column.type_def))
+        .ok_or(AvroEvolutionError::UnknownColumn {
+            table: table.id,
+            column: id,
+        })
+}
+
+fn rename(table: &TableSchema, id: Felt) -> AvroEvolutionError {
+    match column_type(table, id) {
+        Ok((name, _)) => AvroEvolutionError::Rename(name.to_string()),
+        Err(err) => err,
+    }
+}
+
+fn check_retype(table: &TableSchema, id: Felt, type_def: &TypeDef) -> AvroEvolutionResult<()> {
+    let (name, old) = column_type(table, id)?;
+    match avro_can_read(old, type_def) {
+        true => Ok(()),
+        false => Err(AvroEvolutionError::IncompatibleRetype(name.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{AddColumn, RenameColumn, RetypeColumn, RetypePrimary};
+    use introspect_types::{ColumnDef, OptionDef, PrimaryDef, PrimaryTypeDef};
+
+    fn table() -> TableSchema {
+        TableSchema {
+            id: Felt::ONE,
+            name: "player".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("id".to_string(), vec![], PrimaryTypeDef::U32),
+            columns: vec![ColumnDef::new(
+                Felt::ONE,
+                "score".to_string(),
+                vec![],
+                TypeDef::U16,
+            )],
+        }
+    }
+
+    fn retype(id: Felt, type_def: TypeDef) -> TableEvent {
+        TableEvent::RetypeColumn(RetypeColumn {
+            table: Felt::ONE,
+            id,
+            attributes: vec![],
+            type_def,
+        })
+    }
+
+    #[test]
+    fn accepts_promotions_and_added_columns() {
+        let table = table();
+        let add = TableEvent::AddColumn(AddColumn {
+            table: Felt::ONE,
+            id: Felt::TWO,
+            name: "gold".to_string(),
+            attributes: vec![],
+            type_def: TypeDef::U256,
+        });
+        assert_eq!(check_avro_evolution(&table, &add), Ok(()));
+        for type_def in [
+            TypeDef::I32,
+            TypeDef::I64,
+            OptionDef::new_type_def(TypeDef::U32),
+        ] {
+            assert_eq!(
+                check_avro_evolution(&table, &retype(Felt::ONE, type_def)),
+                Ok(())
+            );
+        }
+        let primary = TableEvent::RetypePrimary(RetypePrimary {
+            table: Felt::ONE,
+            attributes: vec![],
+            type_def: PrimaryTypeDef::I64,
+        });
+        assert_eq!(check_avro_evolution(&table, &primary), Ok(()));
+    }
+
+    #[test]
+    fn rejects_narrowing_and_renames() {
+        let table = table();
+        assert_eq!(
+            check_avro_evolution(&table, &retype(Felt::ONE, TypeDef::Bool)),
+            Err(AvroEvolutionError::IncompatibleRetype("score".to_string()))
+        );
+        assert_eq!(
+            check_avro_evolution(&table, &retype(Felt::TWO, TypeDef::U8)),
+            Err(AvroEvolutionError::UnknownColumn {
+                table: Felt::ONE,
+                column: Felt::TWO,
+            })
+        );
+        let rename = TableEvent::RenameColumn(RenameColumn {
+            table: Felt::ONE,
+            id: Felt::ONE,
+            name: "points".to_string(),
+        });
+        assert_eq!(
+            check_avro_evolution(&table, &rename),
+            Err(AvroEvolutionError::Rename("score".to_string()))
+        );
+    }
+}
@@ -1,318 +1,367 @@
-use introspect_types::deserialize_def::TypeDefDeserializer;
 use introspect_types::schema::{PrimaryDef, PrimaryTypeDef};
 use introspect_types::{
-    Attribute, CairoDeserialize, CairoDeserializer, ColumnDef, DecodeResult, FeltId, TypeDef,
+    Attribute, CairoDeserialize, CairoEvent, ColumnDef, FeltId, TableSchema, TypeDef,
 };
 use starknet_types_core::felt::Felt;
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct CreateColumnSet {
     pub id: Felt,
+    #[remaining]
     pub columns: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct CreateTable {
     pub id: Felt,
     pub name: String,
     pub attributes: Vec<Attribute>,
     pub primary: PrimaryDef,
+    #[remaining]
     pub columns: Vec<ColumnDef>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+impl From<CreateTable> for TableSchema {
+    fn from(value: CreateTable) -> Self {
+        TableSchema {
+            id: value.id,
+            name: value.name,
+            attributes: value.attributes,
+            primary: value.primary,
+            columns: value.columns,
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct CreateTableFromClass {
     pub id: Felt,
     pub name: String,
     pub class_hash: Felt,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct CreateTableFromContract {
     pub id: Felt,
     pub name: String,
     pub contract_address: Felt,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct RenameTable {
     pub id: Felt,
     pub name: String,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DropTable {
     pub id: Felt,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct RenamePrimary {
     pub table: Felt,
     pub name: String,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct RetypePrimary {
     pub table: Felt,
-    pub attributes: Vec<Attribute>,
     pub type_def: PrimaryTypeDef,
+    #[remaining]
+    pub attributes: Vec<Attribute>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct AddColumn {
     pub table: Felt,
     pub id: Felt,
     pub name: String,
-    pub attributes: Vec<Attribute>,
     pub type_def: TypeDef,
+    #[remaining]
+    pub attributes: Vec<Attribute>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct AddColumns {
     pub table: Felt,
+    #[remaining]
     pub columns: Vec<ColumnDef>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct RenameColumn {
     pub table: Felt,
     pub id: Felt,
     pub name: String,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct RenameColumns {
     pub table: Felt,
+    #[remaining]
     pub columns: Vec<IdName>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct RetypeColumn {
     pub table: Felt,
     pub id: Felt,
-    pub attributes: Vec<Attribute>,
     pub type_def: TypeDef,
+    #[remaining]
+    pub attributes: Vec<Attribute>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct RetypeColumns {
     pub table: Felt,
+    #[remaining]
     pub columns: Vec<IdTypeDef>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DropColumn {
     pub table: Felt,
     pub id: Felt,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DropColumns {
     pub table: Felt,
+    #[remaining]
     pub ids: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+/// Schema change of a single table.
+#[derive(Clone, Debug)]
+pub enum TableEvent {
+    Create(CreateTable),
+    Rename(RenameTable),
+    Drop(DropTable),
+    RenamePrimary(RenamePrimary),
+    RetypePrimary(RetypePrimary),
+    AddColumn(AddColumn),
+    AddColumns(AddColumns),
+    RenameColumn(RenameColumn),
+    RenameColumns(RenameColumns),
+    RetypeColumn(RetypeColumn),
+    RetypeColumns(RetypeColumns),
+    DropColumn(DropColumn),
+    DropColumns(DropColumns),
+}
+
+impl TableEvent {
+    pub fn table(&self) -> Felt {
+        match self {
+            TableEvent::Create(event) => event.id,
+            TableEvent::Rename(event) => event.id,
+            TableEvent::Drop(event) => event.id,
+            TableEvent::RenamePrimary(event) => event.table,
+            TableEvent::RetypePrimary(event) => event.table,
+            TableEvent::AddColumn(event) => event.table,
+            TableEvent::AddColumns(event) => event.table,
+            TableEvent::RenameColumn(event) => event.table,
+            TableEvent::RenameColumns(event) => event.table,
+            TableEvent::RetypeColumn(event) => event.table,
+            TableEvent::RetypeColumns(event) => event.table,
+            TableEvent::DropColumn(event) => event.table,
+            TableEvent::DropColumns(event) => event.table,
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct CreateIndex {
     pub table: Felt,
     pub id: Felt,
     pub attributes: Vec<Attribute>,
+    #[remaining]
     pub columns: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DropIndex {
     pub table: Felt,
     pub id: Felt,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertRecord {
     pub table: Felt,
     pub row: Felt,
+    #[remaining]
     pub data: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertRecords {
     pub table: Felt,
+    #[remaining]
     pub entries: Vec<Entry>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertField {
     pub table: Felt,
     pub row: Felt,
     pub column: Felt,
+    #[remaining]
     pub data: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertFields {
     pub table: Felt,
     pub row: Felt,
     pub columns: Vec<Felt>,
+    #[remaining]
     pub data: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertsField {
     pub table: Felt,
     pub column: Felt,
+    #[remaining]
     pub entries: Vec<Entry>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertsFields {
     pub table: Felt,
     pub columns: Vec<Felt>,
+    #[remaining]
     pub entries: Vec<Entry>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertFieldSet {
     pub table: Felt,
     pub row: Felt,
     pub set: Felt,
+    #[remaining]
     pub data: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertFieldSets {
     pub table: Felt,
     pub row: Felt,
     pub sets: Vec<Felt>,
+    #[remaining]
     pub data: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertsFieldSet {
     pub table: Felt,
     pub set: Felt,
+    #[remaining]
     pub entries: Vec<Entry>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct InsertsFieldSets {
     pub table: Felt,
     pub sets: Vec<Felt>,
+    #[remaining]
     pub entries: Vec<Entry>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeleteRecord {
     pub table: Felt,
     pub row: Felt,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeleteRecords {
     pub table: Felt,
+    #[remaining]
     pub rows: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeleteField {
     pub table: Felt,
     pub row: Felt,
     pub column: Felt,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeleteFields {
     pub table: Felt,
     pub row: Felt,
+    #[remaining]
     pub columns: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeletesField {
     pub table: Felt,
-    pub rows: Vec<Felt>,
     pub column: Felt,
+    #[remaining]
+    pub rows: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeletesFields {
     pub table: Felt,
     pub rows: Vec<Felt>,
+    #[remaining]
     pub columns: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeleteFieldSet {
     pub table: Felt,
     pub row: Felt,
     pub set: Felt,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeleteFieldSets {
     pub table: Felt,
     pub row: Felt,
+    #[remaining]
     pub sets: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeletesFieldSet {
     pub table: Felt,
-    pub rows: Vec<Felt>,
     pub set: Felt,
+    #[remaining]
+    pub rows: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeletesFieldSets {
     pub table: Felt,
     pub rows: Vec<Felt>,
+    #[remaining]
     pub sets: Vec<Felt>,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoDeserialize)]
 pub struct IdName {
     pub id: Felt,
     pub name: String,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoDeserialize)]
 pub struct IdTypeDef {
     pub id: Felt,
     pub attributes: Vec<Attribute>,
     pub type_def: TypeDef,
 }
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoDeserialize)]
 pub struct Entry {
     pub row: Felt,
     pub data: Vec<Felt>,
 }
 
-impl<D: CairoDeserializer> CairoDeserialize<D> for IdName {
-    fn deserialize(deserializer: &mut D) -> DecodeResult<Self> {
-        let id = deserializer.next_felt()?;
-        let name = deserializer.next_string()?;
-        Ok(IdName { id, name })
-    }
-}
-
-impl<D: TypeDefDeserializer> CairoDeserialize<D> for IdTypeDef {
-    fn deserialize(deserializer: &mut D) -> DecodeResult<Self> {
-        Ok(IdTypeDef {
-            id: deserializer.next_felt()?,
-            attributes: Vec::<Attribute>::deserialize(deserializer)?,
-            type_def: TypeDef::deserialize(deserializer)?,
-        })
-    }
-}
-
-impl<D: CairoDeserializer> CairoDeserialize<D> for Entry {
-    fn deserialize(deserializer: &mut D) -> DecodeResult<Self> {
-        Ok(Entry {
-            row: deserializer.next_felt()?,
-            data: deserializer.next_array()?,
-        })
-    }
-}
-
 impl FeltId for IdName {
     fn id(&self) -> Felt {
         self.id.clone()
@@ -330,3 +379,123 @@ impl FeltId for Entry {
         self.row.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use introspect_types::{
+        CairoDeserializer, CairoEventInfo, CairoISerde, CairoSerde, DecodeError, IndexedEnums,
+        IntoFeltSource,
+    };
+    use starknet::core::utils::get_selector_from_name;
+
+    #[derive(Debug, PartialEq, CairoDeserialize)]
+    enum Direction {
+        Up,
+        Jump(u8),
+        #[selector("0x2a")]
+        Leap(u8),
+        Slide {
+            speed: u16,
+        },
+    }
+
+    #[derive(Debug, PartialEq, CairoEvent)]
+    struct Moved {
+        #[key]
+        player: Felt,
+        x: u32,
+        #[byte_array]
+        label: String,
+        #[remaining]
+        directions: Vec<Direction>,
+    }
+
+    #[test]
+    fn reads_remaining_with_serde_and_iserde() {
+        let felts = [
+            Felt::ONE,
+            Felt::TWO,
+            Felt::THREE,
+            Felt::from(4),
+            Felt::from(5),
+        ];
+        let mut keys = [].into_source();
+        let event = DeletesField::deserialize_and_verify_event(
+            &mut keys,
+            &mut CairoISerde::from(&felts[..]),
+        )
+        .unwrap();
+        assert_eq!((event.table, event.column), (Felt::ONE, Felt::TWO));
+        assert_eq!(event.rows, vec![Felt::THREE, Felt::from(4), Felt::from(5)]);
+
+        let felts = [Felt::ONE, Felt::TWO, Felt::TWO, Felt::THREE, Felt::from(4)];
+        let event = DeletesField::deserialize_and_verify_event(
+            &mut keys,
+            &mut CairoSerde::from(&felts[..]),
+        )
+        .unwrap();
+        assert_eq!(event.rows, vec![Felt::THREE, Felt::from(4)]);
+        assert_eq!(
+            DeletesField::SELECTOR,
+            get_selector_from_name("DeletesField").unwrap()
+        );
+    }
+
+    #[test]
+    fn derives_keys_byte_arrays_and_enums() {
+        let key = [Felt::from(0xabc)];
+        let data = [
+            Felt::from(7),
+            Felt::ZERO,
+            Felt::from_bytes_be_slice(b"go"),
+            Felt::TWO,
+            Felt::THREE,
+            get_selector_from_name("Jump").unwrap(),
+            Felt::from(3),
+            Felt::from(0x2a),
+            Felt::from(4),
+            get_selector_from_name("Slide").unwrap(),
+            Felt::from(9),
+        ];
+        let event = Moved::deserialize_and_verify_event(
+            &mut key[..].into_source(),
+            &mut CairoSerde::from(&data[..]),
+        )
+        .unwrap();
+        assert_eq!(
+            event,
+            Moved {
+                player: Felt::from(0xabc),
+                x: 7,
+                label: "go".to_string(),
+                directions: vec![
+                    Direction::Jump(3),
+                    Direction::Leap(4),
+                    Direction::Slide { speed: 9 },
+                ],
+            }
+        );
+        assert_eq!(Moved::NAME, "Moved");
+        let event = Moved::deserialize_and_verify_event(
+            &mut key[..].into_source(),
+            &mut CairoSerde::from(&[&data[..], &[Felt::ONE]].concat()),
+        );
+        assert!(matches!(event, Err(DecodeError::NotEof)));
+
+        let mut deserializer = CairoSerde::from(&[Felt::ZERO, Felt::TWO][..]);
+        assert!(matches!(
+            deserializer.next_value::<Direction>(),
+            Err(DecodeError::InvalidEnumSelector { .. })
+        ));
+        let mut deserializer = IndexedEnums(CairoSerde::from(&[Felt::TWO, Felt::from(5)][..]));
+        assert_eq!(
+            deserializer.next_value::<Direction>().unwrap(),
+            Direction::Leap(5)
+        );
+        assert!(matches!(
+            IndexedEnums(CairoSerde::from(&[Felt::from(4)][..])).next_value::<Direction>(),
+            Err(DecodeError::EnumIndexOutOfRange { len: 4, .. })
+        ));
+    }
+}
@@ -0,0 +1,405 @@
+use crate::database::TableEvent;
+use crate::types::DeclareType;
+use introspect_types::{
+    ColumnDef, GraphQLError, GraphQLGenerator, GraphQLSchema, TableSchema, TypeDef,
+};
+use starknet_types_core::felt::Felt;
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GraphQLCatalogError {
+    #[error(transparent)]
+    GraphQL(#[from] GraphQLError),
+
+    #[error("unknown table: {0:#x}")]
+    UnknownTable(Felt),
+
+    #[error("table already exists: {0:#x}")]
+    TableExists(Felt),
+
+    #[error("unknown column {column:#x} in table {table:#x}")]
+    UnknownColumn { table: Felt, column: Felt },
+
+    #[error("column {column:#x} already exists in table {table:#x}")]
+    ColumnExists { table: Felt, column: Felt },
+}
+
+pub type GraphQLCatalogResult<T> = Result<T, GraphQLCatalogError>;
+
+/// Keeps the GraphQL SDL of the indexed tables in sync with the schema events.
+///
+/// The types of each table are cached and only the table an event targets is
+/// regenerated; `sdl` merges the cached per-table schemas. A table whose new
+/// schema fails to generate, or defines a type another table defines
+/// differently, is left untouched.
+#[derive(Default)]
+pub struct GraphQLCatalog {
+    tables: BTreeMap<Felt, TableSchema>,
+    schemas: BTreeMap<Felt, GraphQLSchema>,
+    types: HashMap<Felt, TypeDef>,
+}
+
+impl GraphQLCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn table(&self, id: &Felt) -> Option<&TableSchema> {
+        self.tables.get(id)
+    }
+
+    pub fn table_schema(&self, id: &Felt) -> Option<&GraphQLSchema> {
+        self.schemas.get(id)
+    }
+
+    /// Registers a type for `Ref` resolution and regenerates every table. A
+    /// type that fails to regenerate them is not kept, any previous one is.
+    pub fn declare_type(&mut self, event: DeclareType) -> GraphQLCatalogResult<()> {
+        let previous = self.types.insert(event.id, event.type_def);
+        let schemas = self
+            .tables
+            .iter()
+            .map(|(id, table)| Ok((*id, self.generate(table)?)))
+            .collect::<GraphQLCatalogResult<BTreeMap<_, _>>>()
+            .and_then(|schemas| merge(schemas.values()).map(|_| schemas));
+        match schemas {
+            Ok(schemas) => {
+                self.schemas = schemas;
+                Ok(())
+            }
+            Err(err) => {
+                match previous {
+                    Some(previous) => self.types.insert(event.id, previous),
+                    None => self.types.remove(&event.id),
+                };
+                Err(err)
+            }
+        }
+    }
+
+    pub fn apply(&mut self, event: TableEvent) -> GraphQLCatalogResult<()> {
+        let id = event.table();
+        if let TableEvent::Drop(_) = event {
+            self.tables
+                .remove(&id)
+                .ok_or(GraphQLCatalogError::UnknownTable(id))?;
+            self.schemas.remove(&id);
+            return Ok(());
+        }
+        let table = match event {
+            TableEvent::Create(event) if self.tables.contains_key(&event.id) => {
+                return Err(GraphQLCatalogError::TableExists(event.id));
+            }
+            TableEvent::Create(event) => event.into(),
+            event => {
+                let mut table = self
+                    .tables
+                    .get(&id)
+                    .cloned()
+                    .ok_or(GraphQLCatalogError::UnknownTable(id))?;
+                update_table(&mut table, event)?;
+                table
+            }
+        };
+        let schema = self.generate(&table)?;
+        let others = self.schemas.iter().filter(|(other, _)| **other != id);
+        merge(others.map(|(_, schema)| schema).chain([&schema]))?;
+        self.schemas.insert(id, schema);
+        self.tables.insert(id, table);
+        Ok(())
+    }
+
+    /// SDL of all the tables.
+    pub fn sdl(&self) -> GraphQLCatalogResult<String> {
+        merge(self.schemas.values()).map(|merged| merged.to_string())
+    }
+
+    fn generate(&self, table: &TableSchema) -> GraphQLCatalogResult<GraphQLSchema> {
+        let mut generator = GraphQLGenerator::with_refs(&self.types);
+        generator.table(table)?;
+        Ok(generator.finish())
+    }
+}
+
+fn merge<'a>(
+    schemas: impl IntoIterator<Item = &'a GraphQLSchema>,
+) -> GraphQLCatalogResult<GraphQLSchema> {
+    let mut merged = GraphQLSchema::default();
+    for schema in schemas {
+        merged.merge(schema)?;
+    }
+    Ok(merged)
+}
+
+fn update_table(table: &mut TableSchema, event: TableEvent) -> GraphQLCatalogResult<()> {
+    match event {
+        TableEvent::Create(_) | TableEvent::Drop(_) => {}
+        TableEvent::Rename(event) => table.name = event.name,
+        TableEvent::RenamePrimary(event) => table.primary.name = event.name,
+        TableEvent::RetypePrimary(event) => {
+            table.primary.attributes = event.attributes;
+            table.primary.type_def = event.type_def;
+        }
+        TableEvent::AddColumn(event) => add_column(
+            table,
+            ColumnDef::new(event.id, event.name, event.attributes, event.type_def),
+        )?,
+        TableEvent::AddColumns(event) => {
+            for column in event.columns {
+                add_column(table, column)?;
+            }
+        }
+        TableEvent::RenameColumn(event) => column_mut(table, event.id)?.name = event.name,
+        TableEvent::RenameColumns(event) => {
+            for column in event.columns {
+                column_mut(table, column.id)?.name = column.name;
+            }
+        }
+        TableEvent::RetypeColumn(event) => {
+            let column = column_mut(table, event.id)?;
+            column.attributes = event.attributes;
+            column.type_def = event.type_def;
+        }
+        TableEvent::RetypeColumns(event) => {
+            for retype in event.columns {
+                let column = column_mut(table, retype.id)?;
+                column.attributes = retype.attributes;
+                column.type_def = retype.type_def;
+            }
+        }
+        TableEvent::DropColumn(event) => drop_column(table, event.id)?,
+        TableEvent::DropColumns(event) => {
+            for id in event.ids {
+                drop_column(table, id)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn add_column(table: &mut TableSchema, column: ColumnDef) -> GraphQLCatalogResult<()> {
+    if table
+        .columns
+        .iter()
+        .any(|existing| existing.id == column.id)
+    {
+        return Err(GraphQLCatalogError::ColumnExists {
+            table: table.id,
+            column: column.id,
+        });
+    }
+    table.columns.push(column);
+    Ok(())
+}
+
+fn column_mut(table: &mut TableSchema, id: Felt) -> GraphQLCatalogResult<&mut ColumnDef> {
+    let table_id = table.id;
+    table
+        .columns
+        .iter_mut()
+        .find(|column| column.id == id)
+        .ok_or(GraphQLCatalogError::UnknownColumn {
+            table: table_id,
+            column: id,
+        })
+}
+
+fn drop_column(table: &mut TableSchema, id: Felt) -> GraphQLCatalogResult<()> {
+    let index = table
+        .columns
+        .iter()
+        .position(|column| column.id == id)
+        .ok_or(GraphQLCatalogError::UnknownColumn {
+            table: table.id,
+            column: id,
+        })?;
+    table.columns.remove(index);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{AddColumn, AddColumns, CreateTable, DropColumn, DropTable, RenameTable};
+    use introspect_types::{MemberDef, PrimaryDef, PrimaryTypeDef, RefDef, StructDef};
+
+    fn create(id: u64, name: &str) -> TableEvent {
+        TableEvent::Create(CreateTable {
+            id: Felt::from(id),
+            name: name.to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("id".to_string(), vec![], PrimaryTypeDef::Felt252),
+            columns: vec![ColumnDef::new(
+                Felt::ONE,
+                "score".to_string(),
+                vec![],
+                TypeDef::U32,
+            )],
+        })
+    }
+
+    fn add_column(table: u64, name: &str, type_def: TypeDef) -> TableEvent {
+        TableEvent::AddColumn(AddColumn {
+            table: Felt::from(table),
+            id: Felt::TWO,
+            name: name.to_string(),
+            attributes: vec![],
+            type_def,
+        })
+    }
+
+    #[test]
+    fn regenerates_tables_on_column_events() {
+        let mut catalog = GraphQLCatalog::new();
+        catalog.apply(create(1, "Player")).unwrap();
+        catalog.apply(create(2, "Game")).unwrap();
+        let game = catalog.table_schema(&Felt::TWO).cloned();
+
+        catalog.apply(add_column(1, "gold", TypeDef::U256)).unwrap();
+        let sdl = catalog.sdl().unwrap();
+        assert!(sdl.contains("type Player {\n  id: Felt!\n  score: U32!\n  gold: U256!\n}"));
+        assert!(sdl.contains("gameConnection(first: Int, after: Felt): GameConnection!"));
+        assert_eq!(catalog.table_schema(&Felt::TWO).cloned(), game);
+
+        catalog
+            .apply(TableEvent::DropColumn(DropColumn {
+                table: Felt::ONE,
+                id: Felt::ONE,
+            }))
+            .unwrap();
+        assert!(
+            catalog
+                .sdl()
+                .unwrap()
+                .contains("type Player {\n  id: Felt!\n  gold: U256!\n}")
+        );
+
+        catalog
+            .apply(TableEvent::Drop(DropTable { id: Felt::TWO }))
+            .unwrap();
+        assert!(!catalog.sdl().unwrap().contains("Game"));
+        assert!(matches!(
+            catalog.apply(create(1, "Player")),
+            Err(GraphQLCatalogError::TableExists(_))
+        ));
+    }
+
+    #[test]
+    fn keeps_tables_unchanged_on_failure() {
+        let mut catalog = GraphQLCatalog::new();
+        catalog.apply(create(1, "Player")).unwrap();
+        let reference = add_column(1, "pet", RefDef::new_type_def(Felt::from(9)));
+        assert!(matches!(
+            catalog.apply(reference.clone()),
+            Err(GraphQLCatalogError::GraphQL(GraphQLError::UnknownRef(_)))
+        ));
+        assert_eq!(catalog.table(&Felt::ONE).unwrap().columns.len(), 1);
+        assert!(matches!(
+            catalog.apply(TableEvent::DropColumn(DropColumn {
+                table: Felt::ONE,
+                id: Felt::from(5),
+            })),
+            Err(GraphQLCatalogError::UnknownColumn { .. })
+        ));
+
+        catalog
+            .declare_type(DeclareType {
+                id: Felt::from(9),
+                type_def: TypeDef::Bool,
+            })
+            .unwrap();
+        catalog.apply(reference).unwrap();
+        assert!(catalog.sdl().unwrap().contains("pet: Boolean!"));
+
+        assert!(matches!(
+            catalog.declare_type(DeclareType {
+                id: Felt::from(9),
+                type_def: RefDef::new_type_def(Felt::from(10)),
+            }),
+            Err(GraphQLCatalogError::GraphQL(GraphQLError::UnknownRef(_)))
+        ));
+        assert!(catalog.sdl().unwrap().contains("pet: Boolean!"));
+        catalog
+            .declare_type(DeclareType {
+                id: Felt::from(11),
+                type_def: TypeDef::U8,
+            })
+            .unwrap();
+        assert!(catalog.sdl().unwrap().contains("pet: Boolean!"));
+    }
+
+    #[test]
+    fn rejects_duplicate_columns() {
+        let mut catalog = GraphQLCatalog::new();
+        catalog.apply(create(1, "Player")).unwrap();
+        catalog.apply(add_column(1, "gold", TypeDef::U256)).unwrap();
+        assert!(matches!(
+            catalog.apply(add_column(1, "silver", TypeDef::U64)),
+            Err(GraphQLCatalogError::ColumnExists { .. })
+        ));
+        let columns = |ids: [u64; 2]| {
+            TableEvent::AddColumns(AddColumns {
+                table: Felt::ONE,
+                columns: ids
+                    .map(|id| ColumnDef::new(Felt::from(id), format!("c{id}"), vec![], TypeDef::U8))
+                    .to_vec(),
+            })
+        };
+        assert!(matches!(
+            catalog.apply(columns([3, 3])),
+            Err(GraphQLCatalogError::ColumnExists { .. })
+        ));
+        assert!(matches!(
+            catalog.apply(columns([3, 1])),
+            Err(GraphQLCatalogError::ColumnExists { .. })
+        ));
+        assert_eq!(catalog.table(&Felt::ONE).unwrap().columns.len(), 2);
+        catalog.apply(columns([3, 4])).unwrap();
+        assert_eq!(catalog.table(&Felt::ONE).unwrap().columns.len(), 4);
+    }
+
+    #[test]
+    fn rejects_types_conflicting_with_other_tables() {
+        let position = |name: &str, x: TypeDef| {
+            StructDef::new_type_def(
+                name.to_string(),
+                vec![],
+                vec![MemberDef::new("x".to_string(), vec![], x)],
+            )
+        };
+        let mut catalog = GraphQLCatalog::new();
+        catalog.apply(create(1, "Player")).unwrap();
+        catalog.apply(create(2, "Game")).unwrap();
+        catalog
+            .apply(add_column(
+                1,
+                "position",
+                position("a::Position", TypeDef::U32),
+            ))
+            .unwrap();
+        assert!(matches!(
+            catalog.apply(add_column(
+                2,
+                "position",
+                position("b::Position", TypeDef::U64)
+            )),
+            Err(GraphQLCatalogError::GraphQL(GraphQLError::ConflictingType(
+                _
+            )))
+        ));
+        assert_eq!(catalog.table(&Felt::TWO).unwrap().columns.len(), 1);
+
+        assert!(matches!(
+            catalog.apply(TableEvent::Rename(RenameTable {
+                id: Felt::TWO,
+                name: "Player".to_string(),
+            })),
+            Err(GraphQLCatalogError::GraphQL(GraphQLError::ConflictingType(
+                _
+            )))
+        ));
+        assert_eq!(catalog.table(&Felt::TWO).unwrap().name, "Game");
+        assert!(catalog.sdl().unwrap().contains("type Game {"));
+    }
+}
@@ -1,7 +1,12 @@
 pub mod database {
-    pub mod deserialize;
     pub mod events;
     pub use events::*;
 }
+pub mod avro;
 pub mod event;
+pub mod graphql;
+pub mod multipart;
+pub mod registry;
+pub mod resolver;
 pub mod types;
+pub mod variable;
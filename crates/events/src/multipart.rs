@@ -0,0 +1,190 @@
+use introspect_types::{
+    CairoEvent, CairoEventInfo, CairoSerde, DecodeError, EVENT_DATA_FELT_LIMIT, IntoFeltSource,
+};
+use starknet_types_core::felt::Felt;
+use thiserror::Error;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
+pub struct MultiPartEventBegin {
+    pub event_selector: Felt,
+    #[drain]
+    pub data: Vec<Felt>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
+pub struct MultiPartEvent {
+    #[drain]
+    pub data: Vec<Felt>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
+pub struct MultiPartEventEnd {
+    #[drain]
+    pub data: Vec<Felt>,
+}
+
+/// Keys and data of an event as emitted, the first key being its selector.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RawEvent {
+    pub keys: Vec<Felt>,
+    pub data: Vec<Felt>,
+}
+
+impl RawEvent {
+    pub fn new(selector: Felt, data: Vec<Felt>) -> Self {
+        RawEvent {
+            keys: vec![selector],
+            data,
+        }
+    }
+
+    pub fn selector(&self) -> Option<Felt> {
+        self.keys.first().copied()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum MultiPartError {
+    #[error(transparent)]
+    Decode(#[from] DecodeError),
+
+    #[error("multipart event part received before MultiPartEventBegin")]
+    NotStarted,
+
+    #[error("MultiPartEventBegin received while {0:#x} is still being assembled")]
+    AlreadyStarted(Felt),
+}
+
+pub type MultiPartResult<T> = Result<T, MultiPartError>;
+
+/// Encodes an event with a single key, its selector, splitting the data into a
+/// `MultiPartEventBegin`, `MultiPartEvent`s and a `MultiPartEventEnd` when it is
+/// over `EVENT_DATA_FELT_LIMIT`.
+pub fn encode_event(selector: Felt, data: Vec<Felt>) -> Vec<RawEvent> {
+    if data.len() <= EVENT_DATA_FELT_LIMIT {
+        return vec![RawEvent::new(selector, data)];
+    }
+    let (first, rest) = data.split_at(EVENT_DATA_FELT_LIMIT - 1);
+    let mut events = vec![RawEvent::new(
+        MultiPartEventBegin::SELECTOR,
+        [&[selector], first].concat(),
+    )];
+    let mut chunks = rest.chunks(EVENT_DATA_FELT_LIMIT).peekable();
+    while let Some(chunk) = chunks.next() {
+        let selector = match chunks.peek() {
+            Some(_) => MultiPartEvent::SELECTOR,
+            None => MultiPartEventEnd::SELECTOR,
+        };
+        events.push(RawEvent::new(selector, chunk.to_vec()));
+    }
+    events
+}
+
+/// Joins multipart sequences back into the event they were split from, passing
+/// every other event through. Events must be pushed in emission order and from
+/// a single emitter.
+#[derive(Clone, Debug, Default)]
+pub struct MultiPartAssembler {
+    pending: Option<RawEvent>,
+}
+
+impl MultiPartAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selector of the event being assembled, if any.
+    pub fn pending(&self) -> Option<Felt> {
+        self.pending.as_ref().and_then(RawEvent::selector)
+    }
+
+    /// Returns the complete event once `event` ends it, or straight away when it
+    /// is not part of a multipart sequence.
+    pub fn push(&mut self, event: RawEvent) -> MultiPartResult<Option<RawEvent>> {
+        let selector = match event.selector() {
+            Some(selector) if is_multipart_selector(selector) => selector,
+            _ => return Ok(Some(event)),
+        };
+        let mut keys = event.keys[1..].into_source();
+        let mut data = CairoSerde(event.data.into_source());
+        if selector == MultiPartEventBegin::SELECTOR {
+            if let Some(selector) = self.pending() {
+                return Err(MultiPartError::AlreadyStarted(selector));
+            }
+            let begin = MultiPartEventBegin::deserialize_and_verify_event(&mut keys, &mut data)?;
+            self.pending = Some(RawEvent::new(begin.event_selector, begin.data));
+            Ok(None)
+        } else if selector == MultiPartEvent::SELECTOR {
+            let part = MultiPartEvent::deserialize_and_verify_event(&mut keys, &mut data)?;
+            let pending = self.pending.as_mut().ok_or(MultiPartError::NotStarted)?;
+            pending.data.extend(part.data);
+            Ok(None)
+        } else {
+            let end = MultiPartEventEnd::deserialize_and_verify_event(&mut keys, &mut data)?;
+            let mut pending = self.pending.take().ok_or(MultiPartError::NotStarted)?;
+            pending.data.extend(end.data);
+            Ok(Some(pending))
+        }
+    }
+}
+
+fn is_multipart_selector(selector: Felt) -> bool {
+    [
+        MultiPartEventBegin::SELECTOR,
+        MultiPartEvent::SELECTOR,
+        MultiPartEventEnd::SELECTOR,
+    ]
+    .contains(&selector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reassemble(events: Vec<RawEvent>) -> Vec<RawEvent> {
+        let mut assembler = MultiPartAssembler::new();
+        let events = events
+            .into_iter()
+            .filter_map(|event| assembler.push(event).unwrap())
+            .collect();
+        assert_eq!(assembler.pending(), None);
+        events
+    }
+
+    #[test]
+    fn splits_and_reassembles_large_events() {
+        let selector = Felt::from(42);
+        for len in [0usize, 300, 301, 599, 600, 1000] {
+            let data: Vec<Felt> = (0..len as u64).map(Felt::from).collect();
+            let events = encode_event(selector, data.clone());
+            assert!(events.iter().all(|e| e.data.len() <= EVENT_DATA_FELT_LIMIT));
+            let parts = match len {
+                0..=300 => 1,
+                _ => 1 + (len - 299).div_ceil(300),
+            };
+            assert_eq!(events.len(), parts);
+            assert_eq!(reassemble(events), vec![RawEvent::new(selector, data)]);
+        }
+        let events = encode_event(selector, vec![Felt::ONE; 700]);
+        assert_eq!(events[0].selector(), Some(MultiPartEventBegin::SELECTOR));
+        assert_eq!(events[0].data[0], selector);
+        assert_eq!(events[1].selector(), Some(MultiPartEvent::SELECTOR));
+        assert_eq!(events[2].selector(), Some(MultiPartEventEnd::SELECTOR));
+    }
+
+    #[test]
+    fn rejects_out_of_order_parts() {
+        let mut assembler = MultiPartAssembler::new();
+        let end = RawEvent::new(MultiPartEventEnd::SELECTOR, vec![Felt::ONE]);
+        assert!(matches!(
+            assembler.push(end),
+            Err(MultiPartError::NotStarted)
+        ));
+        let events = encode_event(Felt::TWO, vec![Felt::ONE; 400]);
+        assembler.push(events[0].clone()).unwrap();
+        assert!(matches!(
+            assembler.push(events[0].clone()),
+            Err(MultiPartError::AlreadyStarted(_))
+        ));
+    }
+}
@@ -0,0 +1,348 @@
+use crate::variable::{
+    DeclareVariable, DeleteVariable, RegisterVariable, RenameVariable, SetVariable,
+};
+use introspect_types::parser::TypeParserError;
+use introspect_types::{CairoISerde, DecodeError, FeltSource, ParseValue, TypeDef, Value};
+use starknet_types_core::felt::Felt;
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum VariableRegistryError {
+    #[error(transparent)]
+    Parse(#[from] TypeParserError),
+
+    #[error("unknown variable: {0:#x}")]
+    UnknownVariable(Felt),
+
+    #[error("variable already registered: {0:#x}")]
+    AlreadyRegistered(Felt),
+}
+
+pub type VariableRegistryResult<T> = Result<T, VariableRegistryError>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variable {
+    pub id: Felt,
+    pub name: String,
+    pub type_def: TypeDef,
+    pub value: Option<Value>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum VariableChange {
+    Registered {
+        id: Felt,
+        name: String,
+        type_def: TypeDef,
+    },
+    Set {
+        id: Felt,
+        value: Value,
+    },
+    Renamed {
+        id: Felt,
+        name: String,
+    },
+    Deleted {
+        id: Felt,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub enum VariableEvent {
+    Register(RegisterVariable),
+    Declare(DeclareVariable),
+    Set(SetVariable),
+    Rename(RenameVariable),
+    Delete(DeleteVariable),
+}
+
+type Listener = Box<dyn FnMut(u64, &VariableChange) + Send>;
+
+/// Tracks variables declared through variable events, decoding their ISerde
+/// encoded values against the registered `TypeDef`.
+///
+/// Every applied change is sent to the subscribed listeners and recorded per
+/// block. When a retention is set, only the changes of the last `retention`
+/// blocks are kept.
+#[derive(Default)]
+pub struct VariableRegistry {
+    variables: HashMap<Felt, Variable>,
+    history: BTreeMap<u64, Vec<VariableChange>>,
+    retention: Option<u64>,
+    listeners: Vec<Listener>,
+}
+
+impl VariableChange {
+    pub fn id(&self) -> Felt {
+        match self {
+            VariableChange::Registered { id, .. }
+            | VariableChange::Set { id, .. }
+            | VariableChange::Renamed { id, .. }
+            | VariableChange::Deleted { id } => *id,
+        }
+    }
+}
+
+impl VariableRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_retention(retention: u64) -> Self {
+        VariableRegistry {
+            retention: Some(retention),
+            ..Default::default()
+        }
+    }
+
+    pub fn subscribe<F: FnMut(u64, &VariableChange) + Send + 'static>(&mut self, listener: F) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    pub fn get(&self, id: &Felt) -> Option<&Variable> {
+        self.variables.get(id)
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&Variable> {
+        self.variables.values().find(|v| v.name == name)
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = &Variable> {
+        self.variables.values()
+    }
+
+    pub fn history(&self, block: u64) -> &[VariableChange] {
+        self.history.get(&block).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn history_since(&self, block: u64) -> impl Iterator<Item = (u64, &VariableChange)> {
+        self.history
+            .range(block..)
+            .flat_map(|(block, changes)| changes.iter().map(move |c| (*block, c)))
+    }
+
+    /// Returns the value a variable held at the end of `block`, as far as the
+    /// retained history allows. A variable deleted or registered after its last
+    /// set has no value.
+    pub fn value_at(&self, id: &Felt, block: u64) -> Option<&Value> {
+        self.history
+            .range(..=block)
+            .rev()
+            .flat_map(|(_, changes)| changes.iter().rev())
+            .filter(|change| change.id() == *id)
+            .find_map(|change| match change {
+                VariableChange::Set { value, .. } => Some(Some(value)),
+                VariableChange::Deleted { .. } | VariableChange::Registered { .. } => Some(None),
+                VariableChange::Renamed { .. } => None,
+            })
+            .flatten()
+    }
+
+    pub fn apply(&mut self, block: u64, event: VariableEvent) -> VariableRegistryResult<()> {
+        match event {
+            VariableEvent::Register(event) => self.register(block, event),
+            VariableEvent::Declare(event) => self.declare(block, event),
+            VariableEvent::Set(event) => self.set(block, event),
+            VariableEvent::Rename(event) => self.rename(block, event),
+            VariableEvent::Delete(event) => self.delete(block, event),
+        }
+    }
+
+    pub fn register(&mut self, block: u64, event: RegisterVariable) -> VariableRegistryResult<()> {
+        let RegisterVariable { id, name, type_def } = event;
+        if self.variables.contains_key(&id) {
+            return Err(VariableRegistryError::AlreadyRegistered(id));
+        }
+        self.variables.insert(
+            id,
+            Variable {
+                id,
+                name: name.clone(),
+                type_def: type_def.clone(),
+                value: None,
+            },
+        );
+        self.record(block, VariableChange::Registered { id, name, type_def });
+        Ok(())
+    }
+
+    pub fn declare(&mut self, block: u64, event: DeclareVariable) -> VariableRegistryResult<()> {
+        let DeclareVariable {
+            id,
+            name,
+            type_def,
+            data,
+        } = event;
+        if self.variables.contains_key(&id) {
+            return Err(VariableRegistryError::AlreadyRegistered(id));
+        }
+        let value = decode_value(&type_def, data)?;
+        self.register(block, RegisterVariable { id, name, type_def })?;
+        self.update_value(block, id, value);
+        Ok(())
+    }
+
+    pub fn set(&mut self, block: u64, event: SetVariable) -> VariableRegistryResult<()> {
+        let variable = self.get_variable(event.id)?;
+        let value = decode_value(&variable.type_def, event.data)?;
+        self.update_value(block, event.id, value);
+        Ok(())
+    }
+
+    pub fn rename(&mut self, block: u64, event: RenameVariable) -> VariableRegistryResult<()> {
+        let RenameVariable { id, name } = event;
+        self.get_variable_mut(id)?.name = name.clone();
+        self.record(block, VariableChange::Renamed { id, name });
+        Ok(())
+    }
+
+    pub fn delete(&mut self, block: u64, event: DeleteVariable) -> VariableRegistryResult<()> {
+        let DeleteVariable { id } = event;
+        self.variables
+            .remove(&id)
+            .ok_or(VariableRegistryError::UnknownVariable(id))?;
+        self.record(block, VariableChange::Deleted { id });
+        Ok(())
+    }
+
+    fn get_variable(&self, id: Felt) -> VariableRegistryResult<&Variable> {
+        self.variables
+            .get(&id)
+            .ok_or(VariableRegistryError::UnknownVariable(id))
+    }
+
+    fn get_variable_mut(&mut self, id: Felt) -> VariableRegistryResult<&mut Variable> {
+        self.variables
+            .get_mut(&id)
+            .ok_or(VariableRegistryError::UnknownVariable(id))
+    }
+
+    fn update_value(&mut self, block: u64, id: Felt, value: Value) {
+        if let Some(variable) = self.variables.get_mut(&id) {
+            variable.value = Some(value.clone());
+        }
+        self.record(block, VariableChange::Set { id, value });
+    }
+
+    fn record(&mut self, block: u64, change: VariableChange) {
+        for listener in &mut self.listeners {
+            listener(block, &change);
+        }
+        self.history.entry(block).or_default().push(change);
+        if let Some(retention) = self.retention {
+            let oldest = block.saturating_sub(retention.saturating_sub(1));
+            self.history = self.history.split_off(&oldest);
+        }
+    }
+}
+
+fn decode_value(type_def: &TypeDef, data: Vec<Felt>) -> VariableRegistryResult<Value> {
+    let mut deserializer: CairoISerde<_> = data.into();
+    let value = type_def.parse(&mut deserializer)?;
+    match deserializer.next() {
+        Err(e) if e.is_eof() => Ok(value),
+        Ok(_) => Err(TypeParserError::from(DecodeError::NotEof).into()),
+        Err(e) => Err(TypeParserError::from(e).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn register(id: u64) -> VariableEvent {
+        VariableEvent::Register(RegisterVariable {
+            id: Felt::from(id),
+            name: format!("var_{id}"),
+            type_def: TypeDef::U32,
+        })
+    }
+
+    fn set(id: u64, value: u64) -> VariableEvent {
+        VariableEvent::Set(SetVariable {
+            id: Felt::from(id),
+            data: vec![Felt::from(value)],
+        })
+    }
+
+    #[test]
+    fn applies_variable_events() {
+        let mut registry = VariableRegistry::new();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let seen = changes.clone();
+        registry.subscribe(move |block, change| seen.lock().unwrap().push((block, change.id())));
+
+        registry.apply(1, register(1)).unwrap();
+        registry.apply(2, set(1, 5)).unwrap();
+        registry
+            .apply(
+                3,
+                VariableEvent::Rename(RenameVariable {
+                    id: Felt::ONE,
+                    name: "score".to_string(),
+                }),
+            )
+            .unwrap();
+        let variable = registry.get_by_name("score").unwrap();
+        assert_eq!(variable.value, Some(Value::U32(5)));
+        assert_eq!(registry.value_at(&Felt::ONE, 1), None);
+        assert_eq!(registry.value_at(&Felt::ONE, 3), Some(&Value::U32(5)));
+
+        registry
+            .apply(4, VariableEvent::Delete(DeleteVariable { id: Felt::ONE }))
+            .unwrap();
+        assert!(registry.get(&Felt::ONE).is_none());
+        assert_eq!(changes.lock().unwrap().len(), 4);
+        assert!(matches!(
+            registry.apply(5, set(1, 6)),
+            Err(VariableRegistryError::UnknownVariable(_))
+        ));
+    }
+
+    #[test]
+    fn declares_with_value_and_prunes_history() {
+        let mut registry = VariableRegistry::with_retention(2);
+        registry
+            .apply(
+                1,
+                VariableEvent::Declare(DeclareVariable {
+                    id: Felt::TWO,
+                    name: "flag".to_string(),
+                    type_def: TypeDef::Bool,
+                    data: vec![Felt::ONE],
+                }),
+            )
+            .unwrap();
+        assert_eq!(registry.history(1).len(), 2);
+        registry.apply(2, register(3)).unwrap();
+        registry.apply(3, set(3, 7)).unwrap();
+        assert!(registry.history(1).is_empty());
+        assert_eq!(registry.history_since(0).count(), 2);
+        assert_eq!(
+            registry.get(&Felt::TWO).unwrap().value,
+            Some(Value::Bool(true))
+        );
+        assert!(registry.apply(4, set(3, 1 << 40)).is_err());
+    }
+
+    #[test]
+    fn value_at_stops_at_deletes() {
+        let mut registry = VariableRegistry::new();
+        registry.apply(1, register(1)).unwrap();
+        registry.apply(2, set(1, 5)).unwrap();
+        registry
+            .apply(3, VariableEvent::Delete(DeleteVariable { id: Felt::ONE }))
+            .unwrap();
+        assert_eq!(registry.value_at(&Felt::ONE, 2), Some(&Value::U32(5)));
+        assert_eq!(registry.value_at(&Felt::ONE, 3), None);
+        assert_eq!(registry.value_at(&Felt::ONE, 4), None);
+
+        registry.apply(5, register(1)).unwrap();
+        assert_eq!(registry.value_at(&Felt::ONE, 5), None);
+        registry.apply(6, set(1, 8)).unwrap();
+        assert_eq!(registry.value_at(&Felt::ONE, 6), Some(&Value::U32(8)));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::database::{CreateTable, CreateTableFromClass, CreateTableFromContract};
+use introspect_types::deserialize_def::TypeDefDeserializer;
+use introspect_types::{
+    Attributes, CairoSerde, ColumnDef, DecodeError, FeltSource, KEY_ATTRIBUTE, MemberDef,
+    PrimaryDef, PrimaryTypeDef, StructDef, TypeDef,
+};
+use starknet::core::utils::get_selector_from_name;
+use starknet_types_core::felt::Felt;
+use std::collections::HashMap;
+use std::fmt::Display;
+use thiserror::Error;
+
+pub const DEFAULT_PRIMARY_NAME: &str = "__id";
+
+/// Where the `TypeDef` of a table is declared: the `type_def` entrypoint of
+/// `ITypeDefClassContract`, either on a declared class or on a deployed contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SchemaSource {
+    Class(Felt),
+    Contract(Felt),
+}
+
+#[derive(Debug, Error)]
+pub enum SchemaResolverError {
+    #[error(transparent)]
+    Decode(#[from] DecodeError),
+
+    #[error("provider error: {0}")]
+    Provider(String),
+
+    #[error("table type def must be a struct, got {0}")]
+    NotAStruct(&'static str),
+
+    #[error("invalid primary type for member {name}: {type_name}")]
+    InvalidPrimary {
+        name: String,
+        type_name: &'static str,
+    },
+
+    #[error("invalid member name: {0}")]
+    InvalidMemberName(String),
+}
+
+pub type SchemaResolverResult<T> = Result<T, SchemaResolverError>;
+
+impl SchemaResolverError {
+    #[inline]
+    pub fn provider<E: Display>(e: E) -> Self {
+        Self::Provider(e.to_string())
+    }
+}
+
+/// Supplies the raw felts returned by `ITypeDefClassContract::type_def`.
+///
+/// Implement this on top of an RPC client, or use the `HashMap` implementation
+/// as a cache or mock.
+pub trait TypeDefProvider {
+    type Error: Display;
+    fn type_def_felts(&self, source: &SchemaSource) -> Result<Vec<Felt>, Self::Error>;
+}
+
+impl TypeDefProvider for HashMap<SchemaSource, Vec<Felt>> {
+    type Error = String;
+    fn type_def_felts(&self, source: &SchemaSource) -> Result<Vec<Felt>, Self::Error> {
+        self.get(source)
+            .cloned()
+            .ok_or_else(|| format!("no type def for {source:?}"))
+    }
+}
+
+pub trait SchemaResolver {
+    fn resolve_type_def(&self, source: &SchemaSource) -> SchemaResolverResult<TypeDef>;
+
+    fn resolve_table(
+        &self,
+        id: Felt,
+        name: String,
+        source: &SchemaSource,
+    ) -> SchemaResolverResult<CreateTable> {
+        match self.resolve_type_def(source)? {
+            TypeDef::Struct(s) => struct_to_table(id, name, s),
+            type_def => Err(SchemaResolverError::NotAStruct(type_def.item_name())),
+        }
+    }
+
+    fn resolve_class(&self, event: &CreateTableFromClass) -> SchemaResolverResult<CreateTable> {
+        self.resolve_table(
+            event.id,
+            event.name.clone(),
+            &SchemaSource::Class(event.class_hash),
+        )
+    }
+
+    fn resolve_contract(
+        &self,
+        event: &CreateTableFromContract,
+    ) -> SchemaResolverResult<CreateTable> {
+        self.resolve_table(
+            event.id,
+            event.name.clone(),
+            &SchemaSource::Contract(event.contract_address),
+        )
+    }
+}
+
+impl<P: TypeDefProvider> SchemaResolver for P {
+    fn resolve_type_def(&self, source: &SchemaSource) -> SchemaResolverResult<TypeDef> {
+        let felts = self
+            .type_def_felts(source)
+            .map_err(SchemaResolverError::provider)?;
+        let mut deserializer: CairoSerde<_> = felts.into();
+        let type_def = deserializer.deserialize_type_def()?;
+        match deserializer.next() {
+            Err(e) if e.is_eof() => Ok(type_def),
+            Ok(_) => Err(DecodeError::NotEof.into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn member_to_column(member: MemberDef) -> SchemaResolverResult<ColumnDef> {
+    let id = get_selector_from_name(&member.name)
+        .map_err(|_| SchemaResolverError::InvalidMemberName(member.name.clone()))?;
+    Ok(ColumnDef::new(
+        id,
+        member.name,
+        member.attributes,
+        member.type_def,
+    ))
+}
+
+/// Converts a struct type def into a table following the `#[key]` convention:
+/// a single key member becomes the primary, otherwise every member is a column
+/// and the primary is a `felt252` named `__id`.
+pub fn struct_to_table(
+    id: Felt,
+    name: String,
+    struct_def: StructDef,
+) -> SchemaResolverResult<CreateTable> {
+    let StructDef {
+        attributes,
+        mut members,
+        ..
+    } = struct_def;
+    let keys: Vec<usize> = members
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.has_attribute(KEY_ATTRIBUTE))
+        .map(|(i, _)| i)
+        .collect();
+    let primary = match keys.as_slice() {
+        [index] => {
+            let MemberDef {
+                name,
+                attributes,
+                type_def,
+            } = members.remove(*index);
+            let type_def = PrimaryTypeDef::try_from(type_def).map_err(|e| {
+                SchemaResolverError::InvalidPrimary {
+                    name: name.clone(),
+                    type_name: e.0,
+                }
+            })?;
+            let attributes = attributes
+                .into_iter()
+                .filter(|a| a.name != KEY_ATTRIBUTE)
+                .collect();
+            PrimaryDef::new(name, attributes, type_def)
+        }
+        _ => PrimaryDef::new(
+            DEFAULT_PRIMARY_NAME.to_string(),
+            vec![],
+            PrimaryTypeDef::Felt252,
+        ),
+    };
+    let columns = members
+        .into_iter()
+        .map(member_to_column)
+        .collect::<SchemaResolverResult<_>>()?;
+    Ok(CreateTable {
+        id,
+        name,
+        attributes,
+        primary,
+        columns,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use introspect_types::Attribute;
+    use introspect_types::utils::ascii_str_to_felt;
+
+    fn string_felts(s: &str) -> Vec<Felt> {
+        vec![Felt::ZERO, ascii_str_to_felt(s), Felt::from(s.len())]
+    }
+
+    fn member_felts(name: &str, attributes: &[&str], type_selector: &str) -> Vec<Felt> {
+        let mut felts = string_felts(name);
+        felts.push(Felt::from(attributes.len()));
+        for attr in attributes {
+            felts.extend(string_felts(attr));
+            felts.push(Felt::ONE);
+        }
+        felts.push(ascii_str_to_felt(type_selector));
+        felts
+    }
+
+    fn player_felts() -> Vec<Felt> {
+        let mut felts = vec![ascii_str_to_felt("struct")];
+        felts.extend(string_felts("Player"));
+        felts.push(Felt::ZERO);
+        felts.push(Felt::TWO);
+        felts.extend(member_felts("address", &["key"], "contract_address"));
+        felts.extend(member_felts("score", &[], "u32"));
+        felts
+    }
+
+    #[test]
+    fn resolves_class_with_single_key() {
+        let class_hash = Felt::from(0x1234);
+        let provider = HashMap::from([(SchemaSource::Class(class_hash), player_felts())]);
+        let event = CreateTableFromClass {
+            id: Felt::ONE,
+            name: "Player".to_string(),
+            class_hash,
+        };
+        let table = provider.resolve_class(&event).unwrap();
+        assert_eq!(table.primary.name, "address");
+        assert_eq!(table.primary.type_def, PrimaryTypeDef::ContractAddress);
+        assert!(table.primary.attributes.is_empty());
+        assert_eq!(table.columns.len(), 1);
+        assert_eq!(table.columns[0].name, "score");
+        assert_eq!(
+            table.columns[0].id,
+            get_selector_from_name("score").unwrap()
+        );
+        assert_eq!(table.columns[0].type_def, TypeDef::U32);
+    }
+
+    #[test]
+    fn uses_default_primary_without_single_key() {
+        let key = || vec![Attribute::new_empty(KEY_ATTRIBUTE.to_string())];
+        let struct_def = StructDef::new(
+            "Pair".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("a".to_string(), key(), TypeDef::U8),
+                MemberDef::new("b".to_string(), key(), TypeDef::U8),
+            ],
+        );
+        let table = struct_to_table(Felt::ONE, "Pair".to_string(), struct_def).unwrap();
+        assert_eq!(table.primary.name, DEFAULT_PRIMARY_NAME);
+        assert_eq!(table.columns.len(), 2);
+    }
+
+    #[test]
+    fn missing_source_is_provider_error() {
+        let provider: HashMap<SchemaSource, Vec<Felt>> = HashMap::new();
+        let result = provider.resolve_type_def(&SchemaSource::Contract(Felt::ONE));
+        assert!(matches!(result, Err(SchemaResolverError::Provider(_))));
+    }
+}
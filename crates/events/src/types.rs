@@ -1,21 +1,8 @@
-use introspect_types::deserialize_def::TypeDefDeserializer;
-use introspect_types::{
-    CairoDeserialize, CairoEvent, DecodeResult, FeltSource, TypeDef, cairo_event_name_and_selector,
-};
+use introspect_types::{CairoEvent, TypeDef};
 use starknet_types_core::felt::Felt;
 
-#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
 pub struct DeclareType {
     pub id: Felt,
     pub type_def: TypeDef,
 }
-
-impl<D: FeltSource + TypeDefDeserializer> CairoEvent<D> for DeclareType {
-    fn deserialize_event<K: FeltSource>(_keys: &mut K, data: &mut D) -> DecodeResult<Self> {
-        let id = data.next()?;
-        let type_def = TypeDef::deserialize(data)?;
-        Ok(DeclareType { id, type_def })
-    }
-}
-
-cairo_event_name_and_selector!(DeclareType);
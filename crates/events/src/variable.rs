@@ -0,0 +1,36 @@
+use introspect_types::{CairoEvent, TypeDef};
+use starknet_types_core::felt::Felt;
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
+pub struct RegisterVariable {
+    pub id: Felt,
+    pub name: String,
+    pub type_def: TypeDef,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
+pub struct DeclareVariable {
+    pub id: Felt,
+    pub name: String,
+    pub type_def: TypeDef,
+    #[drain]
+    pub data: Vec<Felt>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
+pub struct SetVariable {
+    pub id: Felt,
+    #[drain]
+    pub data: Vec<Felt>,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
+pub struct RenameVariable {
+    pub id: Felt,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, CairoEvent)]
+pub struct DeleteVariable {
+    pub id: Felt,
+}
@@ -0,0 +1,414 @@
+use proc_macro2::TokenStream;
+use quote::{ToTokens, format_ident, quote};
+use starknet_core::utils::get_selector_from_name;
+use syn::{
+    Attribute, Data, DeriveInput, Fields, GenericArgument, Generics, LitStr, PathArguments, Type,
+    WherePredicate, parse_quote,
+};
+
+/// How a field is read.
+enum Read {
+    /// With the `CairoDeserialize` impl of its type.
+    Value,
+    /// `#[byte_array]`: a byte array into a `String` or `Vec<u8>`, so the
+    /// deserializer's own byte array layout (`Serde` or `ISerde`) is used.
+    ByteArray { string: bool },
+    /// `#[remaining]`: every value left, through `CairoDeserializeRemaining`.
+    /// Only allowed on the last field.
+    Remaining(Box<Type>),
+    /// `#[drain]`: every felt left into a `Vec<Felt>`, without a length
+    /// prefix whatever the layout. Only allowed on the last field.
+    Drain,
+}
+
+impl Read {
+    fn is_trailing(&self) -> bool {
+        matches!(self, Read::Remaining(_) | Read::Drain)
+    }
+}
+
+struct Field {
+    ident: Option<syn::Ident>,
+    read: Read,
+    ty: Type,
+    key: bool,
+}
+
+fn has_attribute(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|attr| attr.path().is_ident(name))
+}
+
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn vec_item(ty: &Type) -> Option<Type> {
+    let segment = last_segment(ty).filter(|segment| segment.ident == "Vec")?;
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => match args.args.first() {
+            Some(GenericArgument::Type(item)) => Some(item.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Raw limbs of the selector of `name`, the error pointing at `spanned`.
+pub(crate) fn selector_raw<T: ToTokens>(name: &str, spanned: T) -> syn::Result<[u64; 4]> {
+    get_selector_from_name(name)
+        .map(|selector| selector.to_raw())
+        .map_err(|_| syn::Error::new_spanned(spanned, "invalid selector name"))
+}
+
+/// Selector of a variant: the one of its identifier, or `#[selector("0x...")]`.
+pub(crate) fn variant_selector(variant: &syn::Variant) -> syn::Result<TokenStream> {
+    let Some(attr) = variant
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("selector"))
+    else {
+        let raw = selector_raw(&variant.ident.to_string(), &variant.ident)?;
+        return Ok(quote! { ::starknet_types_core::felt::Felt::from_raw([#(#raw),*]) });
+    };
+    let value = attr.parse_args::<LitStr>()?;
+    let digits = value.value();
+    let digits = digits.strip_prefix("0x").unwrap_or(&digits);
+    if digits.is_empty() || digits.len() > 63 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(syn::Error::new_spanned(
+            &value,
+            "selector must be a hex felt",
+        ));
+    }
+    Ok(quote! { ::starknet_types_core::felt::Felt::from_hex_unchecked(#value) })
+}
+
+fn parse_fields(fields: &Fields) -> syn::Result<Vec<Field>> {
+    let last = fields.len().saturating_sub(1);
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let remaining = has_attribute(&field.attrs, "remaining");
+            let drain = has_attribute(&field.attrs, "drain");
+            let byte_array = has_attribute(&field.attrs, "byte_array");
+            let read = match (remaining, drain, byte_array) {
+                (false, false, false) => Read::Value,
+                (false, false, true) => Read::ByteArray {
+                    string: last_segment(&field.ty).is_some_and(|s| s.ident == "String"),
+                },
+                (true, false, false) => {
+                    Read::Remaining(vec_item(&field.ty).map(Box::new).ok_or_else(|| {
+                        syn::Error::new_spanned(&field.ty, "#[remaining] fields must be a Vec")
+                    })?)
+                }
+                (false, true, false) => match vec_item(&field.ty) {
+                    Some(_) => Read::Drain,
+                    None => {
+                        return Err(syn::Error::new_spanned(
+                            &field.ty,
+                            "#[drain] fields must be a Vec<Felt>",
+                        ));
+                    }
+                },
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        "#[remaining], #[drain] and #[byte_array] cannot be combined",
+                    ));
+                }
+            };
+            if read.is_trailing() && index != last {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    "#[remaining] and #[drain] are only allowed on the last field",
+                ));
+            }
+            Ok(Field {
+                ident: field.ident.clone(),
+                read,
+                ty: field.ty.clone(),
+                key: has_attribute(&field.attrs, "key"),
+            })
+        })
+        .collect()
+}
+
+impl Field {
+    /// Expression reading the field from `source` (a `&mut` deserializer).
+    fn read(&self, source: &TokenStream, deserializer: &TokenStream) -> TokenStream {
+        let ty = &self.ty;
+        match &self.read {
+            Read::Value => quote! {
+                <#ty as ::introspect_types::CairoDeserialize<#deserializer>>::deserialize(#source)?
+            },
+            Read::ByteArray { string: true } => {
+                quote! { ::introspect_types::CairoDeserializer::next_string(#source)? }
+            }
+            Read::ByteArray { string: false } => quote! {
+                ::introspect_types::CairoDeserializer::next_byte_array_bytes(#source)?.into()
+            },
+            Read::Remaining(_) => quote! {
+                ::introspect_types::CairoDeserializeRemaining::deserialize_remaining(#source)?
+            },
+            Read::Drain => quote! { ::introspect_types::CairoDeserializer::drain(#source)? },
+        }
+    }
+
+    /// Bounds the deserializer must meet to read the field.
+    fn bounds(&self, deserializer: &TokenStream) -> Vec<WherePredicate> {
+        let ty = &self.ty;
+        match &self.read {
+            Read::Value => {
+                vec![parse_quote!(#ty: ::introspect_types::CairoDeserialize<#deserializer>)]
+            }
+            Read::ByteArray { .. } | Read::Drain => vec![],
+            Read::Remaining(item) => vec![
+                parse_quote!(#item: ::introspect_types::CairoDeserialize<#deserializer>),
+                parse_quote!(#deserializer: ::introspect_types::CairoDeserializeRemaining),
+            ],
+        }
+    }
+}
+
+/// `Self { a: .., b: .. }`, `Self(.., ..)` or `Self` from one read per field,
+/// binding the reads in declaration order.
+fn construct(path: TokenStream, fields: &[Field], reads: Vec<TokenStream>) -> TokenStream {
+    let bindings: Vec<_> = (0..fields.len())
+        .map(|index| format_ident!("__field_{index}"))
+        .collect();
+    let lets = bindings
+        .iter()
+        .zip(reads)
+        .map(|(binding, read)| quote! { let #binding = #read; });
+    let value = match fields.first().map(|field| field.ident.is_some()) {
+        None => path,
+        Some(true) => {
+            let idents = fields.iter().map(|field| &field.ident);
+            quote! { #path { #(#idents: #bindings),* } }
+        }
+        Some(false) => quote! { #path(#(#bindings),*) },
+    };
+    quote! { { #(#lets)* #value } }
+}
+
+fn generics_with(generics: &Generics, param: syn::GenericParam) -> Generics {
+    let mut generics = generics.clone();
+    generics.params.push(param);
+    generics
+}
+
+pub fn derive_cairo_deserialize(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let deserializer = quote! { __D };
+    let generics = generics_with(
+        &input.generics,
+        parse_quote!(__D: ::introspect_types::CairoDeserializer),
+    );
+    let mut bounds = Vec::new();
+    let source = quote! { deserializer };
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields)?;
+            bounds.extend(fields.iter().flat_map(|field| field.bounds(&deserializer)));
+            let reads = fields
+                .iter()
+                .map(|field| field.read(&source, &deserializer))
+                .collect();
+            let value = construct(quote! { Self }, &fields, reads);
+            quote! { Ok(#value) }
+        }
+        Data::Enum(data) => {
+            let mut selectors = Vec::new();
+            let mut arms = Vec::new();
+            for (index, variant) in data.variants.iter().enumerate() {
+                selectors.push(variant_selector(variant)?);
+                let fields = parse_fields(&variant.fields)?;
+                bounds.extend(fields.iter().flat_map(|field| field.bounds(&deserializer)));
+                let reads = fields
+                    .iter()
+                    .map(|field| field.read(&source, &deserializer))
+                    .collect();
+                let ident = &variant.ident;
+                let value = construct(quote! { Self::#ident }, &fields, reads);
+                arms.push(quote! { Some(#index) => Ok(#value), });
+            }
+            let enum_name = name.to_string();
+            let count = data.variants.len();
+            quote! {
+                let variant = ::introspect_types::CairoDeserializer::next_enum_variant(deserializer)?;
+                let encoding = ::introspect_types::CairoDeserializer::enum_variant_encoding(deserializer);
+                let index = match encoding {
+                    ::introspect_types::EnumVariantEncoding::Selector => {
+                        [#(#selectors),*].iter().position(|selector| *selector == variant)
+                    }
+                    ::introspect_types::EnumVariantEncoding::Index => {
+                        u64::try_from(variant).ok().map(|index| index as usize)
+                    }
+                };
+                match index {
+                    #(#arms)*
+                    _ => Err(match encoding {
+                        ::introspect_types::EnumVariantEncoding::Selector => {
+                            ::introspect_types::DecodeError::invalid_enum_selector(#enum_name, variant)
+                        }
+                        ::introspect_types::EnumVariantEncoding::Index => {
+                            ::introspect_types::DecodeError::enum_index_out_of_range(#enum_name, variant, #count)
+                        }
+                    }),
+                }
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "CairoDeserialize cannot be derived for unions",
+            ));
+        }
+    };
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause.predicates.extend(bounds);
+    Ok(quote! {
+        impl #impl_generics ::introspect_types::CairoDeserialize<__D> for #name #ty_generics #where_clause {
+            fn deserialize(deserializer: &mut __D) -> ::introspect_types::DecodeResult<Self> {
+                #body
+            }
+        }
+    })
+}
+
+pub fn derive_cairo_event(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "CairoEvent can only be derived for structs",
+        ));
+    };
+    let fields = parse_fields(&data.fields)?;
+    let deserializer = quote! { __D };
+    let generics = generics_with(
+        &input.generics,
+        parse_quote!(__D: ::introspect_types::FeltSource + ::introspect_types::CairoDeserializer),
+    );
+    let bounds = fields
+        .iter()
+        .filter(|field| !field.key)
+        .flat_map(|field| field.bounds(&deserializer));
+    let keys = quote! { &mut keys };
+    let data_source = quote! { event_data };
+    let reads = fields
+        .iter()
+        .map(|field| match field.key {
+            true => field.read(&keys, &quote! { _ }),
+            false => field.read(&data_source, &deserializer),
+        })
+        .collect();
+    let value = construct(quote! { Self }, &fields, reads);
+    let has_keys = fields.iter().any(|field| field.key);
+    let keys_ident = format_ident!("{}event_keys", if has_keys { "" } else { "_" });
+    let open_keys = has_keys.then(|| {
+        quote! { let mut keys = ::introspect_types::CairoSerde::from_mut(event_keys); }
+    });
+    let event_name = name.to_string();
+    let selector = selector_raw(&event_name, name)?;
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (info_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let info_where_clause = where_clause;
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause.predicates.extend(bounds);
+    Ok(quote! {
+        impl #impl_generics ::introspect_types::CairoEvent<__D> for #name #ty_generics #where_clause {
+            fn deserialize_event<K: ::introspect_types::FeltSource>(
+                #keys_ident: &mut K,
+                event_data: &mut __D,
+            ) -> ::introspect_types::DecodeResult<Self> {
+                #open_keys
+                Ok(#value)
+            }
+        }
+
+        impl #info_generics ::introspect_types::CairoEventInfo for #name #ty_generics #info_where_clause {
+            const NAME: &'static str = #event_name;
+            const SELECTOR_RAW: [u64; 4] = [#(#selector),*];
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(result: syn::Result<TokenStream>) -> String {
+        result.map(|_| ()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn rejects_unsupported_items() {
+        let input: DeriveInput = parse_quote! {
+            union Bits { a: u32, b: f32 }
+        };
+        assert_eq!(
+            error(derive_cairo_deserialize(input.clone())),
+            "CairoDeserialize cannot be derived for unions"
+        );
+        assert_eq!(
+            error(derive_cairo_event(input)),
+            "CairoEvent can only be derived for structs"
+        );
+        let input: DeriveInput = parse_quote! {
+            enum Side { Left, Right }
+        };
+        assert_eq!(
+            error(derive_cairo_event(input)),
+            "CairoEvent can only be derived for structs"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_field_attributes() {
+        for (input, message) in [
+            (
+                parse_quote! { struct A { #[remaining] #[byte_array] a: Vec<u8> } },
+                "#[remaining], #[drain] and #[byte_array] cannot be combined",
+            ),
+            (
+                parse_quote! { struct A { #[remaining] a: Vec<Felt>, #[drain] b: Vec<Felt> } },
+                "#[remaining] and #[drain] are only allowed on the last field",
+            ),
+            (
+                parse_quote! { struct A { table: Felt, #[remaining] a: Vec<Felt>, b: Felt } },
+                "#[remaining] and #[drain] are only allowed on the last field",
+            ),
+            (
+                parse_quote! { struct A { #[remaining] a: Felt } },
+                "#[remaining] fields must be a Vec",
+            ),
+            (
+                parse_quote! { struct A { #[drain] a: Felt } },
+                "#[drain] fields must be a Vec<Felt>",
+            ),
+            (
+                parse_quote! { enum A { #[selector(Left)] B } },
+                "expected string literal",
+            ),
+            (
+                parse_quote! { enum A { #[selector("Left")] B } },
+                "selector must be a hex felt",
+            ),
+            (parse_quote! { enum A { Flèche } }, "invalid selector name"),
+        ] {
+            assert_eq!(error(derive_cairo_deserialize(input)), message);
+        }
+        let input: DeriveInput = parse_quote! { struct Événement { a: Felt } };
+        assert_eq!(error(derive_cairo_event(input)), "invalid selector name");
+        let input: DeriveInput = parse_quote! {
+            struct A { table: Felt, b: Felt, #[remaining] a: Vec<Felt> }
+        };
+        assert!(derive_cairo_deserialize(input).is_ok());
+    }
+}
@@ -0,0 +1,210 @@
+use crate::cairo_deserialize::variant_selector;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Fields, GenericArgument, Ident, LitStr, PathArguments, Type,
+    WherePredicate, parse_quote,
+};
+
+fn find_attribute<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attrs.iter().find(|attr| attr.path().is_ident(name))
+}
+
+fn string_attribute(attrs: &[Attribute], name: &str) -> syn::Result<Option<String>> {
+    find_attribute(attrs, name)
+        .map(|attr| attr.parse_args::<LitStr>().map(|value| value.value()))
+        .transpose()
+}
+
+/// `TypeDef` of a field: its type's, replaced by `#[type_def(Variant)]` or
+/// modified by `#[raw]` and `#[encoded("...")]`.
+fn field_type_def(
+    field: &syn::Field,
+    bounds: &mut Vec<WherePredicate>,
+) -> syn::Result<TokenStream> {
+    if let Some(attr) = find_attribute(&field.attrs, "type_def") {
+        let variant = attr.parse_args::<Ident>()?;
+        return Ok(quote! { ::introspect_types::TypeDef::#variant });
+    }
+    let ty = &field.ty;
+    bounds.push(parse_quote!(#ty: ::introspect_types::ToTypeDef));
+    let type_def = quote! { <#ty as ::introspect_types::ToTypeDef>::to_type_def() };
+    let raw = find_attribute(&field.attrs, "raw").is_some();
+    match (raw, string_attribute(&field.attrs, "encoded")?) {
+        (true, Some(_)) => Err(syn::Error::new_spanned(
+            field,
+            "#[raw] and #[encoded] cannot be combined",
+        )),
+        (true, None) => Ok(quote! { ::introspect_types::to_type_def::raw_type_def(#type_def) }),
+        (false, Some(encoding)) => Ok(quote! {
+            ::introspect_types::to_type_def::encoded_type_def(#type_def, #encoding)
+        }),
+        (false, None) => Ok(type_def),
+    }
+}
+
+/// Whether `ty` names the item being derived, directly or in a generic argument.
+fn mentions(ty: &Type, ident: &Ident) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.iter().any(|segment| {
+            segment.ident == *ident
+                || segment.ident == "Self"
+                || match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args.args.iter().any(|arg| match arg {
+                        GenericArgument::Type(ty) => mentions(ty, ident),
+                        _ => false,
+                    }),
+                    _ => false,
+                }
+        }),
+        Type::Tuple(tuple) => tuple.elems.iter().any(|ty| mentions(ty, ident)),
+        Type::Array(array) => mentions(&array.elem, ident),
+        Type::Slice(slice) => mentions(&slice.elem, ident),
+        Type::Reference(reference) => mentions(&reference.elem, ident),
+        Type::Paren(paren) => mentions(&paren.elem, ident),
+        Type::Group(group) => mentions(&group.elem, ident),
+        _ => false,
+    }
+}
+
+/// Rejects fields holding the item itself, whose `TypeDef` would need a `Ref`.
+/// Types recursing through another type fail on their cyclic `ToTypeDef`
+/// bounds instead.
+fn reject_recursion<'a>(
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+    ident: &Ident,
+) -> syn::Result<()> {
+    match fields.into_iter().find(|field| mentions(&field.ty, ident)) {
+        Some(field) => Err(syn::Error::new_spanned(
+            &field.ty,
+            "Introspect cannot be derived for recursive types, their TypeDef needs a Ref",
+        )),
+        None => Ok(()),
+    }
+}
+
+fn members(fields: &Fields, bounds: &mut Vec<WherePredicate>) -> syn::Result<Vec<TokenStream>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let name = match string_attribute(&field.attrs, "name")? {
+                Some(name) => name,
+                None => field
+                    .ident
+                    .as_ref()
+                    .map_or_else(|| index.to_string(), ToString::to_string),
+            };
+            let type_def = field_type_def(field, bounds)?;
+            Ok(quote! {
+                ::introspect_types::MemberDef::new(#name.to_string(), vec![], #type_def)
+            })
+        })
+        .collect()
+}
+
+fn variant(
+    variant: &syn::Variant,
+    bounds: &mut Vec<WherePredicate>,
+) -> syn::Result<(TokenStream, String, TokenStream)> {
+    let name = string_attribute(&variant.attrs, "name")?.unwrap_or(variant.ident.to_string());
+    let selector = variant_selector(variant)?;
+    let type_def = match &variant.fields {
+        Fields::Unit => quote! { ::introspect_types::TypeDef::None },
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            field_type_def(&fields.unnamed[0], bounds)?
+        }
+        Fields::Unnamed(fields) => {
+            let elements = fields
+                .unnamed
+                .iter()
+                .map(|field| field_type_def(field, bounds))
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { ::introspect_types::TupleDef::new_type_def(vec![#(#elements),*]) }
+        }
+        Fields::Named(_) => {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "Introspect variants hold a single type, use a tuple variant",
+            ));
+        }
+    };
+    Ok((selector, name, type_def))
+}
+
+pub fn derive_introspect(input: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let name = string_attribute(&input.attrs, "name")?.unwrap_or(ident.to_string());
+    let mut bounds = Vec::new();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            reject_recursion(&data.fields, ident)?;
+            let members = members(&data.fields, &mut bounds)?;
+            quote! {
+                ::introspect_types::StructDef::new_type_def(#name.to_string(), vec![], vec![#(#members),*])
+            }
+        }
+        Data::Enum(data) => {
+            reject_recursion(data.variants.iter().flat_map(|v| &v.fields), ident)?;
+            let variants = data
+                .variants
+                .iter()
+                .map(|v| variant(v, &mut bounds))
+                .collect::<syn::Result<Vec<_>>>()?;
+            let variants = variants.iter().map(|(selector, name, type_def)| {
+                quote! {
+                    (#selector, ::introspect_types::VariantDef::new(#name.to_string(), vec![], #type_def))
+                }
+            });
+            quote! {
+                ::introspect_types::EnumDef::new_type_def(#name.to_string(), vec![], vec![#(#variants),*])
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input,
+                "Introspect cannot be derived for unions",
+            ));
+        }
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause.predicates.extend(bounds);
+    Ok(quote! {
+        impl #impl_generics ::introspect_types::ToTypeDef for #ident #ty_generics #where_clause {
+            fn to_type_def() -> ::introspect_types::TypeDef {
+                #body
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(input: DeriveInput) -> String {
+        derive_introspect(input)
+            .map(|_| ())
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn rejects_recursive_types() {
+        let message = "Introspect cannot be derived for recursive types, their TypeDef needs a Ref";
+        assert_eq!(
+            error(parse_quote! { struct Node { value: u8, next: Option<Box<Node>> } }),
+            message
+        );
+        assert_eq!(
+            error(parse_quote! { enum Tree { Leaf, Branch(Vec<Self>) } }),
+            message
+        );
+        assert!(derive_introspect(parse_quote! { struct Pair { a: (u8, NodeId) } }).is_ok());
+        assert_eq!(
+            error(parse_quote! { union Bits { a: u32 } }),
+            "Introspect cannot be derived for unions"
+        );
+    }
+}
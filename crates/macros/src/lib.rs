@@ -1,3 +1,6 @@
+mod cairo_deserialize;
+mod introspect;
+
 use proc_macro::TokenStream;
 use quote::quote;
 use starknet_core::utils::get_selector_from_name;
@@ -181,3 +184,58 @@ pub fn derive_enum_from(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Derives `CairoDeserialize<D>` for any `CairoDeserializer`, reading fields in
+/// declaration order.
+///
+/// Enum variants are matched by the selector of their identifier, as in
+/// `Introspect`, or by index, following the deserializer's
+/// `EnumVariantEncoding`.
+///
+/// # Attributes:
+/// - `#[selector("0x...")]` - Sets the selector of a variant
+/// - `#[byte_array]` - Reads a `String` or `Vec<u8>` as a Cairo byte array
+/// - `#[remaining]` - Reads the last field, a `Vec<T>`, with `CairoDeserializeRemaining`
+/// - `#[drain]` - Reads every felt left into the last field, a `Vec<Felt>`, whatever
+///   the layout
+#[proc_macro_derive(CairoDeserialize, attributes(byte_array, remaining, drain, selector))]
+pub fn derive_cairo_deserialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    cairo_deserialize::derive_cairo_deserialize(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `CairoEvent<D>` and `CairoEventInfo` for a struct, selected by its
+/// name.
+///
+/// `#[key]` fields are read from the event keys with `CairoSerde`, the others
+/// from the event data in declaration order. Supports the same field attributes
+/// as `CairoDeserialize`.
+#[proc_macro_derive(CairoEvent, attributes(key, byte_array, remaining, drain))]
+pub fn derive_cairo_event(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    cairo_deserialize::derive_cairo_event(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `ToTypeDef` for a struct or enum, giving the `TypeDef` the Cairo
+/// `TypeDef` derive would produce for the same declaration.
+///
+/// Enum variants hold unit, a single type or a tuple of types, and are
+/// selected by the selector of their identifier, even when renamed. Types
+/// holding themselves are rejected, their `TypeDef` needing a `Ref`.
+///
+/// # Attributes:
+/// - `#[name("...")]` - Renames the item, a member or a variant
+/// - `#[selector("0x...")]` - Sets the selector of a variant
+/// - `#[raw]` / `#[encoded("...")]` - Raw or encoded bytes instead of a string
+/// - `#[type_def(Variant)]` - Uses a unit `TypeDef` variant, e.g. `ContractAddress` for a `Felt`
+#[proc_macro_derive(Introspect, attributes(name, selector, raw, encoded, type_def))]
+pub fn derive_introspect(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    introspect::derive_introspect(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,115 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use introspect_types::serialize::ToCairoDeSeFrom;
+use introspect_types::serialize_def::CairoTypeSerialization;
+use introspect_types::transcode::Transcode;
+use introspect_types::{
+    ArrayDef, CairoSerde, DecodePlan, EnumDef, FixedArrayDef, IntoFeltSource, MemberDef, OptionDef,
+    ParseValue, StructDef, TypeDef, VariantDef,
+};
+use starknet_types_core::felt::Felt;
+
+struct Json;
+impl CairoTypeSerialization for Json {}
+
+const VARIANTS: u64 = 16;
+const ITEMS: u64 = 32;
+
+fn type_def() -> TypeDef {
+    let kind = EnumDef::new_type_def(
+        "Kind".to_string(),
+        vec![],
+        (0..VARIANTS)
+            .map(|i| {
+                let variant = VariantDef::new(format!("Kind{i}"), vec![], TypeDef::U32);
+                (Felt::from(0x100 + i), variant)
+            })
+            .collect(),
+    );
+    let item = StructDef::new_type_def(
+        "Item".to_string(),
+        vec![],
+        vec![
+            MemberDef::new("id".to_string(), vec![], TypeDef::U64),
+            MemberDef::new("kind".to_string(), vec![], kind),
+            MemberDef::new(
+                "owner".to_string(),
+                vec![],
+                OptionDef::new_type_def(TypeDef::ContractAddress),
+            ),
+            MemberDef::new(
+                "stats".to_string(),
+                vec![],
+                FixedArrayDef::new_type_def(TypeDef::U16, 8),
+            ),
+        ],
+    );
+    StructDef::new_type_def(
+        "Inventory".to_string(),
+        vec![],
+        vec![
+            MemberDef::new("owner".to_string(), vec![], TypeDef::ContractAddress),
+            MemberDef::new("items".to_string(), vec![], ArrayDef::new_type_def(item)),
+        ],
+    )
+}
+
+fn felts() -> Vec<Felt> {
+    let mut felts = vec![Felt::from(0xabc), Felt::from(ITEMS)];
+    for i in 0..ITEMS {
+        felts.extend([i, 0x100 + i % VARIANTS, i, 0, 0x123].map(Felt::from));
+        felts.extend((0..8u64).map(Felt::from));
+    }
+    felts
+}
+
+fn plan(c: &mut Criterion) {
+    let type_def = type_def();
+    let plan = DecodePlan::compile(&type_def).unwrap();
+    let felts = felts();
+
+    let mut group = c.benchmark_group("parse");
+    group.bench_function("tree", |b| {
+        b.iter(|| type_def.parse(&mut CairoSerde(black_box(felts.as_slice()).into_source())))
+    });
+    group.bench_function("plan", |b| {
+        b.iter(|| plan.parse(&mut CairoSerde(black_box(felts.as_slice()).into_source())))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("transcode");
+    group.bench_function("tree", |b| {
+        b.iter(|| {
+            let mut output = Vec::new();
+            let mut input = CairoSerde(black_box(felts.as_slice()).into_source());
+            type_def.transcode(&mut input, &mut output).unwrap();
+            output
+        })
+    });
+    group.bench_function("plan", |b| {
+        b.iter(|| {
+            let mut output = Vec::new();
+            let mut input = CairoSerde(black_box(felts.as_slice()).into_source());
+            plan.transcode(&mut input, &mut output).unwrap();
+            output
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("json");
+    group.bench_function("tree", |b| {
+        b.iter(|| {
+            let mut de = CairoSerde(black_box(felts.as_slice()).into_source());
+            serde_json::to_vec(&type_def.to_de_se(&mut de, &Json)).unwrap()
+        })
+    });
+    group.bench_function("plan", |b| {
+        b.iter(|| {
+            let mut de = CairoSerde(black_box(felts.as_slice()).into_source());
+            serde_json::to_vec(&plan.to_de_se(&mut de, &Json)).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, plan);
+criterion_main!(benches);
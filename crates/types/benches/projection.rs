@@ -0,0 +1,89 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use introspect_types::{
+    ArrayDef, ColumnDef, MemberDef, PrimaryDef, PrimaryTypeDef, Projection, StructDef, TableSchema,
+    TypeDef,
+};
+use starknet_types_core::felt::Felt;
+
+const COLUMNS: u64 = 30;
+
+fn column_type(i: u64) -> TypeDef {
+    match i % 4 {
+        0 => TypeDef::U64,
+        1 => StructDef::new_type_def(
+            "Stats".to_string(),
+            vec![],
+            ["attack", "defense", "speed"]
+                .into_iter()
+                .map(|name| MemberDef::new(name.to_string(), vec![], TypeDef::U32))
+                .collect(),
+        ),
+        2 => TypeDef::ByteArray,
+        _ => ArrayDef::new_type_def(TypeDef::U32),
+    }
+}
+
+fn column_data(i: u64, data: &mut Vec<Felt>) {
+    match i % 4 {
+        0 => data.push(Felt::from(i)),
+        1 => data.extend([1u64, 2, 3].map(Felt::from)),
+        2 => {
+            let mut chunk = [0u8; 32];
+            chunk[0] = 3;
+            chunk[2] = 5;
+            chunk[27..].copy_from_slice(b"hello");
+            data.push(Felt::from_bytes_be(&chunk));
+        }
+        _ => {
+            data.push(Felt::from(8));
+            data.extend((0..8u64).map(Felt::from));
+        }
+    }
+}
+
+fn table() -> (TableSchema, Vec<Felt>) {
+    let mut data = Vec::new();
+    let columns = (0..COLUMNS)
+        .map(|i| {
+            column_data(i, &mut data);
+            ColumnDef::new(Felt::from(i), format!("column_{i}"), vec![], column_type(i))
+        })
+        .collect();
+    let table = TableSchema {
+        id: Felt::ONE,
+        name: "Bench".to_string(),
+        attributes: vec![],
+        primary: PrimaryDef::new("id".to_string(), vec![], PrimaryTypeDef::Felt252),
+        columns,
+    };
+    (table, data)
+}
+
+fn projection(c: &mut Criterion) {
+    let (table, data) = table();
+    let columns = Projection::columns([Felt::from(0), Felt::from(28)]);
+    let members = columns.clone().with_path(Felt::from(13), &["speed"]);
+
+    let mut group = c.benchmark_group("record");
+    group.bench_function("full", |b| {
+        b.iter(|| table.parse_record(Felt::TWO, black_box(&data)).unwrap())
+    });
+    group.bench_function("projected_columns", |b| {
+        b.iter(|| {
+            table
+                .parse_record_projected(Felt::TWO, black_box(&data), &columns)
+                .unwrap()
+        })
+    });
+    group.bench_function("projected_members", |b| {
+        b.iter(|| {
+            table
+                .parse_record_projected(Felt::TWO, black_box(&data), &members)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, projection);
+criterion_main!(benches);
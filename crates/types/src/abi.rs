@@ -0,0 +1,524 @@
+use crate::parser::TypeParserError;
+use crate::{
+    ArrayDef, Attribute, EnumDef, FixedArrayDef, KEY_ATTRIBUTE, MemberDef, NullableDef, OptionDef,
+    ResultDef, StructDef, TupleDef, TypeDef, VariantDef,
+};
+use cairo_lang_starknet_classes::abi::{
+    Enum, Event, EventField, EventFieldKind, EventKind, Item, Struct,
+};
+use starknet::core::utils::get_selector_from_name;
+use starknet_types_core::felt::Felt;
+use std::collections::HashMap;
+use thiserror::Error;
+
+pub const NESTED_ATTRIBUTE: &str = "nested";
+pub const FLAT_ATTRIBUTE: &str = "flat";
+
+#[derive(Debug, Error)]
+pub enum AbiError {
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Parse(#[from] TypeParserError),
+
+    #[error("unknown abi type: {0}")]
+    UnknownType(String),
+
+    #[error("unsupported abi type: {0}")]
+    UnsupportedType(String),
+
+    #[error("invalid abi type: {0}")]
+    InvalidType(String),
+
+    #[error("recursive abi type: {0}")]
+    RecursiveType(String),
+
+    #[error("invalid name: {0}")]
+    InvalidName(String),
+}
+
+pub type AbiResult<T> = Result<T, AbiError>;
+
+/// Converts the struct, enum and event entries of a Sierra class ABI into `TypeDef`s.
+///
+/// Core types are mapped onto their matching `TypeDef` variants, user types are
+/// expanded inline. Enum variants are keyed by `selector!(variant_name)` and keep
+/// their ABI order in `EnumDef::order`. Event `#[key]`, nested and flat fields are
+/// marked with the `key`, `nested` and `flat` attributes.
+#[derive(Clone, Debug, Default)]
+pub struct AbiTypeDefs {
+    pub structs: HashMap<String, Struct>,
+    pub enums: HashMap<String, Enum>,
+    pub events: HashMap<String, Event>,
+}
+
+impl AbiTypeDefs {
+    pub fn new<I: IntoIterator<Item = Item>>(items: I) -> Self {
+        let mut abi = AbiTypeDefs::default();
+        for item in items {
+            abi.add_item(item);
+        }
+        abi
+    }
+
+    pub fn from_json(json: &str) -> AbiResult<Self> {
+        Ok(Self::new(serde_json::from_str::<Vec<Item>>(json)?))
+    }
+
+    fn add_item(&mut self, item: Item) {
+        match item {
+            Item::Struct(s) => {
+                self.structs.insert(s.name.clone(), s);
+            }
+            Item::Enum(e) => {
+                self.enums.insert(e.name.clone(), e);
+            }
+            Item::Event(e) => {
+                self.events.insert(e.name.clone(), e);
+            }
+            Item::Interface(i) => i.items.into_iter().for_each(|i| self.add_item(i)),
+            _ => {}
+        }
+    }
+
+    pub fn type_def(&self, ty: &str) -> AbiResult<TypeDef> {
+        self.parse_type(ty, &mut Vec::new())
+    }
+
+    pub fn struct_def(&self, name: &str) -> AbiResult<StructDef> {
+        let s = self
+            .structs
+            .get(name)
+            .ok_or_else(|| AbiError::UnknownType(name.to_string()))?;
+        self.convert_struct(s, &mut Vec::new())
+    }
+
+    pub fn enum_def(&self, name: &str) -> AbiResult<EnumDef> {
+        let e = self
+            .enums
+            .get(name)
+            .ok_or_else(|| AbiError::UnknownType(name.to_string()))?;
+        self.convert_enum(e, &mut Vec::new())
+    }
+
+    /// Returns the event as a `TypeDef::Struct` for struct events or `TypeDef::Enum`
+    /// for event enums.
+    pub fn event_def(&self, name: &str) -> AbiResult<TypeDef> {
+        let e = self
+            .events
+            .get(name)
+            .ok_or_else(|| AbiError::UnknownType(name.to_string()))?;
+        self.convert_event(e, &mut Vec::new())
+    }
+
+    pub fn struct_defs(&self) -> AbiResult<Vec<StructDef>> {
+        self.structs
+            .keys()
+            .filter(|name| core_type_def(name).is_none() && generic_core_name(name).is_none())
+            .map(|name| self.struct_def(name))
+            .collect()
+    }
+
+    pub fn enum_defs(&self) -> AbiResult<Vec<EnumDef>> {
+        self.enums
+            .keys()
+            .filter(|name| core_type_def(name).is_none() && generic_core_name(name).is_none())
+            .map(|name| self.enum_def(name))
+            .collect()
+    }
+
+    pub fn event_defs(&self) -> AbiResult<Vec<(String, TypeDef)>> {
+        self.events
+            .keys()
+            .map(|name| Ok((name.clone(), self.event_def(name)?)))
+            .collect()
+    }
+
+    fn parse_type(&self, ty: &str, visiting: &mut Vec<String>) -> AbiResult<TypeDef> {
+        let ty = ty.trim();
+        let ty = ty.strip_prefix('@').map_or(ty, str::trim_start);
+        if let Some(type_def) = core_type_def(ty) {
+            return Ok(type_def);
+        }
+        if let Some(inner) = ty.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+            let elements = split_generics(inner)
+                .into_iter()
+                .map(|e| self.parse_type(e, visiting))
+                .collect::<AbiResult<Vec<_>>>()?;
+            return Ok(match elements.is_empty() {
+                true => TypeDef::None,
+                false => TupleDef::new_type_def(elements),
+            });
+        }
+        if let Some(inner) = ty.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            let (element, size) = inner
+                .rsplit_once(';')
+                .ok_or_else(|| AbiError::InvalidType(ty.to_string()))?;
+            let size = size
+                .trim()
+                .parse()
+                .map_err(|_| AbiError::InvalidType(ty.to_string()))?;
+            return Ok(FixedArrayDef::new_type_def(
+                self.parse_type(element, visiting)?,
+                size,
+            ));
+        }
+        if let Some((path, args)) = split_path_generics(ty) {
+            let args = split_generics(args);
+            match (generic_core_name(path), args.as_slice()) {
+                (Some("Array") | Some("Span"), [inner]) => {
+                    return Ok(ArrayDef::new_type_def(self.parse_type(inner, visiting)?));
+                }
+                (Some("Option"), [inner]) => {
+                    return Ok(OptionDef::new_type_def(self.parse_type(inner, visiting)?));
+                }
+                (Some("Nullable"), [inner]) => {
+                    return Ok(NullableDef::new_type_def(self.parse_type(inner, visiting)?));
+                }
+                (Some("NonZero") | Some("Box"), [inner]) => {
+                    return self.parse_type(inner, visiting);
+                }
+                (Some("Result"), [ok, err]) => {
+                    return Ok(ResultDef::new_type_def(
+                        self.parse_type(ok, visiting)?,
+                        self.parse_type(err, visiting)?,
+                    ));
+                }
+                (Some("Felt252Dict"), _) => {
+                    return Err(AbiError::UnsupportedType(ty.to_string()));
+                }
+                (Some(_), _) => return Err(AbiError::InvalidType(ty.to_string())),
+                (None, _) => {}
+            }
+        }
+        if let Some(s) = self.structs.get(ty) {
+            return self.convert_struct(s, visiting).map(TypeDef::Struct);
+        }
+        if let Some(e) = self.enums.get(ty) {
+            return self.convert_enum(e, visiting).map(TypeDef::Enum);
+        }
+        Err(AbiError::UnknownType(ty.to_string()))
+    }
+
+    /// Runs `convert` with `name` on the `visiting` stack, popping it again whether
+    /// or not the conversion succeeds.
+    fn visit<T>(
+        &self,
+        name: &str,
+        visiting: &mut Vec<String>,
+        convert: impl FnOnce(&mut Vec<String>) -> AbiResult<T>,
+    ) -> AbiResult<T> {
+        if visiting.iter().any(|n| n == name) {
+            return Err(AbiError::RecursiveType(name.to_string()));
+        }
+        visiting.push(name.to_string());
+        let result = convert(visiting);
+        visiting.pop();
+        result
+    }
+
+    fn convert_struct(&self, s: &Struct, visiting: &mut Vec<String>) -> AbiResult<StructDef> {
+        let members = self.visit(&s.name, visiting, |visiting| {
+            s.members
+                .iter()
+                .map(|m| {
+                    let type_def = self.parse_type(&m.ty, visiting)?;
+                    Ok(MemberDef::new(m.name.clone(), vec![], type_def))
+                })
+                .collect::<AbiResult<Vec<_>>>()
+        })?;
+        Ok(StructDef::new(short_name(&s.name), vec![], members))
+    }
+
+    fn convert_enum(&self, e: &Enum, visiting: &mut Vec<String>) -> AbiResult<EnumDef> {
+        let variants = self.visit(&e.name, visiting, |visiting| {
+            e.variants
+                .iter()
+                .map(|v| {
+                    let type_def = self.parse_type(&v.ty, visiting)?;
+                    Ok((
+                        variant_selector(&v.name)?,
+                        VariantDef::new(v.name.clone(), vec![], type_def),
+                    ))
+                })
+                .collect::<AbiResult<Vec<_>>>()
+        })?;
+        Ok(EnumDef::new(short_name(&e.name), vec![], variants))
+    }
+
+    fn convert_event(&self, e: &Event, visiting: &mut Vec<String>) -> AbiResult<TypeDef> {
+        self.visit(&e.name, visiting, |visiting| {
+            self.convert_event_kind(e, visiting)
+        })
+    }
+
+    fn convert_event_kind(&self, e: &Event, visiting: &mut Vec<String>) -> AbiResult<TypeDef> {
+        Ok(match &e.kind {
+            EventKind::Struct { members } => {
+                let members = members
+                    .iter()
+                    .map(|m| {
+                        let type_def = self.event_field_type(m, visiting)?;
+                        Ok(MemberDef::new(
+                            m.name.clone(),
+                            event_field_attributes(&m.kind),
+                            type_def,
+                        ))
+                    })
+                    .collect::<AbiResult<Vec<_>>>()?;
+                StructDef::new_type_def(short_name(&e.name), vec![], members)
+            }
+            EventKind::Enum { variants } => {
+                let variants = variants
+                    .iter()
+                    .map(|v| {
+                        let type_def = self.event_field_type(v, visiting)?;
+                        Ok((
+                            variant_selector(&v.name)?,
+                            VariantDef::new(
+                                v.name.clone(),
+                                event_field_attributes(&v.kind),
+                                type_def,
+                            ),
+                        ))
+                    })
+                    .collect::<AbiResult<Vec<_>>>()?;
+                EnumDef::new_type_def(short_name(&e.name), vec![], variants)
+            }
+        })
+    }
+
+    fn event_field_type(
+        &self,
+        field: &EventField,
+        visiting: &mut Vec<String>,
+    ) -> AbiResult<TypeDef> {
+        match (&field.kind, self.events.get(&field.ty)) {
+            (EventFieldKind::Nested | EventFieldKind::Flat, Some(event)) => {
+                self.convert_event(event, visiting)
+            }
+            _ => self.parse_type(&field.ty, visiting),
+        }
+    }
+}
+
+fn event_field_attributes(kind: &EventFieldKind) -> Vec<Attribute> {
+    let name = match kind {
+        EventFieldKind::KeySerde => KEY_ATTRIBUTE,
+        EventFieldKind::DataSerde => return vec![],
+        EventFieldKind::Nested => NESTED_ATTRIBUTE,
+        EventFieldKind::Flat => FLAT_ATTRIBUTE,
+    };
+    vec![Attribute::new_empty(name.to_string())]
+}
+
+fn variant_selector(name: &str) -> AbiResult<Felt> {
+    get_selector_from_name(name).map_err(|_| AbiError::InvalidName(name.to_string()))
+}
+
+fn core_type_def(ty: &str) -> Option<TypeDef> {
+    Some(match ty {
+        "core::felt252" => TypeDef::Felt252,
+        "core::bool" => TypeDef::Bool,
+        "core::integer::u8" => TypeDef::U8,
+        "core::integer::u16" => TypeDef::U16,
+        "core::integer::u32" => TypeDef::U32,
+        "core::integer::u64" => TypeDef::U64,
+        "core::integer::u128" => TypeDef::U128,
+        "core::integer::u256" => TypeDef::U256,
+        "core::integer::u512" => TypeDef::U512,
+        "core::integer::i8" => TypeDef::I8,
+        "core::integer::i16" => TypeDef::I16,
+        "core::integer::i32" => TypeDef::I32,
+        "core::integer::i64" => TypeDef::I64,
+        "core::integer::i128" => TypeDef::I128,
+        "core::bytes_31::bytes31" => TypeDef::Bytes31,
+        "core::byte_array::ByteArray" => TypeDef::ByteArray,
+        "core::starknet::class_hash::ClassHash" => TypeDef::ClassHash,
+        "core::starknet::contract_address::ContractAddress" => TypeDef::ContractAddress,
+        "core::starknet::eth_address::EthAddress" => TypeDef::EthAddress,
+        "core::starknet::storage_access::StorageAddress" => TypeDef::StorageAddress,
+        "core::starknet::storage_access::StorageBaseAddress" => TypeDef::StorageBaseAddress,
+        "()" => TypeDef::None,
+        _ => return None,
+    })
+}
+
+fn generic_core_name(path: &str) -> Option<&'static str> {
+    let path = split_path_generics(path).map_or(path, |(p, _)| p);
+    Some(match path {
+        "core::array::Array" => "Array",
+        "core::array::Span" => "Span",
+        "core::option::Option" => "Option",
+        "core::result::Result" => "Result",
+        "core::nullable::Nullable" => "Nullable",
+        "core::zeroable::NonZero" => "NonZero",
+        "core::box::Box" => "Box",
+        "core::dict::Felt252Dict" => "Felt252Dict",
+        _ => return None,
+    })
+}
+
+/// Splits `path::<A, B>` into `path` and `A, B`.
+fn split_path_generics(ty: &str) -> Option<(&str, &str)> {
+    let (path, rest) = ty.split_once("::<")?;
+    Some((path, rest.strip_suffix('>')?))
+}
+
+/// Splits a comma separated list of types, ignoring commas nested in brackets.
+fn split_generics(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0usize);
+    for (i, c) in args.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = args[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+fn short_name(path: &str) -> String {
+    let path = split_path_generics(path).map_or(path, |(p, _)| p);
+    path.rsplit("::").next().unwrap_or(path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Attributes;
+
+    const ABI: &str = r#"[
+        {"type": "struct", "name": "core::array::Span::<core::felt252>", "members": [
+            {"name": "snapshot", "type": "@core::array::Array::<core::felt252>"}
+        ]},
+        {"type": "struct", "name": "core::integer::u256", "members": [
+            {"name": "low", "type": "core::integer::u128"},
+            {"name": "high", "type": "core::integer::u128"}
+        ]},
+        {"type": "enum", "name": "core::bool", "variants": [
+            {"name": "False", "type": "()"},
+            {"name": "True", "type": "()"}
+        ]},
+        {"type": "struct", "name": "game::Stats", "members": [
+            {"name": "hp", "type": "core::integer::u32"},
+            {"name": "items", "type": "core::array::Span::<(core::felt252, core::integer::u8)>"}
+        ]},
+        {"type": "enum", "name": "game::Kind", "variants": [
+            {"name": "Warrior", "type": "()"},
+            {"name": "Mage", "type": "game::Stats"}
+        ]},
+        {"type": "struct", "name": "game::Player", "members": [
+            {"name": "owner", "type": "core::starknet::contract_address::ContractAddress"},
+            {"name": "name", "type": "core::byte_array::ByteArray"},
+            {"name": "balance", "type": "core::integer::u256"},
+            {"name": "kind", "type": "core::option::Option::<game::Kind>"}
+        ]},
+        {"type": "event", "name": "game::Moved", "kind": "struct", "members": [
+            {"name": "player", "type": "core::starknet::contract_address::ContractAddress", "kind": "key"},
+            {"name": "x", "type": "core::integer::u32", "kind": "data"}
+        ]},
+        {"type": "event", "name": "game::Event", "kind": "enum", "variants": [
+            {"name": "Moved", "type": "game::Moved", "kind": "nested"}
+        ]}
+    ]"#;
+
+    #[test]
+    fn converts_structs_and_core_types() {
+        let abi = AbiTypeDefs::from_json(ABI).unwrap();
+        let player = abi.struct_def("game::Player").unwrap();
+        assert_eq!(player.name, "Player");
+        assert_eq!(player.members[0].type_def, TypeDef::ContractAddress);
+        assert_eq!(player.members[1].type_def, TypeDef::ByteArray);
+        assert_eq!(player.members[2].type_def, TypeDef::U256);
+        let TypeDef::Option(option) = &player.members[3].type_def else {
+            panic!("expected option");
+        };
+        let TypeDef::Enum(kind) = &option.type_def else {
+            panic!("expected enum");
+        };
+        assert_eq!(kind.order.len(), 2);
+        let mage = kind.get_variant(&kind.order[1]).unwrap();
+        assert_eq!(mage.name, "Mage");
+        let TypeDef::Struct(stats) = &mage.type_def else {
+            panic!("expected struct");
+        };
+        assert_eq!(
+            stats.members[1].type_def,
+            ArrayDef::new_type_def(TupleDef::new_type_def(vec![TypeDef::Felt252, TypeDef::U8]))
+        );
+        assert_eq!(abi.struct_defs().unwrap().len(), 2);
+        assert_eq!(abi.enum_defs().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn converts_events() {
+        let abi = AbiTypeDefs::from_json(ABI).unwrap();
+        let TypeDef::Enum(event) = abi.event_def("game::Event").unwrap() else {
+            panic!("expected enum");
+        };
+        let moved = event.get_variant(&event.order[0]).unwrap();
+        assert!(moved.has_attribute(NESTED_ATTRIBUTE));
+        let TypeDef::Struct(moved) = &moved.type_def else {
+            panic!("expected struct");
+        };
+        assert!(moved.members[0].has_attribute(KEY_ATTRIBUTE));
+        assert!(moved.members[1].attributes.is_empty());
+    }
+
+    #[test]
+    fn parses_fixed_arrays_and_results() {
+        let abi = AbiTypeDefs::default();
+        assert_eq!(
+            abi.type_def("[core::integer::u8; 4]").unwrap(),
+            FixedArrayDef::new_type_def(TypeDef::U8, 4)
+        );
+        assert_eq!(
+            abi.type_def("core::result::Result::<core::felt252, ()>")
+                .unwrap(),
+            ResultDef::new_type_def(TypeDef::Felt252, TypeDef::None)
+        );
+        assert!(matches!(
+            abi.type_def("game::Missing"),
+            Err(AbiError::UnknownType(_))
+        ));
+    }
+
+    #[test]
+    fn strips_snapshots() {
+        let abi = AbiTypeDefs::default();
+        assert_eq!(
+            abi.type_def("@core::array::Array::<core::felt252>")
+                .unwrap(),
+            ArrayDef::new_type_def(TypeDef::Felt252)
+        );
+    }
+
+    #[test]
+    fn pops_visiting_on_error() {
+        let abi = AbiTypeDefs::from_json(
+            r#"[
+            {"type": "struct", "name": "game::Broken", "members": [
+                {"name": "missing", "type": "game::Missing"}
+            ]}
+        ]"#,
+        )
+        .unwrap();
+        let mut visiting = Vec::new();
+        assert!(matches!(
+            abi.parse_type("game::Broken", &mut visiting),
+            Err(AbiError::UnknownType(_))
+        ));
+        assert!(visiting.is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::KEY_ATTRIBUTE;
+use crate::abi::{AbiError, AbiResult, AbiTypeDefs, FLAT_ATTRIBUTE};
+use crate::parser::{TypeParserError, TypeParserResult};
+use crate::{
+    Attributes, CairoSerde, DecodeError, EnumDef, FeltSource, IndexedEnums, IntoFeltSource, Member,
+    ParseValue, Struct, StructDef, TypeDef,
+};
+use starknet::core::types::EmittedEvent;
+use starknet_types_core::felt::Felt;
+use std::collections::HashMap;
+
+enum EventNode {
+    Struct(StructDef),
+    Enum(HashMap<Felt, EventNode>),
+}
+
+/// Decodes arbitrary emitted events from the event enum of a contract ABI.
+///
+/// Each non-flat variant consumes one selector from the event keys, flat variants
+/// are merged into their parent. Once a struct event is reached its `#[key]`
+/// members are read from the remaining keys and the others from the data, using
+/// index-based enum decoding.
+pub struct AbiEventDecoder {
+    events: HashMap<Felt, EventNode>,
+}
+
+impl AbiEventDecoder {
+    pub fn new(event_def: &EnumDef) -> TypeParserResult<Self> {
+        let mut events = HashMap::new();
+        add_variants(&mut events, event_def)?;
+        Ok(AbiEventDecoder { events })
+    }
+
+    pub fn from_abi(abi: &AbiTypeDefs, event_name: &str) -> AbiResult<Self> {
+        match abi.event_def(event_name)? {
+            TypeDef::Enum(enum_def) => Ok(Self::new(&enum_def)?),
+            type_def => Err(AbiError::UnsupportedType(type_def.item_name().to_string())),
+        }
+    }
+
+    pub fn decode(&self, keys: &[Felt], data: &[Felt]) -> TypeParserResult<Struct> {
+        let mut keys = IndexedEnums(CairoSerde(keys.into_source()));
+        let mut data = IndexedEnums(CairoSerde(data.into_source()));
+        let mut events = &self.events;
+        let struct_def = loop {
+            let selector = keys.next()?;
+            match events.get(&selector) {
+                Some(EventNode::Struct(struct_def)) => break struct_def,
+                Some(EventNode::Enum(variants)) => events = variants,
+                None => return Err(TypeParserError::invalid_enum_selector("Event", selector)),
+            }
+        };
+        let members = struct_def
+            .members
+            .iter()
+            .map(|member| match member.has_attribute(KEY_ATTRIBUTE) {
+                true => member.parse(&mut keys),
+                false => member.parse(&mut data),
+            })
+            .collect::<TypeParserResult<Vec<Member>>>()?;
+        match (keys.next(), data.next()) {
+            (Err(k), Err(d)) if k.is_eof() && d.is_eof() => Ok(Struct {
+                name: struct_def.name.clone(),
+                attributes: struct_def.attributes.clone(),
+                members,
+            }),
+            (Err(e), _) if !e.is_eof() => Err(e.into()),
+            (_, Err(e)) if !e.is_eof() => Err(e.into()),
+            _ => Err(DecodeError::NotEof.into()),
+        }
+    }
+
+    pub fn decode_event(&self, event: &EmittedEvent) -> TypeParserResult<Struct> {
+        self.decode(&event.keys, &event.data)
+    }
+}
+
+fn add_variants(events: &mut HashMap<Felt, EventNode>, enum_def: &EnumDef) -> TypeParserResult<()> {
+    for selector in &enum_def.order {
+        let variant = enum_def.get_variant(selector)?;
+        match (&variant.type_def, variant.has_attribute(FLAT_ATTRIBUTE)) {
+            (TypeDef::Enum(inner), true) => add_variants(events, inner)?,
+            (TypeDef::Enum(inner), false) => {
+                let mut variants = HashMap::new();
+                add_variants(&mut variants, inner)?;
+                events.insert(*selector, EventNode::Enum(variants));
+            }
+            (TypeDef::Struct(struct_def), _) => {
+                events.insert(*selector, EventNode::Struct(struct_def.clone()));
+            }
+            (type_def, _) => return Err(TypeParserError::unsupported_type(type_def.item_name())),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value;
+    use starknet::core::utils::get_selector_from_name;
+
+    const ABI: &str = r#"[
+        {"type": "enum", "name": "game::Kind", "variants": [
+            {"name": "Warrior", "type": "()"},
+            {"name": "Mage", "type": "core::integer::u8"}
+        ]},
+        {"type": "event", "name": "game::Moved", "kind": "struct", "members": [
+            {"name": "player", "type": "core::starknet::contract_address::ContractAddress", "kind": "key"},
+            {"name": "kind", "type": "game::Kind", "kind": "data"},
+            {"name": "x", "type": "core::integer::u32", "kind": "data"}
+        ]},
+        {"type": "event", "name": "ownable::OwnershipTransferred", "kind": "struct", "members": [
+            {"name": "previous", "type": "core::starknet::contract_address::ContractAddress", "kind": "key"},
+            {"name": "new", "type": "core::starknet::contract_address::ContractAddress", "kind": "key"}
+        ]},
+        {"type": "event", "name": "ownable::Event", "kind": "enum", "variants": [
+            {"name": "OwnershipTransferred", "type": "ownable::OwnershipTransferred", "kind": "nested"}
+        ]},
+        {"type": "event", "name": "game::Event", "kind": "enum", "variants": [
+            {"name": "Moved", "type": "game::Moved", "kind": "nested"},
+            {"name": "OwnableEvent", "type": "ownable::Event", "kind": "flat"}
+        ]}
+    ]"#;
+
+    fn decoder() -> AbiEventDecoder {
+        let abi = AbiTypeDefs::from_json(ABI).unwrap();
+        AbiEventDecoder::from_abi(&abi, "game::Event").unwrap()
+    }
+
+    #[test]
+    fn decodes_nested_event() {
+        let keys = [get_selector_from_name("Moved").unwrap(), Felt::from(0xabc)];
+        let data = [Felt::ONE, Felt::from(3), Felt::from(9)];
+        let value = decoder().decode(&keys, &data).unwrap();
+        assert_eq!(value.name, "Moved");
+        assert_eq!(
+            value.members[0].value,
+            Value::ContractAddress(Felt::from(0xabc))
+        );
+        let Value::Enum(kind) = &value.members[1].value else {
+            panic!("expected enum");
+        };
+        assert_eq!(kind.variant, "Mage");
+        assert_eq!(kind.value, Value::U8(3));
+        assert_eq!(value.members[2].value, Value::U32(9));
+    }
+
+    #[test]
+    fn decodes_flat_event() {
+        let keys = [
+            get_selector_from_name("OwnershipTransferred").unwrap(),
+            Felt::ONE,
+            Felt::TWO,
+        ];
+        let value = decoder().decode(&keys, &[]).unwrap();
+        assert_eq!(value.name, "OwnershipTransferred");
+        assert_eq!(value.members[1].value, Value::ContractAddress(Felt::TWO));
+    }
+
+    #[test]
+    fn rejects_unknown_selector_and_trailing_data() {
+        let decoder = decoder();
+        assert!(matches!(
+            decoder.decode(&[Felt::ONE], &[]),
+            Err(TypeParserError::InvalidEnumSelector { .. })
+        ));
+        let keys = [
+            get_selector_from_name("OwnershipTransferred").unwrap(),
+            Felt::ONE,
+            Felt::TWO,
+        ];
+        assert!(decoder.decode(&keys, &[Felt::ONE]).is_err());
+    }
+}
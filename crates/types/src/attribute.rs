@@ -2,6 +2,9 @@ use std::ops::Deref;
 
 use serde::{Deserialize, Serialize};
 
+/// Marks the key members of events and tables.
+pub const KEY_ATTRIBUTE: &str = "key";
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Attribute {
     pub name: String,
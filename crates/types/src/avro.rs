@@ -0,0 +1,782 @@
+use crate::{
+    CairoOption, CairoResult, EnumDef, Nullable, Record, StructDef, TableSchema, TypeDef, Value,
+    felt_to_hex_string, identifier, pascal_case,
+};
+use serde_json::{Map, Value as Json, json};
+use starknet_types_core::felt::Felt;
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum AvroError {
+    #[error("{0} is not supported in Avro schemas")]
+    Unsupported(&'static str),
+
+    #[error("conflicting definitions for Avro type {0}")]
+    ConflictingType(String),
+
+    #[error("value does not match type {0}")]
+    Mismatch(&'static str),
+
+    #[error("unknown variant {variant} of enum {name}")]
+    UnknownVariant { name: String, variant: String },
+
+    #[error("missing value for column {0}")]
+    MissingColumn(String),
+}
+
+pub type AvroResult<T> = Result<T, AvroError>;
+
+/// Decimal precision of the integers encoded as Avro decimals.
+fn decimal_precision(type_def: &TypeDef) -> Option<u32> {
+    match type_def {
+        TypeDef::U64 => Some(20),
+        TypeDef::U128 | TypeDef::I128 => Some(39),
+        TypeDef::U256 => Some(78),
+        TypeDef::U512 => Some(155),
+        _ => None,
+    }
+}
+
+fn fixed_size(type_def: &TypeDef) -> Option<(&'static str, usize)> {
+    match type_def {
+        TypeDef::Felt252 => Some(("Felt252", 32)),
+        TypeDef::ClassHash => Some(("ClassHash", 32)),
+        TypeDef::ContractAddress => Some(("ContractAddress", 32)),
+        TypeDef::StorageAddress => Some(("StorageAddress", 32)),
+        TypeDef::StorageBaseAddress => Some(("StorageBaseAddress", 32)),
+        TypeDef::EthAddress => Some(("EthAddress", 20)),
+        TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => Some(("Bytes31", 31)),
+        _ => None,
+    }
+}
+
+/// Types whose schema is plain `"null"`, options of nothing carrying no data.
+fn is_null(type_def: &TypeDef) -> bool {
+    match type_def {
+        TypeDef::None => true,
+        TypeDef::Option(option) => is_null(&option.type_def),
+        TypeDef::Nullable(nullable) => is_null(&nullable.type_def),
+        _ => false,
+    }
+}
+
+fn is_union(type_def: &TypeDef) -> bool {
+    match type_def {
+        TypeDef::Option(_) | TypeDef::Nullable(_) => !is_null(type_def),
+        TypeDef::Result(_) => true,
+        TypeDef::Enum(enum_def) => !is_plain_enum(enum_def),
+        _ => false,
+    }
+}
+
+/// Enums without payloads map to Avro enums, others to a union of records.
+fn is_plain_enum(enum_def: &EnumDef) -> bool {
+    enum_def
+        .variants
+        .values()
+        .all(|variant| variant.type_def == TypeDef::None)
+}
+
+fn variants(enum_def: &EnumDef) -> impl Iterator<Item = &crate::VariantDef> {
+    enum_def
+        .order
+        .iter()
+        .filter_map(|selector| enum_def.variants.get(selector))
+}
+
+fn record(name: &str, fields: Vec<Json>) -> Json {
+    json!({"type": "record", "name": name, "fields": fields})
+}
+
+/// Builds Avro schemas from `TypeDef`s and tables.
+///
+/// Structs and tuples become records, payload free enums Avro enums and other
+/// enums a union of one record per variant. Options, nullables and results are
+/// unions, with a wrapping record when the payload is itself a union. Felts,
+/// addresses and `bytes31` are `fixed`, integers over 64 bits (and `u64`) are
+/// `decimal` bytes. Named types are defined on first use in each schema and
+/// referenced by name afterwards. `Ref`s must be expanded beforehand.
+#[derive(Default)]
+pub struct AvroGenerator {
+    namespace: Option<String>,
+    named: BTreeMap<String, Json>,
+}
+
+impl AvroGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_namespace(namespace: &str) -> Self {
+        AvroGenerator {
+            namespace: Some(namespace.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Record schema of a table. Every column has a default so that records
+    /// written before an `AddColumn` can be read with the new schema.
+    pub fn table(&mut self, table: &TableSchema) -> AvroResult<Json> {
+        self.named.clear();
+        let name = identifier(&pascal_case(&table.name));
+        let primary = TypeDef::from(&table.primary.type_def);
+        let mut fields = vec![json!({
+            "name": identifier(&table.primary.name),
+            "type": self.type_schema(&primary, &name)?,
+        })];
+        for column in &table.columns {
+            let context = format!("{name}{}", pascal_case(&column.name));
+            fields.push(json!({
+                "name": identifier(&column.name),
+                "type": self.type_schema(&column.type_def, &context)?,
+                "default": default_value(&column.type_def)?,
+            }));
+        }
+        let mut schema = record(&name, fields);
+        if let Some(namespace) = &self.namespace {
+            schema["namespace"] = namespace.as_str().into();
+        }
+        Ok(schema)
+    }
+
+    /// Schema of a type, `context` naming anonymous records.
+    pub fn schema(&mut self, type_def: &TypeDef, context: &str) -> AvroResult<Json> {
+        self.named.clear();
+        self.type_schema(type_def, context)
+    }
+
+    fn type_schema(&mut self, type_def: &TypeDef, context: &str) -> AvroResult<Json> {
+        if let Some((name, size)) = fixed_size(type_def) {
+            return self.named(name, json!({"type": "fixed", "name": name, "size": size}));
+        }
+        if let Some(precision) = decimal_precision(type_def) {
+            return Ok(json!({
+                "type": "bytes",
+                "logicalType": "decimal",
+                "precision": precision,
+                "scale": 0,
+            }));
+        }
+        Ok(match type_def {
+            TypeDef::None => json!("null"),
+            TypeDef::Bool => json!("boolean"),
+            TypeDef::U8 | TypeDef::U16 | TypeDef::I8 | TypeDef::I16 | TypeDef::I32 => json!("int"),
+            TypeDef::U32 | TypeDef::I64 => json!("long"),
+            TypeDef::ShortUtf8 | TypeDef::Utf8String => json!("string"),
+            TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) => json!("bytes"),
+            TypeDef::Custom(_) => {
+                json!({"type": "array", "items": self.type_schema(&TypeDef::Felt252, context)?})
+            }
+            TypeDef::Array(array) => {
+                json!({"type": "array", "items": self.type_schema(&array.type_def, &format!("{context}Item"))?})
+            }
+            TypeDef::FixedArray(fixed) => {
+                json!({"type": "array", "items": self.type_schema(&fixed.type_def, &format!("{context}Item"))?})
+            }
+            TypeDef::Felt252Dict(dict) => {
+                json!({"type": "map", "values": self.type_schema(&dict.type_def, &format!("{context}Value"))?})
+            }
+            TypeDef::Tuple(tuple) => {
+                let name = identifier(context);
+                let fields = tuple
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element)| {
+                        let schema = self.type_schema(element, &format!("{name}Item{i}"))?;
+                        Ok(json!({"name": format!("item_{i}"), "type": schema}))
+                    })
+                    .collect::<AvroResult<_>>()?;
+                self.named(&name, record(&name, fields))?
+            }
+            TypeDef::Struct(struct_def) => self.struct_schema(struct_def)?,
+            TypeDef::Enum(enum_def) => self.enum_schema(enum_def)?,
+            TypeDef::Option(_) | TypeDef::Nullable(_) if is_null(type_def) => json!("null"),
+            TypeDef::Option(option) => json!(["null", self.optional(&option.type_def, context)?]),
+            TypeDef::Nullable(nullable) => {
+                json!(["null", self.optional(&nullable.type_def, context)?])
+            }
+            TypeDef::Result(result) => json!([
+                self.value_record(&format!("{}Ok", identifier(context)), &result.ok)?,
+                self.value_record(&format!("{}Err", identifier(context)), &result.err)?,
+            ]),
+            TypeDef::Ref(_) => return Err(AvroError::Unsupported("Ref")),
+            _ => unreachable!("fixed and decimal types are handled above"),
+        })
+    }
+
+    fn optional(&mut self, type_def: &TypeDef, context: &str) -> AvroResult<Json> {
+        match is_union(type_def) {
+            true => self.value_record(&format!("{}Value", identifier(context)), type_def),
+            false => self.type_schema(type_def, context),
+        }
+    }
+
+    /// A record with a single `value` field, or no field for `None`.
+    fn value_record(&mut self, name: &str, type_def: &TypeDef) -> AvroResult<Json> {
+        let fields = match type_def {
+            TypeDef::None => vec![],
+            _ => vec![json!({"name": "value", "type": self.type_schema(type_def, name)?})],
+        };
+        self.named(name, record(name, fields))
+    }
+
+    fn struct_schema(&mut self, struct_def: &StructDef) -> AvroResult<Json> {
+        let name = identifier(&struct_def.name);
+        let fields = struct_def
+            .members
+            .iter()
+            .map(|member| {
+                let context = format!("{name}{}", pascal_case(&member.name));
+                let schema = self.type_schema(&member.type_def, &context)?;
+                Ok(json!({"name": identifier(&member.name), "type": schema}))
+            })
+            .collect::<AvroResult<_>>()?;
+        self.named(&name, record(&name, fields))
+    }
+
+    fn enum_schema(&mut self, enum_def: &EnumDef) -> AvroResult<Json> {
+        let name = identifier(&enum_def.name);
+        if is_plain_enum(enum_def) {
+            let symbols: Vec<String> = variants(enum_def).map(|v| identifier(&v.name)).collect();
+            return self.named(
+                &name,
+                json!({"type": "enum", "name": name, "symbols": symbols}),
+            );
+        }
+        variants(enum_def)
+            .map(|variant| {
+                let record_name = format!("{name}{}", pascal_case(&variant.name));
+                self.value_record(&record_name, &variant.type_def)
+            })
+            .collect::<AvroResult<Vec<_>>>()
+            .map(Json::Array)
+    }
+
+    /// Returns the definition on first use and the name afterwards.
+    fn named(&mut self, name: &str, definition: Json) -> AvroResult<Json> {
+        match self.named.get(name) {
+            Some(existing) if *existing != definition => {
+                Err(AvroError::ConflictingType(name.to_string()))
+            }
+            Some(_) => Ok(json!(name)),
+            None => {
+                self.named.insert(name.to_string(), definition.clone());
+                Ok(definition)
+            }
+        }
+    }
+}
+
+/// Default of a field of the type, the first branch for unions.
+pub fn default_value(type_def: &TypeDef) -> AvroResult<Json> {
+    if let Some((_, size)) = fixed_size(type_def) {
+        return Ok(json!("\u{0}".repeat(size)));
+    }
+    if decimal_precision(type_def).is_some() {
+        return Ok(json!("\u{0}"));
+    }
+    Ok(match type_def {
+        TypeDef::None | TypeDef::Option(_) | TypeDef::Nullable(_) => Json::Null,
+        TypeDef::Bool => json!(false),
+        TypeDef::U8
+        | TypeDef::U16
+        | TypeDef::U32
+        | TypeDef::I8
+        | TypeDef::I16
+        | TypeDef::I32
+        | TypeDef::I64 => json!(0),
+        TypeDef::ShortUtf8
+        | TypeDef::Utf8String
+        | TypeDef::ByteArray
+        | TypeDef::ByteArrayEncoded(_) => json!(""),
+        TypeDef::Custom(_) | TypeDef::Array(_) | TypeDef::FixedArray(_) => json!([]),
+        TypeDef::Felt252Dict(_) => json!({}),
+        TypeDef::Tuple(tuple) => Json::Object(
+            tuple
+                .elements
+                .iter()
+                .enumerate()
+                .map(|(i, element)| Ok((format!("item_{i}"), default_value(element)?)))
+                .collect::<AvroResult<_>>()?,
+        ),
+        TypeDef::Struct(struct_def) => Json::Object(
+            struct_def
+                .members
+                .iter()
+                .map(|member| Ok((identifier(&member.name), default_value(&member.type_def)?)))
+                .collect::<AvroResult<_>>()?,
+        ),
+        TypeDef::Enum(enum_def) => {
+            let Some(first) = variants(enum_def).next() else {
+                return Err(AvroError::Unsupported("empty enum"));
+            };
+            match is_plain_enum(enum_def) {
+                true => json!(identifier(&first.name)),
+                false => value_record_default(&first.type_def)?,
+            }
+        }
+        TypeDef::Result(result) => value_record_default(&result.ok)?,
+        TypeDef::Ref(_) => return Err(AvroError::Unsupported("Ref")),
+        _ => unreachable!("fixed and decimal types are handled above"),
+    })
+}
+
+fn value_record_default(type_def: &TypeDef) -> AvroResult<Json> {
+    let mut fields = Map::new();
+    if *type_def != TypeDef::None {
+        fields.insert("value".to_string(), default_value(type_def)?);
+    }
+    Ok(Json::Object(fields))
+}
+
+/// Whether data written with the Avro schema of `writer` can be read with the
+/// schema of `reader`, following the Avro schema resolution rules.
+pub fn avro_can_read(writer: &TypeDef, reader: &TypeDef) -> bool {
+    if writer == reader {
+        return true;
+    }
+    if let (Some(w), Some(r)) = (decimal_precision(writer), decimal_precision(reader)) {
+        return w <= r;
+    }
+    if let (Some(w), Some(r)) = (fixed_size(writer), fixed_size(reader)) {
+        return w == r;
+    }
+    let is_int = |t: &TypeDef| {
+        matches!(
+            t,
+            TypeDef::U8 | TypeDef::U16 | TypeDef::I8 | TypeDef::I16 | TypeDef::I32
+        )
+    };
+    let is_long = |t: &TypeDef| matches!(t, TypeDef::U32 | TypeDef::I64);
+    let is_text = |t: &TypeDef| {
+        matches!(
+            t,
+            TypeDef::ShortUtf8
+                | TypeDef::Utf8String
+                | TypeDef::ByteArray
+                | TypeDef::ByteArrayEncoded(_)
+        )
+    };
+    match (writer, reader) {
+        (TypeDef::Option(_) | TypeDef::Nullable(_), TypeDef::Option(_) | TypeDef::Nullable(_)) => {
+            avro_can_read(optional_inner(writer), optional_inner(reader))
+        }
+        (w, TypeDef::Option(_) | TypeDef::Nullable(_)) if !is_union(w) => {
+            avro_can_read(w, optional_inner(reader))
+        }
+        (w, r) if is_int(w) => is_int(r) || is_long(r),
+        (w, r) if is_long(w) => is_long(r),
+        (w, r) if is_text(w) => is_text(r),
+        (
+            TypeDef::Array(_) | TypeDef::FixedArray(_),
+            TypeDef::Array(_) | TypeDef::FixedArray(_),
+        ) => avro_can_read(array_items(writer), array_items(reader)),
+        (TypeDef::Felt252Dict(w), TypeDef::Felt252Dict(r)) => {
+            avro_can_read(&w.type_def, &r.type_def)
+        }
+        (TypeDef::Struct(w), TypeDef::Struct(r)) => {
+            identifier(&w.name) == identifier(&r.name)
+                && r.members.iter().all(|reader_member| {
+                    w.members
+                        .iter()
+                        .find(|m| m.name == reader_member.name)
+                        .is_some_and(|m| avro_can_read(&m.type_def, &reader_member.type_def))
+                })
+        }
+        (TypeDef::Enum(w), TypeDef::Enum(r)) => {
+            identifier(&w.name) == identifier(&r.name)
+                && is_plain_enum(w) == is_plain_enum(r)
+                && variants(w).all(|wv| {
+                    variants(r)
+                        .any(|rv| rv.name == wv.name && avro_can_read(&wv.type_def, &rv.type_def))
+                })
+        }
+        (TypeDef::Result(w), TypeDef::Result(r)) => {
+            avro_can_read(&w.ok, &r.ok) && avro_can_read(&w.err, &r.err)
+        }
+        _ => false,
+    }
+}
+
+fn array_items(type_def: &TypeDef) -> &TypeDef {
+    match type_def {
+        TypeDef::Array(array) => &array.type_def,
+        TypeDef::FixedArray(fixed) => &fixed.type_def,
+        type_def => type_def,
+    }
+}
+
+fn optional_inner(type_def: &TypeDef) -> &TypeDef {
+    match type_def {
+        TypeDef::Option(option) => &option.type_def,
+        TypeDef::Nullable(nullable) => &nullable.type_def,
+        type_def => type_def,
+    }
+}
+
+fn write_long(buf: &mut Vec<u8>, value: i64) {
+    let mut n = ((value << 1) ^ (value >> 63)) as u64;
+    while n >= 0x80 {
+        buf.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    buf.push(n as u8);
+}
+
+fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_long(buf, bytes.len() as i64);
+    buf.extend_from_slice(bytes);
+}
+
+/// Writes big endian bytes as the shortest two's complement decimal.
+fn write_decimal(buf: &mut Vec<u8>, bytes: &[u8], signed: bool) {
+    let negative = signed && bytes.first().is_some_and(|b| b & 0x80 != 0);
+    let pad = if negative { 0xff } else { 0 };
+    let mut start = 0;
+    while start + 1 < bytes.len()
+        && bytes[start] == pad
+        && (bytes[start + 1] & 0x80 != 0) == negative
+    {
+        start += 1;
+    }
+    match !negative && bytes[start] & 0x80 != 0 {
+        true => write_bytes(buf, &[&[0], &bytes[start..]].concat()),
+        false => write_bytes(buf, &bytes[start..]),
+    }
+}
+
+fn write_felts(buf: &mut Vec<u8>, felts: &[Felt]) {
+    if !felts.is_empty() {
+        write_long(buf, felts.len() as i64);
+        for felt in felts {
+            buf.extend_from_slice(&felt.to_bytes_be());
+        }
+    }
+    write_long(buf, 0);
+}
+
+fn felt_of(value: &Value) -> Option<Felt> {
+    match value {
+        Value::Felt252(felt)
+        | Value::ClassHash(felt)
+        | Value::ContractAddress(felt)
+        | Value::StorageAddress(felt)
+        | Value::StorageBaseAddress(felt)
+        | Value::EthAddress(felt) => Some(*felt),
+        _ => None,
+    }
+}
+
+/// Appends the Avro binary encoding of `value` under the schema `AvroGenerator`
+/// builds for `type_def`.
+pub fn encode_value(type_def: &TypeDef, value: &Value, buf: &mut Vec<u8>) -> AvroResult<()> {
+    let mismatch = || AvroError::Mismatch(type_def.item_name());
+    match (type_def, value) {
+        (TypeDef::None, Value::None) => {}
+        (TypeDef::EthAddress, value) => {
+            let felt = felt_of(value).ok_or_else(mismatch)?;
+            buf.extend_from_slice(&felt.to_bytes_be()[12..]);
+        }
+        (
+            TypeDef::Felt252
+            | TypeDef::ClassHash
+            | TypeDef::ContractAddress
+            | TypeDef::StorageAddress
+            | TypeDef::StorageBaseAddress,
+            value,
+        ) => buf.extend_from_slice(&felt_of(value).ok_or_else(mismatch)?.to_bytes_be()),
+        (TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_), Value::Bytes31(bytes)) => {
+            buf.extend_from_slice(bytes)
+        }
+        (TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_), Value::Bytes31Encoded(encoded)) => {
+            buf.extend_from_slice(&encoded.bytes)
+        }
+        (TypeDef::Bool, Value::Bool(value)) => buf.push(*value as u8),
+        (TypeDef::U8, Value::U8(value)) => write_long(buf, *value as i64),
+        (TypeDef::U16, Value::U16(value)) => write_long(buf, *value as i64),
+        (TypeDef::U32, Value::U32(value)) => write_long(buf, *value as i64),
+        (TypeDef::I8, Value::I8(value)) => write_long(buf, *value as i64),
+        (TypeDef::I16, Value::I16(value)) => write_long(buf, *value as i64),
+        (TypeDef::I32, Value::I32(value)) => write_long(buf, *value as i64),
+        (TypeDef::I64, Value::I64(value)) => write_long(buf, *value),
+        (TypeDef::U64, Value::U64(value)) => write_decimal(buf, &value.to_be_bytes(), false),
+        (TypeDef::U128, Value::U128(value)) => write_decimal(buf, &value.to_be_bytes(), false),
+        (TypeDef::I128, Value::I128(value)) => write_decimal(buf, &value.to_be_bytes(), true),
+        (TypeDef::U256, Value::U256(value)) => write_decimal(buf, &value.to_big_endian(), false),
+        (TypeDef::U512, Value::U512(value)) => write_decimal(buf, &value.to_big_endian(), false),
+        (TypeDef::ShortUtf8 | TypeDef::Utf8String, Value::ShortUtf8(s) | Value::Utf8String(s)) => {
+            write_bytes(buf, s.as_bytes())
+        }
+        (TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_), Value::ByteArray(bytes)) => {
+            write_bytes(buf, bytes)
+        }
+        (TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_), Value::ByteArrayEncoded(encoded)) => {
+            write_bytes(buf, &encoded.bytes)
+        }
+        (TypeDef::Custom(_), Value::Custom(custom)) => write_felts(buf, &custom.values),
+        (TypeDef::Tuple(tuple), Value::Tuple(values)) => {
+            if tuple.elements.len() != values.len() {
+                return Err(mismatch());
+            }
+            for (element, value) in tuple.elements.iter().zip(values) {
+                encode_value(element, value, buf)?;
+            }
+        }
+        (TypeDef::Array(array), Value::Array(values) | Value::FixedArray(values)) => {
+            encode_array(&array.type_def, values, buf)?
+        }
+        (TypeDef::FixedArray(fixed), Value::Array(values) | Value::FixedArray(values)) => {
+            encode_array(&fixed.type_def, values, buf)?
+        }
+        (TypeDef::Felt252Dict(dict), Value::Felt252Dict(entries)) => {
+            if !entries.is_empty() {
+                write_long(buf, entries.len() as i64);
+                for (key, value) in entries {
+                    write_bytes(buf, felt_to_hex_string(key).as_bytes());
+                    encode_value(&dict.type_def, value, buf)?;
+                }
+            }
+            write_long(buf, 0);
+        }
+        (TypeDef::Struct(struct_def), Value::Struct(value)) => {
+            if struct_def.members.len() != value.members.len() {
+                return Err(mismatch());
+            }
+            for (member, value) in struct_def.members.iter().zip(&value.members) {
+                encode_value(&member.type_def, &value.value, buf)?;
+            }
+        }
+        (TypeDef::Enum(enum_def), Value::Enum(value)) => {
+            let (index, variant) = variants(enum_def)
+                .enumerate()
+                .find(|(_, variant)| variant.name == value.variant)
+                .ok_or_else(|| AvroError::UnknownVariant {
+                    name: enum_def.name.clone(),
+                    variant: value.variant.clone(),
+                })?;
+            write_long(buf, index as i64);
+            if !is_plain_enum(enum_def) {
+                encode_value(&variant.type_def, &value.value, buf)?;
+            }
+        }
+        (TypeDef::Option(_), Value::Option(_)) | (TypeDef::Nullable(_), Value::Nullable(_))
+            if is_null(type_def) => {}
+        (TypeDef::Option(option), Value::Option(value)) => match value.as_ref() {
+            CairoOption::Some(value) => {
+                write_long(buf, 1);
+                encode_value(&option.type_def, value, buf)?;
+            }
+            CairoOption::None => write_long(buf, 0),
+        },
+        (TypeDef::Nullable(nullable), Value::Nullable(value)) => match value.as_ref() {
+            Nullable::NotNull(value) => {
+                write_long(buf, 1);
+                encode_value(&nullable.type_def, value, buf)?;
+            }
+            Nullable::Null => write_long(buf, 0),
+        },
+        (TypeDef::Result(result), Value::Result(value)) => match value.as_ref() {
+            CairoResult::Ok(value) => {
+                write_long(buf, 0);
+                encode_value(&result.ok, value, buf)?;
+            }
+            CairoResult::Err(value) => {
+                write_long(buf, 1);
+                encode_value(&result.err, value, buf)?;
+            }
+        },
+        (TypeDef::Ref(_), _) => return Err(AvroError::Unsupported("Ref")),
+        _ => return Err(mismatch()),
+    }
+    Ok(())
+}
+
+fn encode_array(type_def: &TypeDef, values: &[Value], buf: &mut Vec<u8>) -> AvroResult<()> {
+    if !values.is_empty() {
+        write_long(buf, values.len() as i64);
+        for value in values {
+            encode_value(type_def, value, buf)?;
+        }
+    }
+    write_long(buf, 0);
+    Ok(())
+}
+
+/// Avro binary encoding of a record under the schema of `AvroGenerator::table`.
+pub fn encode_record(table: &TableSchema, record: &Record) -> AvroResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    let primary = TypeDef::from(&table.primary.type_def);
+    encode_value(&primary, &record.primary.value.clone().into(), &mut buf)?;
+    for column in &table.columns {
+        let field = record
+            .fields
+            .iter()
+            .find(|field| field.id == column.id)
+            .ok_or_else(|| AvroError::MissingColumn(column.name.clone()))?;
+        encode_value(&column.type_def, &field.value, &mut buf)?;
+    }
+    Ok(buf)
+}
+
+impl TableSchema {
+    /// Avro record schema of the table, see `AvroGenerator::table`.
+    pub fn avro_schema(&self) -> AvroResult<Json> {
+        AvroGenerator::new().table(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArrayDef, ColumnDef, Enum, Field, OptionDef, Primary, PrimaryDef, PrimaryTypeDef,
+        PrimaryValue, VariantDef,
+    };
+    use primitive_types::U256;
+
+    fn direction() -> TypeDef {
+        EnumDef::new_type_def(
+            "Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::ONE,
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::TWO,
+                    VariantDef::new("Jump".to_string(), vec![], TypeDef::U8),
+                ),
+            ],
+        )
+    }
+
+    fn player() -> TableSchema {
+        TableSchema {
+            id: Felt::ONE,
+            name: "player".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("owner".to_string(), vec![], PrimaryTypeDef::EthAddress),
+            columns: vec![
+                ColumnDef::new(Felt::ONE, "gold".to_string(), vec![], TypeDef::U256),
+                ColumnDef::new(
+                    Felt::TWO,
+                    "moves".to_string(),
+                    vec![],
+                    ArrayDef::new_type_def(OptionDef::new_type_def(direction())),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn maps_tables_to_avro_schemas() {
+        let schema = player().avro_schema().unwrap();
+        assert_eq!(schema["name"], "Player");
+        assert_eq!(
+            schema["fields"][0],
+            json!({"name": "owner", "type": {"type": "fixed", "name": "EthAddress", "size": 20}})
+        );
+        assert_eq!(schema["fields"][1]["type"]["logicalType"], "decimal");
+        assert_eq!(schema["fields"][1]["type"]["precision"], 78);
+        assert_eq!(schema["fields"][1]["default"], "\u{0}");
+        let moves = &schema["fields"][2];
+        assert_eq!(moves["default"], json!([]));
+        assert_eq!(moves["type"]["items"][0], "null");
+        assert_eq!(
+            moves["type"]["items"][1]["type"], "record",
+            "option of an enum union is wrapped"
+        );
+        let branches = &moves["type"]["items"][1]["fields"][0]["type"];
+        assert_eq!(
+            branches[0],
+            json!({"type": "record", "name": "DirectionUp", "fields": []})
+        );
+        assert_eq!(
+            branches[1]["fields"][0],
+            json!({"name": "value", "type": "int"})
+        );
+
+        assert!(avro_can_read(&TypeDef::U8, &TypeDef::I64));
+        assert!(!avro_can_read(&TypeDef::I64, &TypeDef::I32));
+        assert!(avro_can_read(&TypeDef::U64, &TypeDef::U256));
+        assert!(avro_can_read(
+            &TypeDef::U16,
+            &OptionDef::new_type_def(TypeDef::U32)
+        ));
+        assert!(!avro_can_read(&direction(), &TypeDef::U8));
+
+        let mut generator = AvroGenerator::new();
+        let first = generator.table(&player()).unwrap();
+        assert_eq!(generator.table(&player()).unwrap(), first);
+        assert_eq!(
+            generator.schema(&direction(), "Direction").unwrap(),
+            generator.schema(&direction(), "Direction").unwrap()
+        );
+        let unit = OptionDef::new_type_def(TypeDef::None);
+        assert_eq!(generator.schema(&unit, "Unit").unwrap(), json!("null"));
+        let mut buf = Vec::new();
+        encode_value(&unit, &Value::Option(Box::new(CairoOption::None)), &mut buf).unwrap();
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn encodes_records_in_avro_binary() {
+        let jump = |height| {
+            Value::Option(Box::new(CairoOption::Some(Value::Enum(Box::new(Enum {
+                name: "Direction".to_string(),
+                attributes: vec![],
+                variant: "Jump".to_string(),
+                variant_attributes: vec![],
+                value: Value::U8(height),
+            })))))
+        };
+        let record = Record {
+            table_id: Felt::ONE,
+            table_name: "player".to_string(),
+            attributes: vec![],
+            primary: Primary {
+                name: "owner".to_string(),
+                attributes: vec![],
+                value: PrimaryValue::EthAddress(Felt::from(0xabcd)),
+            },
+            fields: vec![
+                Field {
+                    id: Felt::TWO,
+                    name: "moves".to_string(),
+                    attributes: vec![],
+                    value: Value::Array(vec![jump(3), Value::Option(Box::new(CairoOption::None))]),
+                },
+                Field {
+                    id: Felt::ONE,
+                    name: "gold".to_string(),
+                    attributes: vec![],
+                    value: Value::U256(U256::from(200)),
+                },
+            ],
+        };
+        let mut owner = vec![0; 18];
+        owner.extend([0xab, 0xcd]);
+        let expected = [
+            owner,
+            // gold: 200 needs a sign byte
+            vec![4, 0x00, 0xc8],
+            // moves: one block of 2, Some(Jump(3)), None, end
+            vec![4, 2, 2, 6, 0, 0],
+        ]
+        .concat();
+        assert_eq!(encode_record(&player(), &record).unwrap(), expected);
+
+        let mut missing = record.clone();
+        missing.fields.pop();
+        assert_eq!(
+            encode_record(&player(), &missing),
+            Err(AvroError::MissingColumn("gold".to_string()))
+        );
+        let mut buf = Vec::new();
+        encode_value(&TypeDef::I128, &Value::I128(-129), &mut buf).unwrap();
+        assert_eq!(buf, vec![4, 0xff, 0x7f]);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::decode_error::DecodeResultTrait;
+use crate::limits::{DecodeBudget, capped_capacity};
+use crate::path::DecodePath;
 use crate::{ByteArray, CairoDeserializer, DecodeError, DecodeResult};
 use starknet_types_core::felt::Felt;
 use std::ops::Deref;
@@ -16,6 +18,12 @@ pub trait ByteSource {
         Ok(bytes)
     }
     fn position(&self) -> usize;
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        None
+    }
+    fn path(&mut self) -> Option<&mut DecodePath> {
+        None
+    }
 }
 
 impl<S: ByteSource + ?Sized> ByteSource for &mut S {
@@ -33,6 +41,16 @@ impl<S: ByteSource + ?Sized> ByteSource for &mut S {
     fn position(&self) -> usize {
         (**self).position()
     }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        (**self).budget()
+    }
+
+    #[inline]
+    fn path(&mut self) -> Option<&mut DecodePath> {
+        (**self).path()
+    }
 }
 
 pub struct DerefBytesSource<B: Deref<Target = [u8]>> {
@@ -128,6 +146,15 @@ impl<B: ByteSource> CairoDeserializer for B {
     fn next_felt(&mut self) -> DecodeResult<Felt> {
         Ok(Felt::from_bytes_be(&self.nexts::<32>()?))
     }
+    fn decode_budget(&mut self) -> Option<&mut DecodeBudget> {
+        ByteSource::budget(self)
+    }
+    fn decode_path(&mut self) -> Option<&mut DecodePath> {
+        ByteSource::path(self)
+    }
+    fn source_position(&self) -> Option<usize> {
+        Some(ByteSource::position(self))
+    }
     fn next_byte(&mut self) -> DecodeResult<u8> {
         self.next()
     }
@@ -145,12 +172,22 @@ impl<B: ByteSource> CairoDeserializer for B {
         Self: Sized,
     {
         let len = self.next_u32()? as usize;
-        let mut bytes = Vec::with_capacity(len);
+        self.check_byte_array_len(len)?;
+        let mut bytes = Vec::with_capacity(capped_capacity(len));
         for _ in 0..len {
             bytes.push(self.next_byte().raise_eof()?);
         }
         Ok(bytes)
     }
+    /// Byte sources report the felts the byte array uses in the Serde encoding.
+    fn skip_byte_array(&mut self) -> DecodeResult<usize> {
+        let len = self.next_u32()? as usize;
+        self.check_byte_array_len(len)?;
+        for _ in 0..len {
+            self.next_byte().raise_eof()?;
+        }
+        Ok(len / 31 + 3)
+    }
     fn next_i8(&mut self) -> DecodeResult<i8> {
         Ok(self.next_byte()? as i8)
     }
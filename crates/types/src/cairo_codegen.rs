@@ -0,0 +1,545 @@
+use crate::{Attribute, EnumDef, GetRefTypeDef, StructDef, TableSchema, TypeDef, cairo_ident};
+use starknet::core::utils::starknet_keccak;
+use starknet_types_core::felt::Felt;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CairoCodegenError {
+    #[error("unknown type reference: {0:#x}")]
+    UnknownRef(Felt),
+
+    #[error("conflicting definitions for Cairo item {0}")]
+    ConflictingType(String),
+
+    #[error("{0} has no Cairo declaration")]
+    Unsupported(&'static str),
+
+    #[error(
+        "selector {selector:#x} of variant {variant} in {item} is not the hash of its identifier"
+    )]
+    SelectorMismatch {
+        item: String,
+        variant: String,
+        selector: Felt,
+    },
+
+    #[error("id {id:#x} of column {column} in {table} is not the hash of its identifier")]
+    ColumnIdMismatch {
+        table: String,
+        column: String,
+        id: Felt,
+    },
+}
+
+pub type CairoCodegenResult<T> = Result<T, CairoCodegenError>;
+
+fn keccak(name: &str) -> Felt {
+    starknet_keccak(name.as_bytes())
+}
+
+/// Generated Cairo source, one declaration per named item.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CairoModule {
+    pub items: BTreeMap<String, String>,
+}
+
+impl Display for CairoModule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        for (index, item) in self.items.values().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{item}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A member or variant: its identifier and the attribute lines above it.
+struct Field {
+    ident: String,
+    attributes: Vec<String>,
+    ty: Option<String>,
+}
+
+/// Emits Cairo declarations that the introspect macros turn back into the
+/// same `TypeDef`s, for reusing models declared by another project.
+///
+/// Structs and enums derive `TypeDef` (`TypeDefRef` when reached through a
+/// `Ref`) and `ISerde`. Members and variants get `#[name(...)]` when their
+/// name is not a valid identifier, `#[raw]` or `#[encoded(...)]` for byte
+/// types other than the default `bytes31` short string and `ByteArray`
+/// string, and one attribute per introspect attribute with UTF-8 data.
+/// The macros derive variant selectors and column ids from identifiers, not
+/// from `#[name(...)]`, so definitions whose ids are not the hashes of the
+/// emitted identifiers are rejected, as are custom types, dictionaries and raw
+/// or encoded bytes nested in containers. Item names keep their last path
+/// segment.
+#[derive(Default)]
+pub struct CairoGenerator<'a> {
+    refs: Option<&'a dyn GetRefTypeDef>,
+    in_progress: HashSet<String>,
+    module: CairoModule,
+}
+
+impl<'a> CairoGenerator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_refs(refs: &'a dyn GetRefTypeDef) -> Self {
+        CairoGenerator {
+            refs: Some(refs),
+            ..Default::default()
+        }
+    }
+
+    pub fn finish(self) -> CairoModule {
+        self.module
+    }
+
+    /// Cairo type of `type_def`, adding the items it needs to the module.
+    pub fn cairo_type(&mut self, type_def: &TypeDef) -> CairoCodegenResult<String> {
+        self.map(type_def, false)
+    }
+
+    /// Adds a struct named after the table with the primary as its `#[key]`
+    /// member followed by the columns. Only the struct is emitted, declaring
+    /// the table itself is left to the project using it.
+    pub fn table_struct(&mut self, table: &TableSchema) -> CairoCodegenResult<String> {
+        let name = cairo_ident(&table.name);
+        let mut primary = self.field(
+            &table.primary.name,
+            &table.primary.attributes,
+            &TypeDef::from(&table.primary.type_def),
+        )?;
+        primary.attributes.insert(0, "#[key]".to_string());
+        let mut fields = vec![primary];
+        for column in &table.columns {
+            if column.id != keccak(&cairo_ident(&column.name)) {
+                return Err(CairoCodegenError::ColumnIdMismatch {
+                    table: table.name.clone(),
+                    column: column.name.clone(),
+                    id: column.id,
+                });
+            }
+            fields.push(self.field(&column.name, &column.attributes, &column.type_def)?);
+        }
+        self.add_struct(&name, "TypeDef", &fields)?;
+        Ok(name)
+    }
+
+    fn map(&mut self, type_def: &TypeDef, reference: bool) -> CairoCodegenResult<String> {
+        Ok(match type_def {
+            TypeDef::None => "()".to_string(),
+            TypeDef::Felt252 => "felt252".to_string(),
+            TypeDef::ShortUtf8 => "bytes31".to_string(),
+            TypeDef::Utf8String => "ByteArray".to_string(),
+            TypeDef::Bool => "bool".to_string(),
+            TypeDef::U8 => "u8".to_string(),
+            TypeDef::U16 => "u16".to_string(),
+            TypeDef::U32 => "u32".to_string(),
+            TypeDef::U64 => "u64".to_string(),
+            TypeDef::U128 => "u128".to_string(),
+            TypeDef::U256 => "u256".to_string(),
+            TypeDef::U512 => "core::integer::u512".to_string(),
+            TypeDef::I8 => "i8".to_string(),
+            TypeDef::I16 => "i16".to_string(),
+            TypeDef::I32 => "i32".to_string(),
+            TypeDef::I64 => "i64".to_string(),
+            TypeDef::I128 => "i128".to_string(),
+            TypeDef::ClassHash => "starknet::ClassHash".to_string(),
+            TypeDef::ContractAddress => "starknet::ContractAddress".to_string(),
+            TypeDef::EthAddress => "starknet::EthAddress".to_string(),
+            TypeDef::StorageAddress => "starknet::StorageAddress".to_string(),
+            TypeDef::StorageBaseAddress => {
+                "starknet::storage_access::StorageBaseAddress".to_string()
+            }
+            TypeDef::Bytes31
+            | TypeDef::Bytes31Encoded(_)
+            | TypeDef::ByteArray
+            | TypeDef::ByteArrayEncoded(_) => {
+                return Err(CairoCodegenError::Unsupported(
+                    "raw or encoded bytes inside a container",
+                ));
+            }
+            TypeDef::Tuple(tuple) => {
+                let elements = tuple
+                    .elements
+                    .iter()
+                    .map(|element| self.map(element, false))
+                    .collect::<CairoCodegenResult<Vec<_>>>()?;
+                match elements.as_slice() {
+                    [element] => format!("({element},)"),
+                    elements => format!("({})", elements.join(", ")),
+                }
+            }
+            TypeDef::Array(array) => format!("Array<{}>", self.map(&array.type_def, false)?),
+            TypeDef::FixedArray(fixed) => {
+                format!("[{}; {}]", self.map(&fixed.type_def, false)?, fixed.size)
+            }
+            TypeDef::Option(option) => format!("Option<{}>", self.map(&option.type_def, false)?),
+            TypeDef::Nullable(nullable) => {
+                format!("Nullable<{}>", self.map(&nullable.type_def, false)?)
+            }
+            TypeDef::Result(result) => format!(
+                "Result<{}, {}>",
+                self.map(&result.ok, false)?,
+                self.map(&result.err, false)?
+            ),
+            TypeDef::Struct(struct_def) => self.struct_type(struct_def, reference)?,
+            TypeDef::Enum(enum_def) => self.enum_type(enum_def, reference)?,
+            TypeDef::Ref(reference) => {
+                let type_def = self
+                    .refs
+                    .and_then(|refs| refs.get_type_def(reference.id))
+                    .ok_or(CairoCodegenError::UnknownRef(reference.id))?;
+                match type_def {
+                    TypeDef::Struct(_) | TypeDef::Enum(_) => self.map(&type_def, true)?,
+                    _ => return Err(CairoCodegenError::Unsupported("reference to a non item")),
+                }
+            }
+            TypeDef::Custom(_) => return Err(CairoCodegenError::Unsupported("Custom")),
+            TypeDef::Felt252Dict(_) => return Err(CairoCodegenError::Unsupported("Felt252Dict")),
+        })
+    }
+
+    /// Member or variant declaration, moving raw and encoded bytes into
+    /// modifiers as the macros only accept them on the outermost type.
+    fn field(
+        &mut self,
+        name: &str,
+        attributes: &[Attribute],
+        type_def: &TypeDef,
+    ) -> CairoCodegenResult<Field> {
+        let ident = cairo_ident(name);
+        let mut lines = Vec::new();
+        if ident != name {
+            lines.push(format!("#[name({})]", cairo_string(name)));
+        }
+        let ty = match type_def {
+            TypeDef::Bytes31 | TypeDef::ByteArray => {
+                lines.push("#[raw]".to_string());
+                Some(byte_type(type_def))
+            }
+            TypeDef::Bytes31Encoded(encoded) => {
+                lines.push(format!("#[encoded({})]", cairo_string(&encoded.encoding)));
+                Some(byte_type(type_def))
+            }
+            TypeDef::ByteArrayEncoded(encoded) => {
+                lines.push(format!("#[encoded({})]", cairo_string(&encoded.encoding)));
+                Some(byte_type(type_def))
+            }
+            TypeDef::None => None,
+            type_def => Some(self.map(type_def, false)?),
+        };
+        for attribute in attributes {
+            lines.push(cairo_attribute(attribute)?);
+        }
+        Ok(Field {
+            ident,
+            attributes: lines,
+            ty,
+        })
+    }
+
+    fn struct_type(
+        &mut self,
+        struct_def: &StructDef,
+        reference: bool,
+    ) -> CairoCodegenResult<String> {
+        let name = cairo_ident(&struct_def.name);
+        if !self.in_progress.insert(name.clone()) {
+            return Ok(name);
+        }
+        let fields = struct_def
+            .members
+            .iter()
+            .map(|member| self.field(&member.name, &member.attributes, &member.type_def))
+            .collect::<CairoCodegenResult<Vec<_>>>();
+        self.in_progress.remove(&name);
+        self.add_struct(&name, derive(reference), &fields?)?;
+        Ok(name)
+    }
+
+    fn enum_type(&mut self, enum_def: &EnumDef, reference: bool) -> CairoCodegenResult<String> {
+        let name = cairo_ident(&enum_def.name);
+        if !self.in_progress.insert(name.clone()) {
+            return Ok(name);
+        }
+        let variants = enum_def
+            .order
+            .iter()
+            .filter_map(|selector| Some((selector, enum_def.variants.get(selector)?)))
+            .map(|(selector, variant)| {
+                let field = self.field(&variant.name, &variant.attributes, &variant.type_def)?;
+                match *selector == keccak(&field.ident) {
+                    true => Ok(field),
+                    false => Err(CairoCodegenError::SelectorMismatch {
+                        item: name.clone(),
+                        variant: variant.name.clone(),
+                        selector: *selector,
+                    }),
+                }
+            })
+            .collect::<CairoCodegenResult<Vec<_>>>();
+        self.in_progress.remove(&name);
+        let body: String = variants?
+            .iter()
+            .map(|variant| match &variant.ty {
+                Some(ty) => declaration(variant, &format!("{}: {ty}", variant.ident)),
+                None => declaration(variant, &variant.ident),
+            })
+            .collect();
+        let item = format!(
+            "#[derive(Drop, {}, ISerde)]\npub enum {name} {{\n{body}}}\n",
+            derive(reference)
+        );
+        self.add_item(&name, item)?;
+        Ok(name)
+    }
+
+    fn add_struct(&mut self, name: &str, derive: &str, fields: &[Field]) -> CairoCodegenResult<()> {
+        let body: String = fields
+            .iter()
+            .map(|field| {
+                let ty = field.ty.as_deref().unwrap_or("()");
+                declaration(field, &format!("pub {}: {ty}", field.ident))
+            })
+            .collect();
+        let item = format!("#[derive(Drop, {derive}, ISerde)]\npub struct {name} {{\n{body}}}\n");
+        self.add_item(name, item)
+    }
+
+    fn add_item(&mut self, name: &str, item: String) -> CairoCodegenResult<()> {
+        match self.module.items.get(name) {
+            Some(existing) if *existing != item => {
+                Err(CairoCodegenError::ConflictingType(name.to_string()))
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.module.items.insert(name.to_string(), item);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn derive(reference: bool) -> &'static str {
+    match reference {
+        true => "TypeDefRef",
+        false => "TypeDef",
+    }
+}
+
+fn byte_type(type_def: &TypeDef) -> String {
+    match type_def {
+        TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => "bytes31".to_string(),
+        _ => "ByteArray".to_string(),
+    }
+}
+
+fn declaration(field: &Field, line: &str) -> String {
+    let attributes: String = field
+        .attributes
+        .iter()
+        .map(|attribute| format!("    {attribute}\n"))
+        .collect();
+    format!("{attributes}    {line},\n")
+}
+
+fn cairo_string(value: &str) -> String {
+    format!("\"{}\"", value.escape_default())
+}
+
+fn cairo_attribute(attribute: &Attribute) -> CairoCodegenResult<String> {
+    if cairo_ident(&attribute.name) != attribute.name {
+        return Err(CairoCodegenError::Unsupported(
+            "attribute name that is not an identifier",
+        ));
+    }
+    match &attribute.data {
+        None => Ok(format!("#[{}]", attribute.name)),
+        Some(data) => match std::str::from_utf8(data) {
+            Ok(data) => Ok(format!("#[{}({})]", attribute.name, cairo_string(data))),
+            Err(_) => Err(CairoCodegenError::Unsupported(
+                "attribute data that is not UTF-8",
+            )),
+        },
+    }
+}
+
+impl TypeDef {
+    /// Cairo declarations of the type, see `CairoGenerator`.
+    pub fn cairo_module(&self) -> CairoCodegenResult<CairoModule> {
+        let mut generator = CairoGenerator::new();
+        generator.cairo_type(self)?;
+        Ok(generator.finish())
+    }
+}
+
+impl TableSchema {
+    /// Cairo struct of the table, see `CairoGenerator::table_struct`.
+    pub fn cairo_module(&self) -> CairoCodegenResult<CairoModule> {
+        let mut generator = CairoGenerator::new();
+        generator.table_struct(self)?;
+        Ok(generator.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArrayDef, ByteArrayEncodedDef, ColumnDef, MemberDef, OptionDef, PrimaryDef, PrimaryTypeDef,
+        RefDef, TupleDef, VariantDef,
+    };
+    use std::collections::HashMap;
+
+    fn direction() -> TypeDef {
+        EnumDef::new_type_def(
+            "game::Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    keccak("Up"),
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    keccak("Jump"),
+                    VariantDef::new("Jump".to_string(), vec![], TypeDef::Bytes31),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn generates_structs_and_enums() {
+        let position = StructDef::new_type_def(
+            "game::Position".to_string(),
+            vec![],
+            vec![
+                MemberDef::new(
+                    "type".to_string(),
+                    vec![Attribute::new_empty("indexed".to_string())],
+                    TypeDef::U512,
+                ),
+                MemberDef::new(
+                    "moves".to_string(),
+                    vec![],
+                    ArrayDef::new_type_def(OptionDef::new_type_def(TupleDef::new_type_def(vec![
+                        TypeDef::ContractAddress,
+                        TypeDef::ShortUtf8,
+                    ]))),
+                ),
+                MemberDef::new(
+                    "label".to_string(),
+                    vec![Attribute {
+                        name: "doc".to_string(),
+                        data: Some(b"shown \"as is\"".to_vec()),
+                    }],
+                    ByteArrayEncodedDef::new_type_def("ascii".to_string()),
+                ),
+                MemberDef::new("facing".to_string(), vec![], direction()),
+            ],
+        );
+        assert_eq!(
+            position.cairo_module().unwrap().to_string(),
+            "#[derive(Drop, TypeDef, ISerde)]\n\
+             pub enum Direction {\n    Up,\n    #[raw]\n    Jump: bytes31,\n}\n\
+             \n\
+             #[derive(Drop, TypeDef, ISerde)]\n\
+             pub struct Position {\n    \
+             #[name(\"type\")]\n    #[indexed]\n    pub type_: core::integer::u512,\n    \
+             pub moves: Array<Option<(starknet::ContractAddress, bytes31)>>,\n    \
+             #[encoded(\"ascii\")]\n    #[doc(\"shown \\\"as is\\\"\")]\n    pub label: ByteArray,\n    \
+             pub facing: Direction,\n}\n"
+        );
+
+        let nested = ArrayDef::new_type_def(TypeDef::ByteArray);
+        assert_eq!(
+            nested.cairo_module(),
+            Err(CairoCodegenError::Unsupported(
+                "raw or encoded bytes inside a container"
+            ))
+        );
+        let renamed = EnumDef::new_type_def(
+            "Side".to_string(),
+            vec![],
+            vec![(
+                Felt::ONE,
+                VariantDef::new("Left".to_string(), vec![], TypeDef::None),
+            )],
+        );
+        assert_eq!(
+            renamed.cairo_module(),
+            Err(CairoCodegenError::SelectorMismatch {
+                item: "Side".to_string(),
+                variant: "Left".to_string(),
+                selector: Felt::ONE,
+            })
+        );
+    }
+
+    #[test]
+    fn generates_tables_and_resolves_refs() {
+        let refs = HashMap::from([(Felt::from(7), direction())]);
+        let mut table = TableSchema {
+            id: Felt::ONE,
+            name: "Player".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("owner".to_string(), vec![], PrimaryTypeDef::ContractAddress),
+            columns: vec![
+                ColumnDef::new(
+                    keccak("last"),
+                    "last".to_string(),
+                    vec![],
+                    ArrayDef::new_type_def(RefDef::new_type_def(Felt::from(7))),
+                ),
+                ColumnDef::new(
+                    keccak("name"),
+                    "name".to_string(),
+                    vec![],
+                    TypeDef::Utf8String,
+                ),
+            ],
+        };
+        let mut generator = CairoGenerator::with_refs(&refs);
+        assert_eq!(generator.table_struct(&table).unwrap(), "Player");
+        let module = generator.finish();
+        assert_eq!(
+            module.items["Player"],
+            "#[derive(Drop, TypeDef, ISerde)]\npub struct Player {\n    #[key]\n    \
+             pub owner: starknet::ContractAddress,\n    pub last: Array<Direction>,\n    \
+             pub name: ByteArray,\n}\n"
+        );
+        assert!(module.items["Direction"].starts_with("#[derive(Drop, TypeDefRef, ISerde)]\n"));
+
+        assert_eq!(
+            table.cairo_module(),
+            Err(CairoCodegenError::UnknownRef(Felt::from(7)))
+        );
+        table.columns[0].id = Felt::TWO;
+        assert_eq!(
+            table.cairo_module(),
+            Err(CairoCodegenError::ColumnIdMismatch {
+                table: "Player".to_string(),
+                column: "last".to_string(),
+                id: Felt::TWO,
+            })
+        );
+
+        table.columns[0] = ColumnDef::new(keccak("type"), "type".to_string(), vec![], TypeDef::U8);
+        assert!(matches!(
+            table.cairo_module(),
+            Err(CairoCodegenError::ColumnIdMismatch { .. })
+        ));
+        table.columns[0].id = keccak("type_");
+        assert!(
+            table.cairo_module().unwrap().items["Player"]
+                .contains("#[name(\"type\")]\n    pub type_: u8,")
+        );
+    }
+}
@@ -0,0 +1,372 @@
+use crate::{
+    Attribute, CairoOption, CairoResult, ColumnDef, EnumDef, MemberDef, Nullable, PrimaryDef,
+    PrimaryTypeDef, StructDef, TableSchema, TypeDef, Value, VariantDef,
+};
+use starknet_types_core::felt::Felt;
+
+/// Maximum number of data felts a Starknet event can carry.
+pub const EVENT_DATA_FELT_LIMIT: usize = 300;
+
+/// Wire format a felt count is computed for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Cairo `Serde`, as read by `CairoSerde`.
+    Serde,
+    /// Packed `ISerde`, as read by `CairoISerde`.
+    ISerde,
+}
+
+/// Felts used by the tag of an `Option`, `Result` or `Nullable` value. Both
+/// encodings spend a whole felt on it; only the tag values differ.
+const TAG_FELTS: usize = 1;
+
+/// Number of felts a definition or value serializes to.
+pub trait FeltCost {
+    fn felt_cost(&self, encoding: Encoding) -> usize;
+}
+
+/// Felts used by a byte array of `len` bytes.
+pub fn byte_array_cost(len: usize, encoding: Encoding) -> usize {
+    match encoding {
+        Encoding::Serde => 3 + len / 31,
+        Encoding::ISerde => len.div_ceil(31).max(1),
+    }
+}
+
+fn span_cost<T: FeltCost>(items: &[T], encoding: Encoding) -> usize {
+    1 + items_cost(items, encoding)
+}
+
+fn items_cost<'a, T: FeltCost + 'a>(
+    items: impl IntoIterator<Item = &'a T>,
+    encoding: Encoding,
+) -> usize {
+    items.into_iter().map(|i| i.felt_cost(encoding)).sum()
+}
+
+impl FeltCost for str {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        byte_array_cost(self.len(), encoding)
+    }
+}
+
+impl FeltCost for String {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        self.as_str().felt_cost(encoding)
+    }
+}
+
+impl<T: FeltCost> FeltCost for Vec<T> {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        span_cost(self, encoding)
+    }
+}
+
+impl FeltCost for Attribute {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        let data = self
+            .data
+            .as_ref()
+            .map(|d| byte_array_cost(d.len(), encoding));
+        self.name.felt_cost(encoding)
+            + match encoding {
+                Encoding::Serde => 1 + data.unwrap_or(0),
+                Encoding::ISerde => data.unwrap_or(0),
+            }
+    }
+}
+
+/// The definition only: `Option`, `Result` and `Nullable` definitions carry no
+/// tag, their values do.
+impl FeltCost for TypeDef {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        1 + match self {
+            TypeDef::Bytes31Encoded(e) => e.encoding.felt_cost(encoding),
+            TypeDef::ByteArrayEncoded(e) => e.encoding.felt_cost(encoding),
+            TypeDef::Custom(c) => c.encoding.felt_cost(encoding),
+            TypeDef::Ref(_) => 1,
+            TypeDef::Tuple(tuple) => tuple.elements.felt_cost(encoding),
+            TypeDef::Array(a) => a.type_def.felt_cost(encoding),
+            TypeDef::FixedArray(fa) => fa.type_def.felt_cost(encoding) + 1,
+            TypeDef::Felt252Dict(d) => d.type_def.felt_cost(encoding),
+            TypeDef::Struct(s) => s.felt_cost(encoding),
+            TypeDef::Enum(e) => e.felt_cost(encoding),
+            TypeDef::Option(o) => o.type_def.felt_cost(encoding),
+            TypeDef::Result(r) => r.ok.felt_cost(encoding) + r.err.felt_cost(encoding),
+            TypeDef::Nullable(n) => n.type_def.felt_cost(encoding),
+            _ => 0,
+        }
+    }
+}
+
+/// Without the `TypeDef` variant felt.
+impl FeltCost for StructDef {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        self.name.felt_cost(encoding)
+            + self.attributes.felt_cost(encoding)
+            + self.members.felt_cost(encoding)
+    }
+}
+
+impl FeltCost for MemberDef {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        self.name.felt_cost(encoding)
+            + self.attributes.felt_cost(encoding)
+            + self.type_def.felt_cost(encoding)
+    }
+}
+
+/// Without the `TypeDef` variant felt. DESIGN.md's packed formula,
+/// `E = 6 + ...`, counts two felts more than the variant felt, name, attribute
+/// span and variant span length.
+impl FeltCost for EnumDef {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        let packed = match encoding {
+            Encoding::Serde => 0,
+            Encoding::ISerde => 2,
+        };
+        packed
+            + self.name.felt_cost(encoding)
+            + self.attributes.felt_cost(encoding)
+            + 1
+            + items_cost(self.variants.values(), encoding)
+    }
+}
+
+/// Including the selector.
+impl FeltCost for VariantDef {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        1 + self.name.felt_cost(encoding)
+            + self.attributes.felt_cost(encoding)
+            + self.type_def.felt_cost(encoding)
+    }
+}
+
+impl FeltCost for PrimaryTypeDef {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        match self {
+            PrimaryTypeDef::Bytes31Encoded(e) => 1 + e.encoding.felt_cost(encoding),
+            _ => 1,
+        }
+    }
+}
+
+impl FeltCost for PrimaryDef {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        self.name.felt_cost(encoding)
+            + self.attributes.felt_cost(encoding)
+            + self.type_def.felt_cost(encoding)
+    }
+}
+
+/// DESIGN.md's table formulas count `7` felts per column under Serde and `5`
+/// packed besides the attributes and `TypeDef`, two more than the id, name and
+/// attribute span length.
+impl FeltCost for ColumnDef {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        3 + self.name.felt_cost(encoding)
+            + self.attributes.felt_cost(encoding)
+            + self.type_def.felt_cost(encoding)
+    }
+}
+
+impl FeltCost for Value {
+    fn felt_cost(&self, encoding: Encoding) -> usize {
+        match self {
+            Value::None => 0,
+            Value::U256(_) => 2,
+            Value::U512(_) => 4,
+            Value::ByteArray(bytes) => byte_array_cost(bytes.len(), encoding),
+            Value::Utf8String(string) => string.felt_cost(encoding),
+            Value::ByteArrayEncoded(e) => byte_array_cost(e.bytes.len(), encoding),
+            Value::Tuple(values) | Value::FixedArray(values) => items_cost(values, encoding),
+            Value::Array(values) => values.felt_cost(encoding),
+            Value::Felt252Dict(entries) => {
+                1 + items_cost(entries.iter().map(|(_, v)| v), encoding) + entries.len()
+            }
+            Value::Struct(s) => items_cost(s.members.iter().map(|m| &m.value), encoding),
+            Value::Enum(e) => 1 + e.value.felt_cost(encoding),
+            Value::Custom(custom) => 1 + custom.values.len(),
+            Value::Option(option) => match option.as_ref() {
+                CairoOption::Some(value) => TAG_FELTS + value.felt_cost(encoding),
+                CairoOption::None => TAG_FELTS,
+            },
+            Value::Result(result) => match result.as_ref() {
+                CairoResult::Ok(value) | CairoResult::Err(value) => {
+                    TAG_FELTS + value.felt_cost(encoding)
+                }
+            },
+            Value::Nullable(nullable) => match nullable.as_ref() {
+                Nullable::NotNull(value) => TAG_FELTS + value.felt_cost(encoding),
+                Nullable::Null => TAG_FELTS,
+            },
+            _ => 1,
+        }
+    }
+}
+
+/// Felts used by a column in a `CreateTable` event and, when its type has a
+/// static length, by its value in an `InsertRecord` event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnCost {
+    pub id: Felt,
+    pub name: String,
+    pub definition: usize,
+    pub value: Option<usize>,
+}
+
+/// Per-component felt counts of a table in one encoding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableCost {
+    pub encoding: Encoding,
+    pub name: usize,
+    pub attributes: usize,
+    pub primary: usize,
+    pub columns: Vec<ColumnCost>,
+}
+
+impl TableCost {
+    /// Data felts of the `CreateTable` event: the id, name, attributes, primary
+    /// definition and the trailing column definitions.
+    pub fn create_table(&self) -> usize {
+        1 + self.name
+            + self.attributes
+            + self.primary
+            + self.columns.iter().map(|c| c.definition).sum::<usize>()
+    }
+
+    /// Data felts of an `InsertRecord` event (table id, row and column values),
+    /// `None` when a column has a variable length.
+    pub fn insert_record(&self) -> Option<usize> {
+        self.columns
+            .iter()
+            .try_fold(2, |len, c| Some(len + c.value?))
+    }
+
+    /// Whether the `CreateTable` event, or a fixed size `InsertRecord` event, is
+    /// over `EVENT_DATA_FELT_LIMIT`.
+    pub fn exceeds_event_limit(&self) -> bool {
+        self.create_table() > EVENT_DATA_FELT_LIMIT
+            || self
+                .insert_record()
+                .is_some_and(|len| len > EVENT_DATA_FELT_LIMIT)
+    }
+}
+
+impl TableSchema {
+    pub fn felt_cost(&self, encoding: Encoding) -> TableCost {
+        TableCost {
+            encoding,
+            name: self.name.felt_cost(encoding),
+            attributes: self.attributes.felt_cost(encoding),
+            primary: self.primary.felt_cost(encoding),
+            columns: self
+                .columns
+                .iter()
+                .map(|column| ColumnCost {
+                    id: column.id,
+                    name: column.name.clone(),
+                    definition: column.felt_cost(encoding),
+                    value: column.type_def.static_felt_len(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NullableDef, OptionDef, ResultDef, StructDef};
+
+    fn table(columns: usize) -> TableSchema {
+        TableSchema {
+            id: Felt::ONE,
+            name: "Player".to_string(),
+            attributes: vec![Attribute::new_empty("key".to_string())],
+            primary: PrimaryDef::new("id".to_string(), vec![], PrimaryTypeDef::Felt252),
+            columns: (0..columns)
+                .map(|i| {
+                    let position = StructDef::new_type_def(
+                        "Position".to_string(),
+                        vec![],
+                        vec![
+                            MemberDef::new("x".to_string(), vec![], TypeDef::U32),
+                            MemberDef::new("y".to_string(), vec![], TypeDef::U256),
+                        ],
+                    );
+                    ColumnDef::new(Felt::from(i), format!("column_{i}"), vec![], position)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn follows_the_design_formulas() {
+        let cost = table(2).felt_cost(Encoding::Serde);
+        // The event adds the table id and drops the column count from
+        // T = 10 + A + sum(7 + TD), with A = 3 + 1 and TD = S = 6 + 2 * (4 + 1).
+        assert_eq!(cost.create_table(), 1 + (10 - 1) + 4 + 2 * (7 + 16));
+        assert_eq!(cost.insert_record(), Some(2 + 2 * 3));
+
+        let packed = table(2).felt_cost(Encoding::ISerde);
+        // Names and attribute ids take a single felt each, and columns follow
+        // 5 + TD with TD = S = 4 + 2 * (2 + 1).
+        assert_eq!(packed.name, 1);
+        assert_eq!(packed.attributes, 2);
+        assert_eq!(packed.columns[0].definition, 5 + (4 + 2 * (2 + 1)));
+        assert!(packed.create_table() < cost.create_table());
+
+        let direction = EnumDef::new_type_def(
+            "Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::ONE,
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::TWO,
+                    VariantDef::new("Down".to_string(), vec![], TypeDef::None),
+                ),
+            ],
+        );
+        // E = 6 + sum(5 + TD) under Serde and 6 + sum(3 + TD) packed.
+        assert_eq!(direction.felt_cost(Encoding::Serde), 6 + 2 * (5 + 1));
+        assert_eq!(direction.felt_cost(Encoding::ISerde), 6 + 2 * (3 + 1));
+    }
+
+    #[test]
+    fn flags_events_over_the_limit() {
+        assert!(!table(5).felt_cost(Encoding::Serde).exceeds_event_limit());
+        assert!(table(20).felt_cost(Encoding::Serde).exceeds_event_limit());
+        assert!(!table(18).felt_cost(Encoding::ISerde).exceeds_event_limit());
+
+        let long = Value::ByteArray(vec![0; 62]);
+        assert_eq!(long.felt_cost(Encoding::Serde), 5);
+        assert_eq!(long.felt_cost(Encoding::ISerde), 2);
+        let none = Value::Option(Box::new(CairoOption::None));
+        assert_eq!(none.felt_cost(Encoding::ISerde), 1);
+    }
+
+    #[test]
+    fn counts_tags_on_values_not_definitions() {
+        for encoding in [Encoding::Serde, Encoding::ISerde] {
+            let option = OptionDef::new_type_def(TypeDef::U32);
+            let nullable = NullableDef::new_type_def(TypeDef::U256);
+            assert_eq!(option.felt_cost(encoding), 2);
+            assert_eq!(nullable.felt_cost(encoding), 2);
+
+            let some = Value::Option(Box::new(CairoOption::Some(Value::U32(1))));
+            let null = Value::Nullable(Box::new(Nullable::Null));
+            let not_null = Value::Nullable(Box::new(Nullable::NotNull(Value::U256(0.into()))));
+            assert_eq!(some.felt_cost(encoding), 2);
+            assert_eq!(null.felt_cost(encoding), 1);
+            assert_eq!(not_null.felt_cost(encoding), 3);
+
+            let result = ResultDef::new_type_def(TypeDef::U32, TypeDef::Felt252);
+            let ok = Value::Result(Box::new(CairoResult::Ok(Value::U32(1))));
+            assert_eq!(result.static_felt_len(), Some(ok.felt_cost(encoding)));
+        }
+    }
+}
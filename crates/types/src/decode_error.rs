@@ -1,5 +1,7 @@
 use std::borrow::Cow;
 
+use crate::path::{Locate, Location};
+
 use starknet_types_core::felt::Felt;
 use thiserror::Error;
 
@@ -62,6 +64,13 @@ pub enum DecodeError {
         value: Felt,
     },
 
+    #[error("enum variant index out of range for {name}: {index:?} (variants {len})")]
+    EnumIndexOutOfRange {
+        name: Cow<'static, str>,
+        index: Felt,
+        len: usize,
+    },
+
     #[error("unexpected length for {what}: expected {expected}, got {got}")]
     UnexpectedLen {
         what: &'static str,
@@ -71,6 +80,12 @@ pub enum DecodeError {
 
     #[error("{0}")]
     Message(Cow<'static, str>),
+
+    #[error("{source} at {location}")]
+    Located {
+        location: Box<Location>,
+        source: Box<DecodeError>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
@@ -86,6 +101,15 @@ pub enum ByteArrayError {
 }
 
 impl DecodeError {
+    /// Whether the input ran out, looking through any `Located` wrapper.
+    pub fn is_eof(&self) -> bool {
+        match self {
+            Self::Eof => true,
+            Self::Located { source, .. } => source.is_eof(),
+            _ => false,
+        }
+    }
+
     #[inline]
     pub fn out_of_range<T>(value: Felt) -> Self {
         Self::OutOfRangeFeltConversion {
@@ -125,6 +149,16 @@ impl DecodeError {
         }
     }
 
+    #[inline]
+    #[allow(private_bounds)]
+    pub fn enum_index_out_of_range<N: ToCowStr>(name: N, index: Felt, len: usize) -> Self {
+        Self::EnumIndexOutOfRange {
+            name: name.to_cow_str(),
+            index,
+            len,
+        }
+    }
+
     #[inline]
     pub fn unexpected_len(what: &'static str, expected: usize, got: usize) -> Self {
         Self::UnexpectedLen {
@@ -159,6 +193,19 @@ impl ToCowStr for String {
     }
 }
 
+impl Locate for DecodeError {
+    fn is_located(&self) -> bool {
+        matches!(self, Self::Located { .. })
+    }
+
+    fn with_location(self, location: Location) -> Self {
+        Self::Located {
+            location: Box::new(location),
+            source: Box::new(self),
+        }
+    }
+}
+
 impl From<core::str::Utf8Error> for DecodeError {
     #[inline]
     fn from(e: core::str::Utf8Error) -> Self {
@@ -1,5 +1,7 @@
 use crate::decode_error::DecodeResultTrait;
-use crate::{ByteArray, Bytes31, DecodeError, DecodeResult, EthAddress};
+use crate::limits::{DecodeBudget, capped_capacity};
+use crate::path::{DecodePath, Location};
+use crate::{ByteArray, Bytes31, DecodeError, DecodeResult, EnumDef, EthAddress, VariantDef};
 use primitive_types::{U256, U512};
 use starknet_types_core::felt::{Felt, PrimitiveFromFeltError};
 
@@ -38,7 +40,7 @@ where
 {
     fn deserialize(deserializer: &mut D) -> DecodeResult<Self>;
     fn deserialize_multiple(deserializer: &mut D, count: usize) -> DecodeResult<Vec<Self>> {
-        let mut items = Vec::with_capacity(count);
+        let mut items = Vec::with_capacity(capped_capacity(count));
         for _ in 0..count {
             items.push(Self::deserialize(deserializer)?);
         }
@@ -169,8 +171,69 @@ impl<T: CairoDeserializer> CairoDeserialize<T> for String {
     }
 }
 
+/// Reads the values left in the input: a length prefixed array with `Serde`,
+/// everything up to the end with `ISerde`.
+pub trait CairoDeserializeRemaining {
+    fn deserialize_remaining<T: CairoDeserialize<Self>>(&mut self) -> DecodeResult<Vec<T>>
+    where
+        Self: Sized;
+}
+
+/// How an enum variant felt is interpreted: as the variant selector (introspect
+/// encoding) or as the zero-based variant index (standard Cairo `Serde`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EnumVariantEncoding {
+    #[default]
+    Selector,
+    Index,
+}
+
 pub trait CairoDeserializer {
     fn next_felt(&mut self) -> DecodeResult<Felt>;
+    fn enum_variant_encoding(&self) -> EnumVariantEncoding {
+        EnumVariantEncoding::Selector
+    }
+    fn decode_budget(&mut self) -> Option<&mut DecodeBudget> {
+        None
+    }
+    fn check_array_len(&mut self, what: &'static str, len: usize) -> DecodeResult<()> {
+        match self.decode_budget() {
+            Some(budget) => budget.check_array_len(what, len),
+            None => Ok(()),
+        }
+    }
+    fn check_byte_array_len(&mut self, len: usize) -> DecodeResult<()> {
+        match self.decode_budget() {
+            Some(budget) => budget.check_byte_array_len(len),
+            None => Ok(()),
+        }
+    }
+    /// Counts a nesting level against `max_depth`, a no-op unless the source
+    /// is `Limited`.
+    fn enter_nested(&mut self) -> DecodeResult<()> {
+        match self.decode_budget() {
+            Some(budget) => budget.enter(),
+            None => Ok(()),
+        }
+    }
+    fn exit_nested(&mut self) {
+        if let Some(budget) = self.decode_budget() {
+            budget.exit();
+        }
+    }
+    fn decode_path(&mut self) -> Option<&mut DecodePath> {
+        None
+    }
+    fn source_position(&self) -> Option<usize> {
+        None
+    }
+    fn location(&mut self) -> Option<Location> {
+        let position = self.source_position();
+        self.decode_path().map(|path| Location {
+            path: path.clone(),
+            position,
+        })
+    }
     fn next_byte(&mut self) -> DecodeResult<u8> {
         self.next_bytes::<1>().map(|b| b[0])
     }
@@ -200,7 +263,7 @@ pub trait CairoDeserializer {
         loop {
             match self.next_felt() {
                 Ok(felt) => felts.push(felt),
-                Err(DecodeError::Eof) => break Ok(felts),
+                Err(err) if err.is_eof() => break Ok(felts),
                 Err(err) => break Err(err),
             }
         }
@@ -213,7 +276,7 @@ pub trait CairoDeserializer {
         loop {
             match T::deserialize(self) {
                 Ok(item) => items.push(item),
-                Err(DecodeError::Eof) => break Ok(items),
+                Err(err) if err.is_eof() => break Ok(items),
                 Err(err) => break Err(err),
             }
         }
@@ -315,11 +378,13 @@ pub trait CairoDeserializer {
     where
         Self: Sized,
     {
-        Ok(ByteArray::new_from_parts(
-            self.next_array::<Bytes31>()?,
-            self.next_bytes31().raise_eof()?.into(),
-            self.next_u8().raise_eof()?,
-        ))
+        let len = self.next_u32()? as usize;
+        self.check_byte_array_len(len.saturating_mul(31))?;
+        let data = Bytes31::deserialize_multiple(self, len).raise_eof()?;
+        let pending_word = self.next_bytes31().raise_eof()?.into();
+        let pending_len = self.next_u8().raise_eof()?;
+        self.check_byte_array_len(len * 31 + pending_len as usize)?;
+        Ok(ByteArray::new_from_parts(data, pending_word, pending_len))
     }
     fn next_byte_array_bytes(&mut self) -> DecodeResult<Vec<u8>>
     where
@@ -327,6 +392,18 @@ pub trait CairoDeserializer {
     {
         self.next_byte_array().map(Into::into)
     }
+    /// Advances past a byte array without collecting it, returning the number of
+    /// felts it used.
+    fn skip_byte_array(&mut self) -> DecodeResult<usize> {
+        let len = self.next_u32()? as usize;
+        self.check_byte_array_len(len.saturating_mul(31))?;
+        for _ in 0..len {
+            self.next_felt().raise_eof()?;
+        }
+        self.next_felt().raise_eof()?;
+        self.next_u8().raise_eof()?;
+        Ok(len + 3)
+    }
     fn next_string(&mut self) -> DecodeResult<String>
     where
         Self: Sized,
@@ -338,8 +415,9 @@ pub trait CairoDeserializer {
     where
         Self: Sized,
     {
-        let len = self.next_u32()?;
-        T::deserialize_multiple(self, len as usize).raise_eof()
+        let len = self.next_u32()? as usize;
+        self.check_array_len("array", len)?;
+        T::deserialize_multiple(self, len).raise_eof()
     }
 
     fn next_fixed_size_array<T: CairoDeserialize<Self>>(
@@ -349,11 +427,24 @@ pub trait CairoDeserializer {
     where
         Self: Sized,
     {
+        self.check_array_len("fixed size array", size)?;
         T::deserialize_multiple(self, size).raise_eof()
     }
     fn next_enum_variant(&mut self) -> DecodeResult<Felt> {
         self.next_felt()
     }
+    fn next_enum_variant_def<'a>(
+        &mut self,
+        enum_def: &'a EnumDef,
+    ) -> DecodeResult<(Felt, &'a VariantDef)> {
+        let variant = self.next_enum_variant()?;
+        match self.enum_variant_encoding() {
+            EnumVariantEncoding::Selector => enum_def
+                .get_variant(&variant)
+                .map(|variant_def| (variant, variant_def)),
+            EnumVariantEncoding::Index => enum_def.get_variant_by_index(variant),
+        }
+    }
     fn next_option_is_some(&mut self) -> DecodeResult<bool> {
         self.next_bool_tag("option").map(|b| !b)
     }
@@ -35,6 +35,13 @@ pub trait TypeDefDeserializer: CairoDeserializer + Sized {
     fn deserialize_attribute(&mut self) -> DecodeResult<Attribute>;
 
     fn deserialize_type_def(&mut self) -> DecodeResult<TypeDef> {
+        self.enter_nested()?;
+        let type_def = self.deserialize_type_def_item();
+        self.exit_nested();
+        type_def
+    }
+
+    fn deserialize_type_def_item(&mut self) -> DecodeResult<TypeDef> {
         let selector = self.next_enum_variant()?;
         match selector.to_be_digits() {
             selectors::None => Ok(TypeDef::None),
@@ -40,11 +40,10 @@ where
     }
     fn verify_eof<K: FeltSource>(self, keys: &mut K, data: &mut D) -> DecodeResult<Self> {
         match (keys.next(), data.next()) {
-            (Err(DecodeError::Eof), Err(DecodeError::Eof)) => Ok(self),
-            (Ok(_), Err(DecodeError::Eof)) | (Err(DecodeError::Eof), Ok(_)) | (Ok(_), Ok(_)) => {
-                Err(DecodeError::NotEof)
-            }
-            (Err(e), _) | (_, Err(e)) => Err(e),
+            (Err(k), Err(d)) if k.is_eof() && d.is_eof() => Ok(self),
+            (Err(e), _) if !e.is_eof() => Err(e),
+            (_, Err(e)) if !e.is_eof() => Err(e),
+            _ => Err(DecodeError::NotEof),
         }
     }
     fn deserialize_and_verify_event_enum<K: FeltSource, T: From<Self>>(
@@ -1,10 +1,18 @@
 use starknet_types_core::felt::Felt;
 
+use crate::limits::DecodeBudget;
+use crate::path::DecodePath;
 use crate::{DecodeError, DecodeResult};
 
 pub trait FeltSource {
     fn next(&mut self) -> Result<Felt, DecodeError>;
     fn position(&self) -> usize;
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        None
+    }
+    fn path(&mut self) -> Option<&mut DecodePath> {
+        None
+    }
 }
 
 pub struct SliceFeltSource<'a> {
@@ -32,6 +40,16 @@ impl<S: FeltSource + ?Sized> FeltSource for &mut S {
     fn position(&self) -> usize {
         (**self).position()
     }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        (**self).budget()
+    }
+
+    #[inline]
+    fn path(&mut self) -> Option<&mut DecodePath> {
+        (**self).path()
+    }
 }
 
 pub trait IntoFeltSource {
@@ -0,0 +1,702 @@
+use crate::deserialize::FeltToPrimitive;
+use crate::{
+    CairoDeserializer, CairoISerde, CairoSerde, DecodeError, GetRefTypeDef, MemberDef, RefDef,
+    TypeDef, felt_to_hex_string,
+};
+use serde::de::value::{SeqDeserializer, StrDeserializer};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use starknet_types_core::felt::Felt;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum FromFeltsError {
+    #[error(transparent)]
+    Decode(#[from] DecodeError),
+    #[error("unknown type reference: {0:#x}")]
+    UnknownRef(Felt),
+    #[error("{0} cannot be deserialized")]
+    Unsupported(&'static str),
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl de::Error for FromFeltsError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+/// A serde `Deserializer` reading felts from a `CairoDeserializer` as laid out
+/// by a `TypeDef`.
+///
+/// Structs are maps keyed by member name, enums and results are externally
+/// tagged by variant name, felts, addresses and 256/512 bit integers are hex
+/// strings and byte arrays are bytes.
+pub struct FeltDeserializer<'a, D> {
+    type_def: &'a TypeDef,
+    deserializer: &'a mut D,
+    refs: Option<&'a dyn GetRefTypeDef>,
+}
+
+/// Deserializes `T` from `felts` serialised with `Serde`.
+pub fn from_felts<T: DeserializeOwned>(
+    type_def: &TypeDef,
+    felts: &[Felt],
+) -> Result<T, FromFeltsError> {
+    from_deserializer(type_def, &mut CairoSerde::from(felts))
+}
+
+/// Deserializes `T` from `felts` serialised with `ISerde`.
+pub fn from_ifelts<T: DeserializeOwned>(
+    type_def: &TypeDef,
+    felts: &[Felt],
+) -> Result<T, FromFeltsError> {
+    from_deserializer(type_def, &mut CairoISerde::from(felts))
+}
+
+/// Deserializes `T` from `deserializer`, which must be fully consumed.
+pub fn from_deserializer<T: DeserializeOwned, D: CairoDeserializer>(
+    type_def: &TypeDef,
+    deserializer: &mut D,
+) -> Result<T, FromFeltsError> {
+    let value = T::deserialize(FeltDeserializer::new(type_def, deserializer))?;
+    match deserializer.next_felt() {
+        Err(e) if e.is_eof() => Ok(value),
+        Ok(_) => Err(DecodeError::NotEof.into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl<'a, D: CairoDeserializer> FeltDeserializer<'a, D> {
+    pub fn new(type_def: &'a TypeDef, deserializer: &'a mut D) -> Self {
+        Self {
+            type_def,
+            deserializer,
+            refs: None,
+        }
+    }
+
+    /// Resolves `TypeDef::Ref`s through `refs`.
+    pub fn with_refs(mut self, refs: &'a dyn GetRefTypeDef) -> Self {
+        self.refs = Some(refs);
+        self
+    }
+
+    fn is_felt(&self) -> bool {
+        matches!(
+            self.type_def,
+            TypeDef::Felt252
+                | TypeDef::ClassHash
+                | TypeDef::ContractAddress
+                | TypeDef::EthAddress
+                | TypeDef::StorageAddress
+                | TypeDef::StorageBaseAddress
+        )
+    }
+}
+
+fn resolve(
+    refs: Option<&dyn GetRefTypeDef>,
+    reference: &RefDef,
+) -> Result<TypeDef, FromFeltsError> {
+    refs.and_then(|refs| refs.get_type_def(reference.id))
+        .ok_or(FromFeltsError::UnknownRef(reference.id))
+}
+
+fn child<'b, D>(
+    type_def: &'b TypeDef,
+    deserializer: &'b mut D,
+    refs: Option<&'b dyn GetRefTypeDef>,
+) -> FeltDeserializer<'b, D> {
+    FeltDeserializer {
+        type_def,
+        deserializer,
+        refs,
+    }
+}
+
+/// Calls `method` again on the `TypeDef` a `Ref` points to.
+macro_rules! resolve_ref {
+    ($self:ident, $method:ident($($arg:expr),*)) => {
+        if let TypeDef::Ref(reference) = $self.type_def {
+            let type_def = resolve($self.refs, reference)?;
+            return child(&type_def, $self.deserializer, $self.refs).$method($($arg),*);
+        }
+    };
+}
+
+/// Integer hints also accept felts, converted when in range.
+macro_rules! deserialize_int {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FromFeltsError> {
+            resolve_ref!(self, $method(visitor));
+            match self.is_felt() {
+                true => visitor.$visit(self.deserializer.next_felt()?.to_primitive()?),
+                false => self.deserialize_any(visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de, D: CairoDeserializer> de::Deserializer<'de> for FeltDeserializer<'_, D> {
+    type Error = FromFeltsError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FromFeltsError> {
+        let d = self.deserializer;
+        match self.type_def {
+            TypeDef::None => visitor.visit_unit(),
+            TypeDef::Felt252
+            | TypeDef::ClassHash
+            | TypeDef::ContractAddress
+            | TypeDef::EthAddress
+            | TypeDef::StorageAddress
+            | TypeDef::StorageBaseAddress => {
+                visitor.visit_string(felt_to_hex_string(&d.next_felt()?))
+            }
+            TypeDef::ShortUtf8 => visitor.visit_string(d.next_short_string()?),
+            TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => {
+                visitor.visit_byte_buf(<[u8; 31]>::from(d.next_bytes31()?).to_vec())
+            }
+            TypeDef::Bool => visitor.visit_bool(d.next_bool()?),
+            TypeDef::U8 => visitor.visit_u8(d.next_u8()?),
+            TypeDef::U16 => visitor.visit_u16(d.next_u16()?),
+            TypeDef::U32 => visitor.visit_u32(d.next_u32()?),
+            TypeDef::U64 => visitor.visit_u64(d.next_u64()?),
+            TypeDef::U128 => visitor.visit_u128(d.next_u128()?),
+            TypeDef::U256 => visitor.visit_string(format!("{:#x}", d.next_u256()?)),
+            TypeDef::U512 => visitor.visit_string(format!("{:#x}", d.next_u512()?)),
+            TypeDef::I8 => visitor.visit_i8(d.next_i8()?),
+            TypeDef::I16 => visitor.visit_i16(d.next_i16()?),
+            TypeDef::I32 => visitor.visit_i32(d.next_i32()?),
+            TypeDef::I64 => visitor.visit_i64(d.next_i64()?),
+            TypeDef::I128 => visitor.visit_i128(d.next_i128()?),
+            TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) => {
+                visitor.visit_byte_buf(d.next_byte_array_bytes()?)
+            }
+            TypeDef::Utf8String => visitor.visit_string(d.next_string()?),
+            TypeDef::Tuple(tuple) => visitor.visit_seq(Elements {
+                items: ElementDefs::Tuple(tuple.elements.iter()),
+                deserializer: d,
+                refs: self.refs,
+            }),
+            TypeDef::Array(array) => {
+                let len = d.next_u32()? as usize;
+                d.check_array_len("array", len)?;
+                visitor.visit_seq(Elements {
+                    items: ElementDefs::Repeat(&array.type_def, len),
+                    deserializer: d,
+                    refs: self.refs,
+                })
+            }
+            TypeDef::FixedArray(array) => {
+                let len = array.size as usize;
+                d.check_array_len("fixed size array", len)?;
+                visitor.visit_seq(Elements {
+                    items: ElementDefs::Repeat(&array.type_def, len),
+                    deserializer: d,
+                    refs: self.refs,
+                })
+            }
+            TypeDef::Felt252Dict(_) => Err(FromFeltsError::Unsupported("Felt252Dict")),
+            TypeDef::Struct(struct_def) => visitor.visit_map(Members {
+                members: struct_def.members.iter(),
+                current: None,
+                deserializer: d,
+                refs: self.refs,
+            }),
+            TypeDef::Enum(enum_def) => {
+                let (_, variant) = d.next_enum_variant_def(enum_def)?;
+                visitor.visit_map(VariantMap {
+                    name: Some(&variant.name),
+                    type_def: &variant.type_def,
+                    deserializer: d,
+                    refs: self.refs,
+                })
+            }
+            TypeDef::Option(option) => match d.next_option_is_some()? {
+                true => visitor.visit_some(child(&option.type_def, d, self.refs)),
+                false => visitor.visit_none(),
+            },
+            TypeDef::Result(result) => {
+                let (name, type_def) = match d.next_result_is_ok()? {
+                    true => ("Ok", &result.ok),
+                    false => ("Err", &result.err),
+                };
+                visitor.visit_map(VariantMap {
+                    name: Some(name),
+                    type_def,
+                    deserializer: d,
+                    refs: self.refs,
+                })
+            }
+            TypeDef::Nullable(nullable) => match d.next_nullable_is_null()? {
+                false => visitor.visit_some(child(&nullable.type_def, d, self.refs)),
+                true => visitor.visit_none(),
+            },
+            TypeDef::Ref(reference) => {
+                let type_def = resolve(self.refs, reference)?;
+                child(&type_def, d, self.refs).deserialize_any(visitor)
+            }
+            TypeDef::Custom(_) => {
+                let felts: Vec<Felt> = d.next_array()?;
+                visitor.visit_seq(SeqDeserializer::new(felts.iter().map(felt_to_hex_string)))
+            }
+        }
+    }
+
+    deserialize_int! {
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+    }
+
+    /// Byte arrays are also sequences of `u8`, for `Vec<u8>` and `[u8; 31]`.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FromFeltsError> {
+        resolve_ref!(self, deserialize_seq(visitor));
+        let bytes: Vec<u8> = match self.type_def {
+            TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => {
+                <[u8; 31]>::from(self.deserializer.next_bytes31()?).to_vec()
+            }
+            TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) => {
+                self.deserializer.next_byte_array_bytes()?
+            }
+            _ => return self.deserialize_any(visitor),
+        };
+        visitor.visit_seq(SeqDeserializer::new(bytes.into_iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, FromFeltsError> {
+        self.deserialize_seq(visitor)
+    }
+
+    /// Any non optional type is read as `Some`.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, FromFeltsError> {
+        resolve_ref!(self, deserialize_option(visitor));
+        match self.type_def {
+            TypeDef::None => visitor.visit_none(),
+            TypeDef::Option(_) | TypeDef::Nullable(_) => self.deserialize_any(visitor),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, FromFeltsError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, FromFeltsError> {
+        resolve_ref!(self, deserialize_enum(name, variants, visitor));
+        let d = self.deserializer;
+        let (name, type_def) = match self.type_def {
+            TypeDef::Enum(enum_def) => {
+                let (_, variant) = d.next_enum_variant_def(enum_def)?;
+                (variant.name.as_str(), &variant.type_def)
+            }
+            TypeDef::Result(result) => match d.next_result_is_ok()? {
+                true => ("Ok", &result.ok),
+                false => ("Err", &result.err),
+            },
+            _ => return child(self.type_def, d, self.refs).deserialize_any(visitor),
+        };
+        visitor.visit_enum(Variant {
+            name,
+            type_def,
+            deserializer: d,
+            refs: self.refs,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf unit unit_struct
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+enum ElementDefs<'a> {
+    Tuple(std::slice::Iter<'a, TypeDef>),
+    Repeat(&'a TypeDef, usize),
+}
+
+struct Elements<'a, D> {
+    items: ElementDefs<'a>,
+    deserializer: &'a mut D,
+    refs: Option<&'a dyn GetRefTypeDef>,
+}
+
+impl<'de, D: CairoDeserializer> SeqAccess<'de> for Elements<'_, D> {
+    type Error = FromFeltsError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, FromFeltsError> {
+        let type_def = match &mut self.items {
+            ElementDefs::Tuple(elements) => elements.next(),
+            ElementDefs::Repeat(_, 0) => None,
+            ElementDefs::Repeat(type_def, len) => {
+                *len -= 1;
+                Some(*type_def)
+            }
+        };
+        type_def
+            .map(|type_def| seed.deserialize(child(type_def, self.deserializer, self.refs)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match &self.items {
+            ElementDefs::Tuple(elements) => Some(elements.len()),
+            ElementDefs::Repeat(_, len) => Some(*len),
+        }
+    }
+}
+
+struct Members<'a, D> {
+    members: std::slice::Iter<'a, MemberDef>,
+    current: Option<&'a TypeDef>,
+    deserializer: &'a mut D,
+    refs: Option<&'a dyn GetRefTypeDef>,
+}
+
+impl<'de, D: CairoDeserializer> MapAccess<'de> for Members<'_, D> {
+    type Error = FromFeltsError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, FromFeltsError> {
+        let Some(member) = self.members.next() else {
+            return Ok(None);
+        };
+        self.current = Some(&member.type_def);
+        seed.deserialize(StrDeserializer::new(&member.name))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, FromFeltsError> {
+        let type_def = self.current.take().ok_or_else(|| {
+            <FromFeltsError as de::Error>::custom("value requested before its key")
+        })?;
+        seed.deserialize(child(type_def, self.deserializer, self.refs))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.members.len())
+    }
+}
+
+/// A single entry map `{ variant: value }`, for self describing visitors.
+struct VariantMap<'a, D> {
+    name: Option<&'a str>,
+    type_def: &'a TypeDef,
+    deserializer: &'a mut D,
+    refs: Option<&'a dyn GetRefTypeDef>,
+}
+
+impl<'de, D: CairoDeserializer> MapAccess<'de> for VariantMap<'_, D> {
+    type Error = FromFeltsError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, FromFeltsError> {
+        self.name
+            .take()
+            .map(|name| seed.deserialize(StrDeserializer::new(name)))
+            .transpose()
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, FromFeltsError> {
+        seed.deserialize(child(self.type_def, self.deserializer, self.refs))
+    }
+}
+
+struct Variant<'a, D> {
+    name: &'a str,
+    type_def: &'a TypeDef,
+    deserializer: &'a mut D,
+    refs: Option<&'a dyn GetRefTypeDef>,
+}
+
+impl<'de, 'a, D: CairoDeserializer> EnumAccess<'de> for Variant<'a, D> {
+    type Error = FromFeltsError;
+    type Variant = FeltDeserializer<'a, D>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), FromFeltsError> {
+        let variant = seed.deserialize(StrDeserializer::<FromFeltsError>::new(self.name))?;
+        Ok((variant, child(self.type_def, self.deserializer, self.refs)))
+    }
+}
+
+impl<'de, D: CairoDeserializer> VariantAccess<'de> for FeltDeserializer<'_, D> {
+    type Error = FromFeltsError;
+
+    /// Reads and drops any payload, so unit variants can stand for data ones.
+    fn unit_variant(self) -> Result<(), FromFeltsError> {
+        <de::IgnoredAny as de::Deserialize>::deserialize(self).map(|_| ())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, FromFeltsError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, FromFeltsError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, FromFeltsError> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EnumDef, NullableDef, OptionDef, StructDef, TupleDef, VariantDef};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Class {
+        Warrior,
+        Mage(u8),
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Player {
+        owner: Felt,
+        name: String,
+        class: Class,
+        moves: Vec<(u8, u8)>,
+        pet: Option<String>,
+    }
+
+    fn player_def() -> TypeDef {
+        let class = EnumDef::new_type_def(
+            "Class".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::from(1),
+                    VariantDef::new("Warrior".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::from(2),
+                    VariantDef::new("Mage".to_string(), vec![], TypeDef::U8),
+                ),
+            ],
+        );
+        let moves = TypeDef::Array(Box::new(crate::ArrayDef {
+            type_def: TupleDef::new_type_def(vec![TypeDef::U8, TypeDef::U8]),
+        }));
+        let pet = TypeDef::Option(Box::new(crate::OptionDef {
+            type_def: TypeDef::ShortUtf8,
+        }));
+        StructDef::new_type_def(
+            "Player".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("owner".to_string(), vec![], TypeDef::ContractAddress),
+                MemberDef::new("level".to_string(), vec![], TypeDef::U32),
+                MemberDef::new("name".to_string(), vec![], TypeDef::ShortUtf8),
+                MemberDef::new("class".to_string(), vec![], class),
+                MemberDef::new("moves".to_string(), vec![], moves),
+                MemberDef::new("pet".to_string(), vec![], pet),
+            ],
+        )
+    }
+
+    #[test]
+    fn deserializes_struct_by_member_name() {
+        let felts = [
+            Felt::from(0x1234),
+            Felt::from(7),
+            Felt::from_bytes_be_slice(b"bob"),
+            Felt::from(2),
+            Felt::from(3),
+            Felt::from(2),
+            Felt::from(1),
+            Felt::from(2),
+            Felt::from(3),
+            Felt::from(4),
+            Felt::from(0),
+            Felt::from_bytes_be_slice(b"rex"),
+        ];
+        let player: Player = from_felts(&player_def(), &felts).unwrap();
+        assert_eq!(
+            player,
+            Player {
+                owner: Felt::from(0x1234),
+                name: "bob".to_string(),
+                class: Class::Mage(3),
+                moves: vec![(1, 2), (3, 4)],
+                pet: Some("rex".to_string()),
+            }
+        );
+        let value: serde_json::Value = from_felts(&player_def(), &felts).unwrap();
+        assert_eq!(value["class"], serde_json::json!({ "Mage": 3 }));
+        assert_eq!(value["level"], 7);
+    }
+
+    #[test]
+    fn reports_bad_input() {
+        let type_def = player_def();
+        let felts = [
+            Felt::from(1),
+            Felt::from(7),
+            Felt::from_bytes_be_slice(b"bob"),
+            Felt::from(5),
+        ];
+        assert!(matches!(
+            from_felts::<Player>(&type_def, &felts),
+            Err(FromFeltsError::Decode(
+                DecodeError::InvalidEnumSelector { .. }
+            ))
+        ));
+        let felts = [Felt::from(5), Felt::from(300)];
+        assert_eq!(from_felts::<u8>(&TypeDef::Felt252, &felts[..1]), Ok(5));
+        assert_eq!(
+            from_felts::<u8>(&TypeDef::Felt252, &felts),
+            Err(FromFeltsError::Decode(DecodeError::NotEof))
+        );
+        assert!(from_felts::<u8>(&TypeDef::Felt252, &felts[1..]).is_err());
+        let reference = TypeDef::Ref(RefDef { id: Felt::from(9) });
+        assert_eq!(
+            from_felts::<u8>(&reference, &felts[1..]),
+            Err(FromFeltsError::UnknownRef(Felt::from(9)))
+        );
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Pet {
+        name: String,
+        tag: Option<u8>,
+        owner: Option<u8>,
+        nick: Option<String>,
+    }
+
+    fn pet_def() -> TypeDef {
+        StructDef::new_type_def(
+            "Pet".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("name".to_string(), vec![], TypeDef::Utf8String),
+                MemberDef::new(
+                    "tag".to_string(),
+                    vec![],
+                    OptionDef::new_type_def(TypeDef::U8),
+                ),
+                MemberDef::new(
+                    "owner".to_string(),
+                    vec![],
+                    NullableDef::new_type_def(TypeDef::U8),
+                ),
+                MemberDef::new(
+                    "nick".to_string(),
+                    vec![],
+                    OptionDef::new_type_def(TypeDef::Utf8String),
+                ),
+            ],
+        )
+    }
+
+    /// An ISerde byte array felt: the info byte, the length of a partial
+    /// chunk and the bytes right aligned.
+    fn byte_array_felt(info: u8, bytes: &[u8]) -> Felt {
+        let mut felt = [0; 32];
+        felt[0] = info;
+        if info & 2 == 2 {
+            felt[2] = bytes.len() as u8;
+        }
+        felt[32 - bytes.len()..].copy_from_slice(bytes);
+        Felt::from_bytes_be(&felt)
+    }
+
+    #[test]
+    fn deserializes_iserde_tags() {
+        let name = "a".repeat(31) + "bc";
+        let felts = [
+            byte_array_felt(0, &name.as_bytes()[..31]),
+            byte_array_felt(3, b"bc"),
+            Felt::ONE,
+            Felt::from(5),
+            Felt::ONE,
+            Felt::from(7),
+            Felt::ZERO,
+        ];
+        assert_eq!(
+            from_ifelts::<Pet>(&pet_def(), &felts),
+            Ok(Pet {
+                name,
+                tag: Some(5),
+                owner: Some(7),
+                nick: None,
+            })
+        );
+        let felts = [
+            byte_array_felt(3, b"rex"),
+            Felt::ZERO,
+            Felt::ZERO,
+            Felt::ONE,
+            byte_array_felt(3, b""),
+        ];
+        assert_eq!(
+            from_ifelts::<Pet>(&pet_def(), &felts),
+            Ok(Pet {
+                name: "rex".to_string(),
+                tag: None,
+                owner: None,
+                nick: Some(String::new()),
+            })
+        );
+        let felts = [byte_array_felt(3, b"rex"), Felt::TWO];
+        assert!(matches!(
+            from_ifelts::<Pet>(&pet_def(), &felts),
+            Err(FromFeltsError::Decode(DecodeError::InvalidTag {
+                what: "option",
+                ..
+            }))
+        ));
+    }
+}
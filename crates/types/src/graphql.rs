@@ -0,0 +1,516 @@
+use crate::{
+    EnumDef, GetRefTypeDef, StructDef, TableSchema, TypeDef, camel_case, felt_to_hex_string,
+    identifier,
+};
+use starknet_types_core::felt::Felt;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::{self, Display, Write};
+use thiserror::Error;
+
+pub const PAGE_INFO: &str = "PageInfo";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum GraphQLError {
+    #[error("unknown type reference: {0:#x}")]
+    UnknownRef(Felt),
+
+    #[error("conflicting definitions for GraphQL type {0}")]
+    ConflictingType(String),
+
+    #[error("conflicting definitions for GraphQL query {0}")]
+    ConflictingQuery(String),
+
+    #[error("recursive type reference through an unnamed type: {0:#x}")]
+    RecursiveRef(Felt),
+}
+
+pub type GraphQLResult<T> = Result<T, GraphQLError>;
+
+/// Generated GraphQL definitions, rendered as SDL through `Display`.
+///
+/// Schemas built separately, e.g. one per table, can be combined with `merge`
+/// as long as the types and queries they share have the same definition.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphQLSchema {
+    pub scalars: BTreeSet<String>,
+    pub types: BTreeMap<String, String>,
+    pub queries: BTreeMap<String, String>,
+}
+
+impl GraphQLSchema {
+    pub fn merge(&mut self, other: &GraphQLSchema) -> GraphQLResult<()> {
+        for (name, definition) in &other.types {
+            insert_definition(
+                &mut self.types,
+                name.clone(),
+                definition.clone(),
+                GraphQLError::ConflictingType,
+            )?;
+        }
+        for (name, field) in &other.queries {
+            insert_definition(
+                &mut self.queries,
+                name.clone(),
+                field.clone(),
+                GraphQLError::ConflictingQuery,
+            )?;
+        }
+        self.scalars.extend(other.scalars.iter().cloned());
+        Ok(())
+    }
+}
+
+impl Display for GraphQLSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut blocks: Vec<String> = self.scalars.iter().map(|s| format!("scalar {s}")).collect();
+        blocks.extend(self.types.values().cloned());
+        if !self.queries.is_empty() {
+            blocks.push(block("type Query", self.queries.values()));
+        }
+        write!(f, "{}", blocks.join("\n\n"))
+    }
+}
+
+fn insert_definition(
+    definitions: &mut BTreeMap<String, String>,
+    name: String,
+    definition: String,
+    conflict: fn(String) -> GraphQLError,
+) -> GraphQLResult<()> {
+    match definitions.get(&name) {
+        Some(existing) if *existing != definition => Err(conflict(name)),
+        Some(_) => Ok(()),
+        None => {
+            definitions.insert(name, definition);
+            Ok(())
+        }
+    }
+}
+
+fn block<'a>(header: &str, lines: impl IntoIterator<Item = &'a String>) -> String {
+    let mut out = format!("{header} {{\n");
+    for line in lines {
+        let _ = writeln!(out, "  {line}");
+    }
+    out.push('}');
+    out
+}
+
+fn pascal_case(name: &str) -> String {
+    crate::pascal_case(&identifier(name))
+}
+
+/// Builds GraphQL types from `TypeDef`s and tables into a `GraphQLSchema`.
+///
+/// Structs and enums are named after their definitions, tuples and results
+/// after the field they appear in. Enums without payloads become GraphQL enums,
+/// others a tagged object with a `variant` field and a nullable field per
+/// variant with a payload. Felts, addresses and integers over 32 bits use
+/// custom scalars carrying the JSON representation of `to_json`.
+#[derive(Default)]
+pub struct GraphQLGenerator<'a> {
+    refs: Option<&'a dyn GetRefTypeDef>,
+    in_progress: HashSet<String>,
+    schema: GraphQLSchema,
+}
+
+impl<'a> GraphQLGenerator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_refs(refs: &'a dyn GetRefTypeDef) -> Self {
+        GraphQLGenerator {
+            refs: Some(refs),
+            ..Default::default()
+        }
+    }
+
+    pub fn finish(self) -> GraphQLSchema {
+        self.schema
+    }
+
+    /// Adds the object type of a table, its connection types and the queries
+    /// fetching a record by primary and paginating by primary.
+    pub fn table(&mut self, table: &TableSchema) -> GraphQLResult<()> {
+        let name = pascal_case(&table.name);
+        let primary_name = identifier(&table.primary.name);
+        let primary = self.base_type(&(&table.primary.type_def).into(), &name)?;
+        let mut fields = vec![format!("{primary_name}: {primary}!")];
+        for column in &table.columns {
+            let context = format!("{name}{}", pascal_case(&column.name));
+            let field_type = self.field_type(&column.type_def, &context)?;
+            fields.push(format!("{}: {field_type}", identifier(&column.name)));
+        }
+        self.add_type(name.clone(), block(&format!("type {name}"), &fields))?;
+
+        let edge = format!("{name}Edge");
+        let connection = format!("{name}Connection");
+        self.add_type(
+            edge.clone(),
+            block(
+                &format!("type {edge}"),
+                &[format!("cursor: {primary}!"), format!("node: {name}!")],
+            ),
+        )?;
+        self.add_type(
+            connection.clone(),
+            block(
+                &format!("type {connection}"),
+                &[
+                    format!("edges: [{edge}!]!"),
+                    format!("pageInfo: {PAGE_INFO}!"),
+                    "totalCount: Int!".to_string(),
+                ],
+            ),
+        )?;
+        self.add_type(
+            PAGE_INFO.to_string(),
+            block(
+                &format!("type {PAGE_INFO}"),
+                &[
+                    "hasNextPage: Boolean!".to_string(),
+                    "hasPreviousPage: Boolean!".to_string(),
+                ],
+            ),
+        )?;
+
+        let query = camel_case(&name);
+        let list_query = format!("{query}Connection");
+        self.schema.queries.insert(
+            query.clone(),
+            format!("{query}({primary_name}: {primary}!): {name}"),
+        );
+        self.schema.queries.insert(
+            list_query.clone(),
+            format!("{list_query}(first: Int, after: {primary}): {connection}!"),
+        );
+        Ok(())
+    }
+
+    /// GraphQL type of a field holding `type_def`, non null unless it is an
+    /// option or nullable. `context` names anonymous types.
+    pub fn field_type(&mut self, type_def: &TypeDef, context: &str) -> GraphQLResult<String> {
+        match type_def {
+            TypeDef::Option(option) => self.base_type(&option.type_def, context),
+            TypeDef::Nullable(nullable) => self.base_type(&nullable.type_def, context),
+            _ => Ok(format!("{}!", self.base_type(type_def, context)?)),
+        }
+    }
+
+    fn base_type(&mut self, type_def: &TypeDef, context: &str) -> GraphQLResult<String> {
+        Ok(match type_def {
+            TypeDef::Bool => "Boolean".to_string(),
+            TypeDef::ShortUtf8 | TypeDef::Utf8String => "String".to_string(),
+            TypeDef::U8 | TypeDef::U16 | TypeDef::I8 | TypeDef::I16 | TypeDef::I32 => {
+                "Int".to_string()
+            }
+            TypeDef::None => self.scalar("Unit"),
+            TypeDef::Felt252 | TypeDef::StorageAddress | TypeDef::StorageBaseAddress => {
+                self.scalar("Felt")
+            }
+            TypeDef::ClassHash => self.scalar("ClassHash"),
+            TypeDef::ContractAddress => self.scalar("ContractAddress"),
+            TypeDef::EthAddress => self.scalar("EthAddress"),
+            TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => self.scalar("Bytes31"),
+            TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) => self.scalar("ByteArray"),
+            TypeDef::U32 => self.scalar("U32"),
+            TypeDef::U64 => self.scalar("U64"),
+            TypeDef::U128 => self.scalar("U128"),
+            TypeDef::U256 => self.scalar("U256"),
+            TypeDef::U512 => self.scalar("U512"),
+            TypeDef::I64 => self.scalar("I64"),
+            TypeDef::I128 => self.scalar("I128"),
+            TypeDef::Felt252Dict(_) => self.scalar("JSON"),
+            TypeDef::Custom(_) => format!("[{}!]", self.scalar("Felt")),
+            TypeDef::Array(array) => {
+                format!("[{}]", self.field_type(&array.type_def, context)?)
+            }
+            TypeDef::FixedArray(fixed) => {
+                format!("[{}]", self.field_type(&fixed.type_def, context)?)
+            }
+            TypeDef::Option(option) => self.base_type(&option.type_def, context)?,
+            TypeDef::Nullable(nullable) => self.base_type(&nullable.type_def, context)?,
+            TypeDef::Tuple(tuple) => {
+                let name = identifier(context);
+                let fields = tuple
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element)| {
+                        let field_type = self.field_type(element, &format!("{name}{i}"))?;
+                        Ok(format!("_{i}: {field_type}"))
+                    })
+                    .collect::<GraphQLResult<Vec<_>>>()?;
+                self.add_type(name.clone(), block(&format!("type {name}"), &fields))?;
+                name
+            }
+            TypeDef::Result(result) => {
+                let name = identifier(context);
+                let fields = [
+                    format!("Ok: {}", self.base_type(&result.ok, &format!("{name}Ok"))?),
+                    format!(
+                        "Err: {}",
+                        self.base_type(&result.err, &format!("{name}Err"))?
+                    ),
+                ];
+                self.add_type(name.clone(), block(&format!("type {name}"), &fields))?;
+                name
+            }
+            TypeDef::Struct(struct_def) => self.struct_type(struct_def)?,
+            TypeDef::Enum(enum_def) => self.enum_type(enum_def)?,
+            TypeDef::Ref(reference) => {
+                let type_def = self
+                    .refs
+                    .and_then(|refs| refs.get_type_def(reference.id))
+                    .ok_or(GraphQLError::UnknownRef(reference.id))?;
+                if let TypeDef::Struct(_) | TypeDef::Enum(_) = type_def {
+                    return self.base_type(&type_def, context);
+                }
+                // Only structs and enums have a name to point back to, other
+                // types reached again through the same reference never end.
+                let key = felt_to_hex_string(&reference.id);
+                if !self.in_progress.insert(key.clone()) {
+                    return Err(GraphQLError::RecursiveRef(reference.id));
+                }
+                let base = self.base_type(&type_def, context);
+                self.in_progress.remove(&key);
+                base?
+            }
+        })
+    }
+
+    fn struct_type(&mut self, struct_def: &StructDef) -> GraphQLResult<String> {
+        let name = identifier(&struct_def.name);
+        if !self.in_progress.insert(name.clone()) {
+            return Ok(name);
+        }
+        let fields = struct_def
+            .members
+            .iter()
+            .map(|member| {
+                let context = format!("{name}{}", pascal_case(&member.name));
+                let field_type = self.field_type(&member.type_def, &context)?;
+                Ok(format!("{}: {field_type}", identifier(&member.name)))
+            })
+            .collect::<GraphQLResult<Vec<_>>>();
+        self.in_progress.remove(&name);
+        self.add_type(name.clone(), block(&format!("type {name}"), &fields?))?;
+        Ok(name)
+    }
+
+    fn enum_type(&mut self, enum_def: &EnumDef) -> GraphQLResult<String> {
+        let name = identifier(&enum_def.name);
+        let variants: Vec<_> = enum_def
+            .order
+            .iter()
+            .filter_map(|selector| enum_def.variants.get(selector))
+            .collect();
+        let names: Vec<String> = variants.iter().map(|v| identifier(&v.name)).collect();
+        if variants.iter().all(|v| v.type_def == TypeDef::None) {
+            self.add_type(name.clone(), block(&format!("enum {name}"), &names))?;
+            return Ok(name);
+        }
+        if !self.in_progress.insert(name.clone()) {
+            return Ok(name);
+        }
+        let tag = format!("{name}Variant");
+        let mut fields = vec![format!("variant: {tag}!")];
+        let payloads = variants
+            .iter()
+            .zip(&names)
+            .filter(|(variant, _)| variant.type_def != TypeDef::None)
+            .map(|(variant, field)| {
+                let context = format!("{name}{}", pascal_case(field));
+                Ok(format!(
+                    "{field}: {}",
+                    self.base_type(&variant.type_def, &context)?
+                ))
+            })
+            .collect::<GraphQLResult<Vec<_>>>();
+        self.in_progress.remove(&name);
+        fields.extend(payloads?);
+        self.add_type(tag.clone(), block(&format!("enum {tag}"), &names))?;
+        self.add_type(name.clone(), block(&format!("type {name}"), &fields))?;
+        Ok(name)
+    }
+
+    fn scalar(&mut self, name: &str) -> String {
+        self.schema.scalars.insert(name.to_string());
+        name.to_string()
+    }
+
+    fn add_type(&mut self, name: String, definition: String) -> GraphQLResult<()> {
+        insert_definition(
+            &mut self.schema.types,
+            name,
+            definition,
+            GraphQLError::ConflictingType,
+        )
+    }
+}
+
+impl TableSchema {
+    /// GraphQL types and queries of the table, see `GraphQLGenerator`.
+    pub fn graphql_schema(&self) -> GraphQLResult<GraphQLSchema> {
+        let mut generator = GraphQLGenerator::new();
+        generator.table(self)?;
+        Ok(generator.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ColumnDef, MemberDef, OptionDef, PrimaryDef, PrimaryTypeDef, RefDef, TupleDef, VariantDef,
+    };
+    use std::collections::HashMap;
+
+    fn direction(with_payload: bool) -> TypeDef {
+        let jump = match with_payload {
+            true => TypeDef::U8,
+            false => TypeDef::None,
+        };
+        EnumDef::new_type_def(
+            "game::Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::ONE,
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (Felt::TWO, VariantDef::new("Jump".to_string(), vec![], jump)),
+            ],
+        )
+    }
+
+    fn player(columns: Vec<ColumnDef>) -> TableSchema {
+        TableSchema {
+            id: Felt::ONE,
+            name: "player".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("owner".to_string(), vec![], PrimaryTypeDef::ContractAddress),
+            columns,
+        }
+    }
+
+    #[test]
+    fn generates_table_types_and_connections() {
+        let table = player(vec![
+            ColumnDef::new(Felt::ONE, "gold".to_string(), vec![], TypeDef::U256),
+            ColumnDef::new(
+                Felt::TWO,
+                "position".to_string(),
+                vec![],
+                OptionDef::new_type_def(TupleDef::new_type_def(vec![TypeDef::U8, TypeDef::I8])),
+            ),
+            ColumnDef::new(Felt::THREE, "facing".to_string(), vec![], direction(false)),
+            ColumnDef::new(Felt::from(4), "last".to_string(), vec![], direction(true)),
+        ]);
+        let result = table.graphql_schema();
+        assert_eq!(
+            result,
+            Err(GraphQLError::ConflictingType("Direction".to_string()))
+        );
+
+        let mut table = table;
+        table.columns.pop();
+        let sdl = table.graphql_schema().unwrap().to_string();
+        for expected in [
+            "scalar ContractAddress",
+            "scalar U256",
+            "type Player {\n  owner: ContractAddress!\n  gold: U256!\n  position: PlayerPosition\n  facing: Direction!\n}",
+            "type PlayerPosition {\n  _0: Int!\n  _1: Int!\n}",
+            "enum Direction {\n  Up\n  Jump\n}",
+            "type PlayerEdge {\n  cursor: ContractAddress!\n  node: Player!\n}",
+            "edges: [PlayerEdge!]!",
+            "player(owner: ContractAddress!): Player",
+            "playerConnection(first: Int, after: ContractAddress): PlayerConnection!",
+        ] {
+            assert!(sdl.contains(expected), "missing {expected:?} in\n{sdl}");
+        }
+    }
+
+    #[test]
+    fn tags_enums_and_resolves_recursive_refs() {
+        let node = StructDef::new_type_def(
+            "Node".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("step".to_string(), vec![], direction(true)),
+                MemberDef::new(
+                    "next".to_string(),
+                    vec![],
+                    OptionDef::new_type_def(RefDef::new_type_def(Felt::from(7))),
+                ),
+            ],
+        );
+        let refs = HashMap::from([(Felt::from(7), node)]);
+        let table = player(vec![ColumnDef::new(
+            Felt::ONE,
+            "path".to_string(),
+            vec![],
+            RefDef::new_type_def(Felt::from(7)),
+        )]);
+        assert_eq!(
+            table.graphql_schema(),
+            Err(GraphQLError::UnknownRef(Felt::from(7)))
+        );
+
+        let mut generator = GraphQLGenerator::with_refs(&refs);
+        generator.table(&table).unwrap();
+        let schema = generator.finish();
+        assert_eq!(
+            schema.types["Node"],
+            "type Node {\n  step: Direction!\n  next: Node\n}"
+        );
+        assert_eq!(
+            schema.types["Direction"],
+            "type Direction {\n  variant: DirectionVariant!\n  Jump: Int\n}"
+        );
+        assert_eq!(
+            schema.types["DirectionVariant"],
+            "enum DirectionVariant {\n  Up\n  Jump\n}"
+        );
+
+        let list = TupleDef::new_type_def(vec![
+            TypeDef::U8,
+            OptionDef::new_type_def(RefDef::new_type_def(Felt::from(8))),
+        ]);
+        let refs = HashMap::from([(Felt::from(8), list)]);
+        let table = player(vec![ColumnDef::new(
+            Felt::ONE,
+            "list".to_string(),
+            vec![],
+            RefDef::new_type_def(Felt::from(8)),
+        )]);
+        assert_eq!(
+            GraphQLGenerator::with_refs(&refs).table(&table),
+            Err(GraphQLError::RecursiveRef(Felt::from(8)))
+        );
+
+        let mut merged = player(vec![]).graphql_schema().unwrap();
+        assert_eq!(
+            merged.merge(&schema),
+            Err(GraphQLError::ConflictingType("Player".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_queries_on_merge() {
+        let query = |primary: &str| GraphQLSchema {
+            queries: BTreeMap::from([(
+                "player".to_string(),
+                format!("player(owner: {primary}!): Player"),
+            )]),
+            ..Default::default()
+        };
+        let mut merged = query("Felt");
+        assert_eq!(merged.merge(&query("Felt")), Ok(()));
+        assert_eq!(
+            merged.merge(&query("U32")),
+            Err(GraphQLError::ConflictingQuery("player".to_string()))
+        );
+    }
+}
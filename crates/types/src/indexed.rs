@@ -0,0 +1,142 @@
+use crate::deserialize::{CairoDeserializer, EnumVariantEncoding};
+use crate::limits::DecodeBudget;
+use crate::path::DecodePath;
+use crate::{ByteArray, DecodeResult, FeltSource};
+use starknet_types_core::felt::Felt;
+
+/// Wraps a deserializer so enum variants are read as zero-based indices into
+/// `EnumDef::order`, as produced by standard Cairo `Serde`, instead of selectors.
+pub struct IndexedEnums<D: CairoDeserializer>(pub D);
+
+impl<D: CairoDeserializer> IndexedEnums<D> {
+    pub fn new(deserializer: D) -> Self {
+        IndexedEnums(deserializer)
+    }
+
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+impl<D: CairoDeserializer> CairoDeserializer for IndexedEnums<D> {
+    fn next_felt(&mut self) -> DecodeResult<Felt> {
+        self.0.next_felt()
+    }
+
+    fn decode_budget(&mut self) -> Option<&mut DecodeBudget> {
+        self.0.decode_budget()
+    }
+
+    fn decode_path(&mut self) -> Option<&mut DecodePath> {
+        self.0.decode_path()
+    }
+
+    fn source_position(&self) -> Option<usize> {
+        self.0.source_position()
+    }
+
+    fn enum_variant_encoding(&self) -> EnumVariantEncoding {
+        EnumVariantEncoding::Index
+    }
+
+    fn next_enum_variant(&mut self) -> DecodeResult<Felt> {
+        self.0.next_enum_variant()
+    }
+
+    fn next_option_is_some(&mut self) -> DecodeResult<bool> {
+        self.0.next_option_is_some()
+    }
+
+    fn next_result_is_ok(&mut self) -> DecodeResult<bool> {
+        self.0.next_result_is_ok()
+    }
+
+    fn next_nullable_is_null(&mut self) -> DecodeResult<bool> {
+        self.0.next_nullable_is_null()
+    }
+
+    fn next_byte_array(&mut self) -> DecodeResult<ByteArray> {
+        self.0.next_byte_array()
+    }
+
+    fn skip_byte_array(&mut self) -> DecodeResult<usize> {
+        self.0.skip_byte_array()
+    }
+}
+
+impl<D: CairoDeserializer + FeltSource> FeltSource for IndexedEnums<D> {
+    fn next(&mut self) -> Result<Felt, crate::DecodeError> {
+        self.0.next()
+    }
+
+    fn position(&self) -> usize {
+        self.0.position()
+    }
+
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        self.0.budget()
+    }
+
+    fn path(&mut self) -> Option<&mut DecodePath> {
+        self.0.path()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TypeParserError;
+    use crate::{CairoSerde, EnumDef, ParseValue, TypeDef, Value, VariantDef};
+
+    fn direction() -> EnumDef {
+        EnumDef::new(
+            "Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::from(0x100),
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::from(0x200),
+                    VariantDef::new("Down".to_string(), vec![], TypeDef::U8),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn parses_variant_by_index() {
+        let felts = vec![Felt::ONE, Felt::from(7)];
+        let mut deserializer = IndexedEnums(CairoSerde::from(felts));
+        let value = direction().parse(&mut deserializer).unwrap();
+        assert_eq!(value.variant, "Down");
+        assert_eq!(value.value, Value::U8(7));
+    }
+
+    #[test]
+    fn distinguishes_index_and_selector_errors() {
+        let felts = vec![Felt::TWO];
+        let mut deserializer = IndexedEnums(CairoSerde::from(felts.clone()));
+        assert!(matches!(
+            direction().parse(&mut deserializer),
+            Err(TypeParserError::EnumIndexOutOfRange { len: 2, .. })
+        ));
+        let mut deserializer = CairoSerde::from(felts);
+        assert!(matches!(
+            direction().parse(&mut deserializer),
+            Err(TypeParserError::InvalidEnumSelector { .. })
+        ));
+    }
+
+    #[test]
+    fn keeps_the_cause_of_index_lookup_errors() {
+        let mut enum_def = direction();
+        enum_def.order.push(Felt::from(0x300));
+        let mut deserializer = IndexedEnums(CairoSerde::from(vec![Felt::TWO]));
+        assert!(matches!(
+            enum_def.parse(&mut deserializer),
+            Err(TypeParserError::InvalidEnumSelector { .. })
+        ));
+    }
+}
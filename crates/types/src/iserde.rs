@@ -1,6 +1,8 @@
 use crate::decode_error::DecodeResultTrait;
-use crate::deserialize::CairoDeserializer;
+use crate::deserialize::{CairoDeserialize, CairoDeserializeRemaining, CairoDeserializer};
 use crate::felt::IntoFeltSource;
+use crate::limits::DecodeBudget;
+use crate::path::DecodePath;
 use crate::{ByteArray, DecodeResult, FeltSource};
 use starknet_types_core::felt::Felt;
 
@@ -11,6 +13,18 @@ impl<I: FeltSource> CairoDeserializer for CairoISerde<I> {
         self.0.next()
     }
 
+    fn decode_budget(&mut self) -> Option<&mut DecodeBudget> {
+        self.0.budget()
+    }
+
+    fn decode_path(&mut self) -> Option<&mut DecodePath> {
+        self.0.path()
+    }
+
+    fn source_position(&self) -> Option<usize> {
+        Some(self.0.position())
+    }
+
     fn next_option_is_some(&mut self) -> DecodeResult<bool> {
         self.next_bool_tag("option")
     }
@@ -20,6 +34,26 @@ impl<I: FeltSource> CairoDeserializer for CairoISerde<I> {
             .map(|(bytes, _)| bytes)
             .map(Into::into)
     }
+
+    fn skip_byte_array(&mut self) -> DecodeResult<usize> {
+        let mut felts = 0;
+        let mut len = 0;
+        loop {
+            let [info, felt_bytes @ ..] = match felts {
+                0 => self.next_felt_bytes()?,
+                _ => self.next_felt_bytes().raise_eof()?,
+            };
+            felts += 1;
+            len += match info & 2 {
+                0 => 31,
+                _ => felt_bytes[1] as usize,
+            };
+            self.check_byte_array_len(len)?;
+            if info & 1 == 1 {
+                return Ok(felts);
+            }
+        }
+    }
 }
 
 impl<'a, S: FeltSource + ?Sized> CairoISerde<&'a mut S> {
@@ -46,6 +80,7 @@ impl<I: FeltSource> CairoISerde<I> {
                 0 => &felt_bytes,
                 _ => &felt_bytes[(31 - felt_bytes[1] as usize)..31],
             });
+            deserializer.check_byte_array_len(bytes.len())?;
             Ok(info)
         }
 
@@ -64,6 +99,12 @@ impl<I: FeltSource> CairoISerde<I> {
     }
 }
 
+impl<F: FeltSource> CairoDeserializeRemaining for CairoISerde<F> {
+    fn deserialize_remaining<T: CairoDeserialize<Self>>(&mut self) -> DecodeResult<Vec<T>> {
+        self.drain_values()
+    }
+}
+
 impl<I: FeltSource> FeltSource for CairoISerde<I> {
     fn next(&mut self) -> Result<Felt, crate::DecodeError> {
         self.0.next()
@@ -0,0 +1,635 @@
+use crate::path::{DecodePath, PathSegment};
+use crate::{
+    Bytes31EncodedDef, CairoOption, CairoResult, Custom, Encoded31Bytes, EncodedBytes, Enum,
+    EnumDef, EthAddress, Member, Nullable, Struct, StructDef, TypeDef, Value,
+    bytes31_to_hex_string, felt_to_hex_string,
+};
+use primitive_types::{U256, U512};
+use serde_json::{Map, Value as Json};
+use starknet_types_core::felt::Felt;
+use std::fmt::{self, Display};
+use thiserror::Error;
+
+#[derive(Clone, Debug, Error, PartialEq)]
+pub enum JsonErrorKind {
+    #[error("expected {expected}, found {found}")]
+    InvalidType {
+        expected: &'static str,
+        found: &'static str,
+    },
+    #[error("invalid {expected}: {value}")]
+    InvalidValue {
+        expected: &'static str,
+        value: String,
+    },
+    #[error("expected {expected} elements, found {found}")]
+    InvalidLength { expected: usize, found: usize },
+    #[error("missing member: {0}")]
+    MissingMember(String),
+    #[error("unknown member: {0}")]
+    UnknownMember(String),
+    #[error("unknown variant: {0}")]
+    UnknownVariant(String),
+    #[error("{0} is not supported")]
+    Unsupported(&'static str),
+}
+
+/// Error converting JSON to a `Value`, with the path of the offending item.
+#[derive(Clone, Debug, Error, PartialEq)]
+pub struct JsonError {
+    pub path: DecodePath,
+    pub kind: JsonErrorKind,
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.is_empty() {
+            true => write!(f, "{}", self.kind),
+            false => write!(f, "{} at {}", self.kind, self.path),
+        }
+    }
+}
+
+pub type JsonResult<T> = Result<T, JsonError>;
+
+fn hex_bytes(bytes: &[u8]) -> Json {
+    Json::String(format!("0x{}", hex::encode(bytes)))
+}
+
+/// Converts a value to plain JSON: felts and byte arrays as `0x` hex strings,
+/// integers above 64 bits as decimal strings, structs as objects, enums and
+/// results as `{variant: payload}` and empty options or nullables as `null`.
+pub fn to_json(value: &Value) -> Json {
+    match value {
+        Value::None => Json::Null,
+        Value::Felt252(felt)
+        | Value::ClassHash(felt)
+        | Value::ContractAddress(felt)
+        | Value::StorageAddress(felt)
+        | Value::StorageBaseAddress(felt) => Json::String(felt_to_hex_string(felt)),
+        Value::EthAddress(felt) => match EthAddress::try_from(felt) {
+            Ok(address) => hex_bytes(&address.0),
+            Err(_) => Json::String(felt_to_hex_string(felt)),
+        },
+        Value::ShortUtf8(string) | Value::Utf8String(string) => Json::String(string.clone()),
+        Value::Bytes31(bytes) => Json::String(bytes31_to_hex_string(bytes)),
+        Value::Bytes31Encoded(encoded) => Json::String(bytes31_to_hex_string(encoded.bytes)),
+        Value::Bool(value) => Json::Bool(*value),
+        Value::U8(value) => (*value).into(),
+        Value::U16(value) => (*value).into(),
+        Value::U32(value) => (*value).into(),
+        Value::U64(value) => (*value).into(),
+        Value::U128(value) => Json::String(value.to_string()),
+        Value::U256(value) => Json::String(value.to_string()),
+        Value::U512(value) => Json::String(value.to_string()),
+        Value::I8(value) => (*value).into(),
+        Value::I16(value) => (*value).into(),
+        Value::I32(value) => (*value).into(),
+        Value::I64(value) => (*value).into(),
+        Value::I128(value) => Json::String(value.to_string()),
+        Value::ByteArray(bytes) => hex_bytes(bytes),
+        Value::ByteArrayEncoded(encoded) => hex_bytes(&encoded.bytes),
+        Value::Tuple(values) | Value::Array(values) | Value::FixedArray(values) => {
+            Json::Array(values.iter().map(to_json).collect())
+        }
+        Value::Felt252Dict(entries) => Json::Object(
+            entries
+                .iter()
+                .map(|(key, value)| (felt_to_hex_string(key), to_json(value)))
+                .collect(),
+        ),
+        Value::Struct(s) => Json::Object(
+            s.members
+                .iter()
+                .map(|m| (m.name.clone(), to_json(&m.value)))
+                .collect(),
+        ),
+        Value::Enum(e) => variant(&e.variant, &e.value),
+        Value::Custom(custom) => Json::Array(
+            custom
+                .values
+                .iter()
+                .map(|felt| Json::String(felt_to_hex_string(felt)))
+                .collect(),
+        ),
+        Value::Option(option) => match option.as_ref() {
+            CairoOption::Some(value) => to_json(value),
+            CairoOption::None => Json::Null,
+        },
+        Value::Result(result) => match result.as_ref() {
+            CairoResult::Ok(value) => variant("Ok", value),
+            CairoResult::Err(value) => variant("Err", value),
+        },
+        Value::Nullable(nullable) => match nullable.as_ref() {
+            Nullable::NotNull(value) => to_json(value),
+            Nullable::Null => Json::Null,
+        },
+    }
+}
+
+fn variant(name: &str, value: &Value) -> Json {
+    Json::Object(Map::from_iter([(name.to_string(), to_json(value))]))
+}
+
+/// Parses JSON into a value of `type_def`, the inverse of `to_json`.
+///
+/// Felts and integers are read from numbers, decimal strings or `0x` hex
+/// strings. Enum variants without a payload can also be given as a bare string.
+/// `Option<Option<T>>` can't tell `None` from `Some(None)` and reads `null` as
+/// the outer `None`.
+pub fn from_json(type_def: &TypeDef, json: Json) -> JsonResult<Value> {
+    JsonParser::default().parse(type_def, json)
+}
+
+#[derive(Default)]
+struct JsonParser {
+    path: DecodePath,
+}
+
+fn json_type(json: &Json) -> &'static str {
+    match json {
+        Json::Null => "null",
+        Json::Bool(_) => "bool",
+        Json::Number(_) => "number",
+        Json::String(_) => "string",
+        Json::Array(_) => "array",
+        Json::Object(_) => "object",
+    }
+}
+
+fn parse_uint<T: TryFrom<u128>>(string: &str) -> Option<T> {
+    let value = match string.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(hex, 16).ok()?,
+        None => string.parse().ok()?,
+    };
+    value.try_into().ok()
+}
+
+fn parse_int<T: TryFrom<i128>>(string: &str) -> Option<T> {
+    let value = match string.strip_prefix("0x") {
+        Some(hex) => i128::from_str_radix(hex, 16).ok()?,
+        None => string.parse().ok()?,
+    };
+    value.try_into().ok()
+}
+
+impl JsonParser {
+    fn error<T>(&self, kind: JsonErrorKind) -> JsonResult<T> {
+        Err(JsonError {
+            path: self.path.clone(),
+            kind,
+        })
+    }
+
+    fn invalid_type<T>(&self, expected: &'static str, json: &Json) -> JsonResult<T> {
+        self.error(JsonErrorKind::InvalidType {
+            expected,
+            found: json_type(json),
+        })
+    }
+
+    fn invalid_value<T>(&self, expected: &'static str, json: &Json) -> JsonResult<T> {
+        let value = match json {
+            Json::String(string) => string.clone(),
+            json => json.to_string(),
+        };
+        self.error(JsonErrorKind::InvalidValue { expected, value })
+    }
+
+    fn segment<T>(
+        &mut self,
+        segment: PathSegment,
+        f: impl FnOnce(&mut Self) -> JsonResult<T>,
+    ) -> JsonResult<T> {
+        self.path.push(segment);
+        let result = f(self);
+        self.path.pop();
+        result
+    }
+
+    fn parse(&mut self, type_def: &TypeDef, json: Json) -> JsonResult<Value> {
+        match type_def {
+            TypeDef::None => match json {
+                Json::Null => Ok(Value::None),
+                json => self.invalid_type("null", &json),
+            },
+            TypeDef::Felt252 => self.felt(&json).map(Value::Felt252),
+            TypeDef::ClassHash => self.felt(&json).map(Value::ClassHash),
+            TypeDef::ContractAddress => self.felt(&json).map(Value::ContractAddress),
+            TypeDef::StorageAddress => self.felt(&json).map(Value::StorageAddress),
+            TypeDef::StorageBaseAddress => self.felt(&json).map(Value::StorageBaseAddress),
+            TypeDef::EthAddress => {
+                let felt = self.felt(&json)?;
+                match EthAddress::try_from(&felt) {
+                    Ok(_) => Ok(Value::EthAddress(felt)),
+                    Err(_) => self.invalid_value("eth address", &json),
+                }
+            }
+            TypeDef::ShortUtf8 => match self.string(json)? {
+                string if string.len() <= 31 => Ok(Value::ShortUtf8(string)),
+                string => self.invalid_value("short string", &Json::String(string)),
+            },
+            TypeDef::Utf8String => self.string(json).map(Value::Utf8String),
+            TypeDef::Bytes31 => self.bytes31(&json).map(Value::Bytes31),
+            TypeDef::Bytes31Encoded(Bytes31EncodedDef { encoding }) => {
+                self.bytes31(&json).map(|bytes| {
+                    Value::Bytes31Encoded(Encoded31Bytes {
+                        encoding: encoding.clone(),
+                        bytes,
+                    })
+                })
+            }
+            TypeDef::ByteArray => self.bytes(&json).map(Value::ByteArray),
+            TypeDef::ByteArrayEncoded(def) => self.bytes(&json).map(|bytes| {
+                Value::ByteArrayEncoded(EncodedBytes {
+                    encoding: def.encoding.clone(),
+                    bytes,
+                })
+            }),
+            TypeDef::Bool => match json {
+                Json::Bool(value) => Ok(Value::Bool(value)),
+                json => self.invalid_type("bool", &json),
+            },
+            TypeDef::U8 => self.uint("u8", &json).map(Value::U8),
+            TypeDef::U16 => self.uint("u16", &json).map(Value::U16),
+            TypeDef::U32 => self.uint("u32", &json).map(Value::U32),
+            TypeDef::U64 => self.uint("u64", &json).map(Value::U64),
+            TypeDef::U128 => self.uint("u128", &json).map(Value::U128),
+            TypeDef::I8 => self.int("i8", &json).map(Value::I8),
+            TypeDef::I16 => self.int("i16", &json).map(Value::I16),
+            TypeDef::I32 => self.int("i32", &json).map(Value::I32),
+            TypeDef::I64 => self.int("i64", &json).map(Value::I64),
+            TypeDef::I128 => self.int("i128", &json).map(Value::I128),
+            TypeDef::U256 => {
+                let value = self.big_uint("u256", &json, U256::from_dec_str, |hex| {
+                    U256::from_str_radix(hex, 16).ok()
+                })?;
+                Ok(Value::U256(value))
+            }
+            TypeDef::U512 => {
+                let value = self.big_uint("u512", &json, U512::from_dec_str, |hex| {
+                    U512::from_str_radix(hex, 16).ok()
+                })?;
+                Ok(Value::U512(value))
+            }
+            TypeDef::Tuple(tuple) => {
+                let values = self.array(json, Some(tuple.elements.len()))?;
+                self.elements(tuple.elements.iter().zip(values))
+                    .map(Value::Tuple)
+            }
+            TypeDef::Array(array) => {
+                let values = self.array(json, None)?;
+                self.elements(std::iter::repeat(&array.type_def).zip(values))
+                    .map(Value::Array)
+            }
+            TypeDef::FixedArray(fixed) => {
+                let values = self.array(json, Some(fixed.size as usize))?;
+                self.elements(std::iter::repeat(&fixed.type_def).zip(values))
+                    .map(Value::FixedArray)
+            }
+            TypeDef::Felt252Dict(dict) => {
+                let Json::Object(entries) = json else {
+                    return self.invalid_type("object", &json);
+                };
+                let mut values = Vec::with_capacity(entries.len());
+                for (key, value) in entries {
+                    let felt = self.felt(&Json::String(key.clone()))?;
+                    let value =
+                        self.segment(PathSegment::Member(key), |p| p.parse(&dict.type_def, value))?;
+                    values.push((felt, value));
+                }
+                Ok(Value::Felt252Dict(values))
+            }
+            TypeDef::Struct(struct_def) => self
+                .segment(PathSegment::Type(struct_def.name.clone()), |p| {
+                    p.parse_struct(struct_def, json)
+                }),
+            TypeDef::Enum(enum_def) => self
+                .segment(PathSegment::Type(enum_def.name.clone()), |p| {
+                    p.parse_enum(enum_def, json)
+                }),
+            TypeDef::Option(option) => match json {
+                Json::Null => Ok(Value::Option(Box::new(CairoOption::None))),
+                json => self
+                    .parse(&option.type_def, json)
+                    .map(|value| Value::Option(Box::new(CairoOption::Some(value)))),
+            },
+            TypeDef::Nullable(nullable) => match json {
+                Json::Null => Ok(Value::Nullable(Box::new(Nullable::Null))),
+                json => self
+                    .parse(&nullable.type_def, json)
+                    .map(|value| Value::Nullable(Box::new(Nullable::NotNull(value)))),
+            },
+            TypeDef::Result(result) => {
+                let (name, payload) = self.variant(json)?;
+                let result = match name.as_str() {
+                    "Ok" => self
+                        .segment(PathSegment::Variant(name), |p| p.parse(&result.ok, payload))
+                        .map(CairoResult::Ok),
+                    "Err" => self
+                        .segment(PathSegment::Variant(name), |p| {
+                            p.parse(&result.err, payload)
+                        })
+                        .map(CairoResult::Err),
+                    _ => self.error(JsonErrorKind::UnknownVariant(name)),
+                }?;
+                Ok(Value::Result(Box::new(result)))
+            }
+            TypeDef::Custom(custom) => {
+                let values = self.array(json, None)?;
+                let mut felts = Vec::with_capacity(values.len());
+                for (index, value) in values.into_iter().enumerate() {
+                    felts.push(self.segment(PathSegment::Index(index), |p| p.felt(&value))?);
+                }
+                Ok(Value::Custom(Custom {
+                    encoding: custom.encoding.clone(),
+                    values: felts,
+                }))
+            }
+            TypeDef::Ref(_) => self.error(JsonErrorKind::Unsupported("TypeDef Ref")),
+        }
+    }
+
+    fn parse_struct(&mut self, struct_def: &StructDef, json: Json) -> JsonResult<Value> {
+        let Json::Object(mut entries) = json else {
+            return self.invalid_type("object", &json);
+        };
+        let mut members = Vec::with_capacity(struct_def.members.len());
+        for member in &struct_def.members {
+            let Some(value) = entries.remove(&member.name) else {
+                return self.error(JsonErrorKind::MissingMember(member.name.clone()));
+            };
+            members.push(Member {
+                name: member.name.clone(),
+                attributes: member.attributes.clone(),
+                value: self.segment(PathSegment::Member(member.name.clone()), |p| {
+                    p.parse(&member.type_def, value)
+                })?,
+            });
+        }
+        if let Some(name) = entries.keys().next() {
+            return self.error(JsonErrorKind::UnknownMember(name.clone()));
+        }
+        Ok(Value::Struct(Struct {
+            name: struct_def.name.clone(),
+            attributes: struct_def.attributes.clone(),
+            members,
+        }))
+    }
+
+    fn parse_enum(&mut self, enum_def: &EnumDef, json: Json) -> JsonResult<Value> {
+        let (name, payload) = match json {
+            Json::String(name) => (name, Json::Null),
+            json => self.variant(json)?,
+        };
+        let Some(variant) = enum_def
+            .order
+            .iter()
+            .filter_map(|selector| enum_def.variants.get(selector))
+            .find(|v| v.name == name)
+        else {
+            return self.error(JsonErrorKind::UnknownVariant(name));
+        };
+        let value = self.segment(PathSegment::Variant(name), |p| {
+            p.parse(&variant.type_def, payload)
+        })?;
+        Ok(Value::Enum(Box::new(Enum {
+            name: enum_def.name.clone(),
+            attributes: enum_def.attributes.clone(),
+            variant: variant.name.clone(),
+            variant_attributes: variant.attributes.clone(),
+            value,
+        })))
+    }
+
+    fn variant(&self, json: Json) -> JsonResult<(String, Json)> {
+        match json {
+            Json::Object(entries) if entries.len() == 1 => {
+                Ok(entries.into_iter().next().expect("one entry"))
+            }
+            Json::Object(entries) => self.error(JsonErrorKind::InvalidLength {
+                expected: 1,
+                found: entries.len(),
+            }),
+            json => self.invalid_type("object", &json),
+        }
+    }
+
+    fn elements<'a>(
+        &mut self,
+        elements: impl Iterator<Item = (&'a TypeDef, Json)>,
+    ) -> JsonResult<Vec<Value>> {
+        elements
+            .enumerate()
+            .map(|(index, (type_def, json))| {
+                self.segment(PathSegment::Index(index), |p| p.parse(type_def, json))
+            })
+            .collect()
+    }
+
+    fn array(&self, json: Json, len: Option<usize>) -> JsonResult<Vec<Json>> {
+        match (json, len) {
+            (Json::Array(values), Some(len)) if values.len() != len => {
+                self.error(JsonErrorKind::InvalidLength {
+                    expected: len,
+                    found: values.len(),
+                })
+            }
+            (Json::Array(values), _) => Ok(values),
+            (json, _) => self.invalid_type("array", &json),
+        }
+    }
+
+    fn string(&self, json: Json) -> JsonResult<String> {
+        match json {
+            Json::String(string) => Ok(string),
+            json => self.invalid_type("string", &json),
+        }
+    }
+
+    fn felt(&self, json: &Json) -> JsonResult<Felt> {
+        let felt = match json {
+            Json::Number(number) => number.as_u64().map(Felt::from),
+            Json::String(string) => match string.starts_with("0x") {
+                true => Felt::from_hex(string).ok(),
+                false => Felt::from_dec_str(string).ok(),
+            },
+            json => return self.invalid_type("felt", json),
+        };
+        felt.map_or_else(|| self.invalid_value("felt", json), Ok)
+    }
+
+    fn bytes(&self, json: &Json) -> JsonResult<Vec<u8>> {
+        let Json::String(string) = json else {
+            return self.invalid_type("hex string", json);
+        };
+        match string.strip_prefix("0x").map(hex::decode) {
+            Some(Ok(bytes)) => Ok(bytes),
+            _ => self.invalid_value("hex bytes", json),
+        }
+    }
+
+    fn bytes31(&self, json: &Json) -> JsonResult<[u8; 31]> {
+        let bytes = self.bytes(json)?;
+        match bytes.len() <= 31 {
+            true => {
+                let mut bytes31 = [0; 31];
+                bytes31[31 - bytes.len()..].copy_from_slice(&bytes);
+                Ok(bytes31)
+            }
+            false => self.invalid_value("bytes31", json),
+        }
+    }
+
+    fn uint<T: TryFrom<u128>>(&self, expected: &'static str, json: &Json) -> JsonResult<T> {
+        let value = match json {
+            Json::Number(number) => number.as_u64().and_then(|n| T::try_from(n as u128).ok()),
+            Json::String(string) => parse_uint(string),
+            json => return self.invalid_type("integer", json),
+        };
+        value.map_or_else(|| self.invalid_value(expected, json), Ok)
+    }
+
+    fn int<T: TryFrom<i128>>(&self, expected: &'static str, json: &Json) -> JsonResult<T> {
+        let value = match json {
+            Json::Number(number) => number.as_i64().and_then(|n| T::try_from(n as i128).ok()),
+            Json::String(string) => parse_int(string),
+            json => return self.invalid_type("integer", json),
+        };
+        value.map_or_else(|| self.invalid_value(expected, json), Ok)
+    }
+
+    fn big_uint<T: From<u64>, E>(
+        &self,
+        expected: &'static str,
+        json: &Json,
+        from_dec: impl FnOnce(&str) -> Result<T, E>,
+        from_hex: impl FnOnce(&str) -> Option<T>,
+    ) -> JsonResult<T> {
+        let value = match json {
+            Json::Number(number) => number.as_u64().map(T::from),
+            Json::String(string) => match string.strip_prefix("0x") {
+                Some(hex) => from_hex(hex),
+                None => from_dec(string).ok(),
+            },
+            json => return self.invalid_type("integer", json),
+        };
+        value.map_or_else(|| self.invalid_value(expected, json), Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemberDef, OptionDef, ResultDef, TupleDef, VariantDef};
+    use serde_json::json;
+
+    fn player() -> TypeDef {
+        let class = EnumDef::new_type_def(
+            "Class".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::from(1),
+                    VariantDef::new("Warrior".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::from(2),
+                    VariantDef::new("Mage".to_string(), vec![], TypeDef::U8),
+                ),
+            ],
+        );
+        StructDef::new_type_def(
+            "Player".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("owner".to_string(), vec![], TypeDef::ContractAddress),
+                MemberDef::new("gold".to_string(), vec![], TypeDef::U256),
+                MemberDef::new("name".to_string(), vec![], TypeDef::Utf8String),
+                MemberDef::new("class".to_string(), vec![], class),
+                MemberDef::new(
+                    "pet".to_string(),
+                    vec![],
+                    OptionDef::new_type_def(TupleDef::new_type_def(vec![
+                        TypeDef::I8,
+                        TypeDef::ByteArray,
+                    ])),
+                ),
+                MemberDef::new(
+                    "last".to_string(),
+                    vec![],
+                    ResultDef::new_type_def(TypeDef::U128, TypeDef::ShortUtf8),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn round_trips_natural_json() {
+        let json = json!({
+            "owner": "0x7b",
+            "gold": "340282366920938463463374607431768211456",
+            "name": "bob",
+            "class": {"Mage": 3},
+            "pet": [-2, "0x6869"],
+            "last": {"Ok": "12"},
+        });
+        let value = from_json(&player(), json).unwrap();
+        let Value::Struct(player_value) = &value else {
+            panic!("expected struct");
+        };
+        assert_eq!(
+            player_value.members[0].value,
+            Value::ContractAddress(Felt::from(123))
+        );
+        assert_eq!(
+            player_value.members[1].value,
+            Value::U256(U256::from(u128::MAX) + 1)
+        );
+        let back = to_json(&value);
+        assert_eq!(back["owner"], json!(felt_to_hex_string(&Felt::from(123))));
+        assert_eq!(back["pet"], json!([-2, "0x6869"]));
+        assert_eq!(from_json(&player(), back).unwrap(), value);
+
+        let warrior = json!({
+            "owner": 1, "gold": 0, "name": "", "class": "Warrior", "pet": null,
+            "last": {"Err": "no"},
+        });
+        let value = from_json(&player(), warrior).unwrap();
+        assert_eq!(to_json(&value)["class"], json!({"Warrior": null}));
+        assert_eq!(to_json(&value)["pet"], Json::Null);
+    }
+
+    #[test]
+    fn reports_precise_errors() {
+        let error = |json| from_json(&player(), json).unwrap_err().to_string();
+        let base = json!({
+            "owner": 1, "gold": 0, "name": "", "class": "Warrior", "pet": null,
+            "last": {"Ok": 1},
+        });
+        let with = |key: &str, value: Json| {
+            let mut json = base.clone();
+            json[key] = value;
+            json
+        };
+        assert_eq!(
+            error(with("class", json!({"Rogue": null}))),
+            "unknown variant: Rogue at Player.class"
+        );
+        assert_eq!(
+            error(with("pet", json!([300, "0x"]))),
+            "invalid i8: 300 at Player.pet[0]"
+        );
+        assert_eq!(
+            error(with("gold", json!(true))),
+            "expected integer, found bool at Player.gold"
+        );
+        let mut missing = base.clone();
+        missing.as_object_mut().unwrap().remove("name");
+        assert_eq!(error(missing), "missing member: name at Player");
+        assert_eq!(
+            error(with("extra", json!(1))),
+            "unknown member: extra at Player"
+        );
+    }
+}
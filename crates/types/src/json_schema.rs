@@ -0,0 +1,332 @@
+use crate::{EnumDef, GetRefTypeDef, StructDef, TableSchema, TypeDef, felt_to_hex_string};
+use serde_json::{Map, Value as Json, json};
+use starknet_types_core::felt::Felt;
+use thiserror::Error;
+
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+const FELT_PATTERN: &str = "^0x[0-9a-fA-F]{1,64}$";
+const ETH_ADDRESS_PATTERN: &str = "^0x[0-9a-fA-F]{1,40}$";
+const BYTES31_PATTERN: &str = "^0x([0-9a-fA-F]{2}){0,31}$";
+const BYTES_PATTERN: &str = "^0x([0-9a-fA-F]{2})*$";
+const UINT_PATTERN: &str = "^[0-9]+$";
+const INT_PATTERN: &str = "^-?[0-9]+$";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum JsonSchemaError {
+    #[error("unknown type reference: {0:#x}")]
+    UnknownRef(Felt),
+}
+
+pub type JsonSchemaResult<T> = Result<T, JsonSchemaError>;
+
+/// Builds draft 2020-12 JSON Schemas matching the JSON produced by `to_json`
+/// and accepted by `from_json`.
+///
+/// `Ref` types are resolved through the given `GetRefTypeDef` and emitted once
+/// under `$defs`, keyed by their hex id.
+#[derive(Default)]
+pub struct JsonSchemaGenerator<'a> {
+    refs: Option<&'a dyn GetRefTypeDef>,
+    defs: Map<String, Json>,
+}
+
+fn pattern(pattern: &str) -> Json {
+    json!({"type": "string", "pattern": pattern})
+}
+
+fn integer(minimum: impl Into<Json>, maximum: impl Into<Json>) -> Json {
+    json!({"type": "integer", "minimum": minimum.into(), "maximum": maximum.into()})
+}
+
+fn object(properties: Map<String, Json>, title: Option<&str>) -> Json {
+    let required: Vec<&String> = properties.keys().collect();
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false,
+    });
+    if let Some(title) = title {
+        schema["title"] = title.into();
+    }
+    schema
+}
+
+fn variant(name: &str, schema: Json) -> Json {
+    object(Map::from_iter([(name.to_string(), schema)]), None)
+}
+
+impl<'a> JsonSchemaGenerator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_refs(refs: &'a dyn GetRefTypeDef) -> Self {
+        JsonSchemaGenerator {
+            refs: Some(refs),
+            defs: Map::new(),
+        }
+    }
+
+    /// Root schema of a type.
+    pub fn type_def_schema(mut self, type_def: &TypeDef) -> JsonSchemaResult<Json> {
+        let schema = self.schema(type_def)?;
+        Ok(self.root(schema))
+    }
+
+    /// Root schema of a full record of the table: an object keyed by the primary
+    /// and column names.
+    pub fn record_schema(mut self, table: &TableSchema) -> JsonSchemaResult<Json> {
+        let mut properties = Map::new();
+        properties.insert(
+            table.primary.name.clone(),
+            self.schema(&(&table.primary.type_def).into())?,
+        );
+        for column in &table.columns {
+            properties.insert(column.name.clone(), self.schema(&column.type_def)?);
+        }
+        let schema = object(properties, Some(&table.name));
+        Ok(self.root(schema))
+    }
+
+    fn root(self, mut schema: Json) -> Json {
+        schema["$schema"] = JSON_SCHEMA_DIALECT.into();
+        if !self.defs.is_empty() {
+            schema["$defs"] = Json::Object(self.defs);
+        }
+        schema
+    }
+
+    /// Schema of a type, without the dialect and `$defs`.
+    pub fn schema(&mut self, type_def: &TypeDef) -> JsonSchemaResult<Json> {
+        Ok(match type_def {
+            TypeDef::None => json!({"type": "null"}),
+            TypeDef::Felt252
+            | TypeDef::ClassHash
+            | TypeDef::ContractAddress
+            | TypeDef::StorageAddress
+            | TypeDef::StorageBaseAddress => pattern(FELT_PATTERN),
+            TypeDef::EthAddress => pattern(ETH_ADDRESS_PATTERN),
+            TypeDef::ShortUtf8 => json!({"type": "string", "maxLength": 31}),
+            TypeDef::Utf8String => json!({"type": "string"}),
+            TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => pattern(BYTES31_PATTERN),
+            TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) => pattern(BYTES_PATTERN),
+            TypeDef::Bool => json!({"type": "boolean"}),
+            TypeDef::U8 => integer(u8::MIN, u8::MAX),
+            TypeDef::U16 => integer(u16::MIN, u16::MAX),
+            TypeDef::U32 => integer(u32::MIN, u32::MAX),
+            TypeDef::U64 => integer(u64::MIN, u64::MAX),
+            TypeDef::I8 => integer(i8::MIN, i8::MAX),
+            TypeDef::I16 => integer(i16::MIN, i16::MAX),
+            TypeDef::I32 => integer(i32::MIN, i32::MAX),
+            TypeDef::I64 => integer(i64::MIN, i64::MAX),
+            TypeDef::U128 | TypeDef::U256 | TypeDef::U512 => pattern(UINT_PATTERN),
+            TypeDef::I128 => pattern(INT_PATTERN),
+            TypeDef::Tuple(tuple) => {
+                let items = tuple
+                    .elements
+                    .iter()
+                    .map(|element| self.schema(element))
+                    .collect::<JsonSchemaResult<Vec<_>>>()?;
+                let len = items.len();
+                json!({
+                    "type": "array",
+                    "prefixItems": items,
+                    "items": false,
+                    "minItems": len,
+                    "maxItems": len,
+                })
+            }
+            TypeDef::Array(array) => {
+                json!({"type": "array", "items": self.schema(&array.type_def)?})
+            }
+            TypeDef::FixedArray(fixed) => json!({
+                "type": "array",
+                "items": self.schema(&fixed.type_def)?,
+                "minItems": fixed.size,
+                "maxItems": fixed.size,
+            }),
+            TypeDef::Felt252Dict(dict) => json!({
+                "type": "object",
+                "propertyNames": {"pattern": FELT_PATTERN},
+                "additionalProperties": self.schema(&dict.type_def)?,
+            }),
+            TypeDef::Struct(struct_def) => self.struct_schema(struct_def)?,
+            TypeDef::Enum(enum_def) => self.enum_schema(enum_def)?,
+            TypeDef::Option(option) => {
+                json!({"anyOf": [self.schema(&option.type_def)?, {"type": "null"}]})
+            }
+            TypeDef::Nullable(nullable) => {
+                json!({"anyOf": [self.schema(&nullable.type_def)?, {"type": "null"}]})
+            }
+            TypeDef::Result(result) => json!({
+                "oneOf": [
+                    variant("Ok", self.schema(&result.ok)?),
+                    variant("Err", self.schema(&result.err)?),
+                ]
+            }),
+            TypeDef::Custom(custom) => json!({
+                "type": "array",
+                "items": pattern(FELT_PATTERN),
+                "description": custom.encoding,
+            }),
+            TypeDef::Ref(reference) => self.ref_schema(reference.id)?,
+        })
+    }
+
+    fn struct_schema(&mut self, struct_def: &StructDef) -> JsonSchemaResult<Json> {
+        let mut properties = Map::new();
+        for member in &struct_def.members {
+            properties.insert(member.name.clone(), self.schema(&member.type_def)?);
+        }
+        Ok(object(properties, Some(&struct_def.name)))
+    }
+
+    /// Variants are `{name: payload}` objects; variants without a payload may
+    /// also be given as a bare name.
+    fn enum_schema(&mut self, enum_def: &EnumDef) -> JsonSchemaResult<Json> {
+        let mut variants = Vec::new();
+        for selector in &enum_def.order {
+            let Some(variant_def) = enum_def.variants.get(selector) else {
+                continue;
+            };
+            if variant_def.type_def == TypeDef::None {
+                variants.push(json!({"const": variant_def.name}));
+            }
+            variants.push(variant(
+                &variant_def.name,
+                self.schema(&variant_def.type_def)?,
+            ));
+        }
+        Ok(json!({"title": enum_def.name, "oneOf": variants}))
+    }
+
+    fn ref_schema(&mut self, id: Felt) -> JsonSchemaResult<Json> {
+        let key = felt_to_hex_string(&id);
+        if !self.defs.contains_key(&key) {
+            let type_def = self
+                .refs
+                .and_then(|refs| refs.get_type_def(id))
+                .ok_or(JsonSchemaError::UnknownRef(id))?;
+            // Placeholder so recursive types reference themselves instead of
+            // expanding forever.
+            self.defs.insert(key.clone(), Json::Bool(true));
+            let schema = self.schema(&type_def)?;
+            self.defs.insert(key.clone(), schema);
+        }
+        Ok(json!({"$ref": format!("#/$defs/{key}")}))
+    }
+}
+
+impl TypeDef {
+    /// JSON Schema of the values of the type, see `JsonSchemaGenerator`.
+    pub fn json_schema(&self) -> JsonSchemaResult<Json> {
+        JsonSchemaGenerator::new().type_def_schema(self)
+    }
+}
+
+impl TableSchema {
+    /// JSON Schema of a full record of the table, see `JsonSchemaGenerator`.
+    pub fn record_json_schema(&self) -> JsonSchemaResult<Json> {
+        JsonSchemaGenerator::new().record_schema(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArrayDef, ColumnDef, MemberDef, OptionDef, PrimaryDef, PrimaryTypeDef, RefDef, VariantDef,
+    };
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn describes_the_json_shape() {
+        let direction = EnumDef::new_type_def(
+            "Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::ONE,
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::TWO,
+                    VariantDef::new("Jump".to_string(), vec![], TypeDef::U8),
+                ),
+            ],
+        );
+        let table = TableSchema {
+            id: Felt::ONE,
+            name: "Player".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("id".to_string(), vec![], PrimaryTypeDef::ContractAddress),
+            columns: vec![
+                ColumnDef::new(Felt::ONE, "level".to_string(), vec![], TypeDef::I8),
+                ColumnDef::new(Felt::TWO, "gold".to_string(), vec![], TypeDef::U256),
+                ColumnDef::new(
+                    Felt::THREE,
+                    "moves".to_string(),
+                    vec![],
+                    OptionDef::new_type_def(ArrayDef::new_type_def(direction)),
+                ),
+            ],
+        };
+        let schema = table.record_json_schema().unwrap();
+        assert_eq!(schema["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(schema["title"], "Player");
+        let required: HashSet<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(Json::as_str)
+            .collect();
+        assert_eq!(required, HashSet::from(["gold", "id", "level", "moves"]));
+        assert_eq!(schema["properties"]["id"]["pattern"], FELT_PATTERN);
+        assert_eq!(schema["properties"]["level"], integer(-128, 127));
+        assert_eq!(schema["properties"]["gold"]["pattern"], UINT_PATTERN);
+        let moves = &schema["properties"]["moves"]["anyOf"];
+        assert_eq!(moves[1], json!({"type": "null"}));
+        assert_eq!(
+            moves[0]["items"]["oneOf"],
+            json!([
+                {"const": "Up"},
+                variant("Up", json!({"type": "null"})),
+                variant("Jump", integer(0, 255)),
+            ])
+        );
+    }
+
+    #[test]
+    fn emits_refs_as_defs() {
+        let node = StructDef::new_type_def(
+            "Node".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("value".to_string(), vec![], TypeDef::U32),
+                MemberDef::new(
+                    "next".to_string(),
+                    vec![],
+                    OptionDef::new_type_def(RefDef::new_type_def(Felt::from(7))),
+                ),
+            ],
+        );
+        let refs = HashMap::from([(Felt::from(7), node)]);
+        let root = ArrayDef::new_type_def(RefDef::new_type_def(Felt::from(7)));
+        let schema = JsonSchemaGenerator::with_refs(&refs)
+            .type_def_schema(&root)
+            .unwrap();
+        let key = felt_to_hex_string(&Felt::from(7));
+        let reference = format!("#/$defs/{key}");
+        assert_eq!(schema["items"]["$ref"], reference);
+        assert_eq!(
+            schema["$defs"][&key]["properties"]["next"]["anyOf"][0]["$ref"],
+            reference
+        );
+        assert_eq!(
+            root.json_schema(),
+            Err(JsonSchemaError::UnknownRef(Felt::from(7)))
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{TableSchema, TypeDef};
+use starknet_types_core::felt::Felt;
+
+/// Where a value sits in the felts of an enclosing value, found by
+/// `TypeDef::felt_span` without decoding anything before it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeltSpan<'a> {
+    pub offset: usize,
+    /// `None` when the value itself is variable length, it then runs to the end
+    /// of the data as far as the span is concerned.
+    pub len: Option<usize>,
+    pub type_def: &'a TypeDef,
+}
+
+impl<'a> FeltSpan<'a> {
+    /// The felts of the value, `None` when `data` is too short.
+    pub fn slice<'b>(&self, data: &'b [Felt]) -> Option<&'b [Felt]> {
+        match self.len {
+            Some(len) => data.get(self.offset..self.offset.checked_add(len)?),
+            None => data.get(self.offset..),
+        }
+    }
+}
+
+fn static_sum<'a>(type_defs: impl IntoIterator<Item = &'a TypeDef>) -> Option<usize> {
+    type_defs.into_iter().try_fold(0usize, |len, type_def| {
+        len.checked_add(type_def.static_felt_len()?)
+    })
+}
+
+/// Length of a tagged union: the tag plus the payload, when every alternative has
+/// the same length.
+fn static_union<'a>(alternatives: impl IntoIterator<Item = &'a TypeDef>) -> Option<usize> {
+    let mut lens = alternatives.into_iter().map(TypeDef::static_felt_len);
+    let first = lens.next().unwrap_or(Some(0))?;
+    match lens.all(|len| len == Some(first)) {
+        true => first.checked_add(1),
+        false => None,
+    }
+}
+
+impl TypeDef {
+    /// Number of felts every value of the type is encoded in, or `None` when it
+    /// depends on the value (byte arrays, arrays, enums with payloads of
+    /// different lengths...).
+    pub fn static_felt_len(&self) -> Option<usize> {
+        match self {
+            TypeDef::None => Some(0),
+            TypeDef::Felt252
+            | TypeDef::ShortUtf8
+            | TypeDef::Bytes31
+            | TypeDef::Bytes31Encoded(_)
+            | TypeDef::Bool
+            | TypeDef::U8
+            | TypeDef::U16
+            | TypeDef::U32
+            | TypeDef::U64
+            | TypeDef::U128
+            | TypeDef::I8
+            | TypeDef::I16
+            | TypeDef::I32
+            | TypeDef::I64
+            | TypeDef::I128
+            | TypeDef::ClassHash
+            | TypeDef::ContractAddress
+            | TypeDef::EthAddress
+            | TypeDef::StorageAddress
+            | TypeDef::StorageBaseAddress => Some(1),
+            TypeDef::U256 => Some(2),
+            TypeDef::U512 => Some(4),
+            TypeDef::ByteArray
+            | TypeDef::Utf8String
+            | TypeDef::ByteArrayEncoded(_)
+            | TypeDef::Custom(_)
+            | TypeDef::Array(_)
+            | TypeDef::Felt252Dict(_)
+            | TypeDef::Ref(_) => None,
+            TypeDef::Tuple(tuple) => static_sum(&tuple.elements),
+            TypeDef::FixedArray(fa) => fa.type_def.static_felt_len()?.checked_mul(fa.size as usize),
+            TypeDef::Struct(s) => static_sum(s.members.iter().map(|m| &m.type_def)),
+            TypeDef::Enum(e) => static_union(e.variants.values().map(|v| &v.type_def)),
+            TypeDef::Option(option) => static_union([&TypeDef::None, &option.type_def]),
+            TypeDef::Result(result) => static_union([&result.ok, &result.err]),
+            TypeDef::Nullable(nullable) => static_union([&TypeDef::None, &nullable.type_def]),
+        }
+    }
+
+    /// Locates the value at `path` (struct member names, or indexes into tuples
+    /// and fixed size arrays, e.g. `["stats", "2", "attack"]`) so it can be read
+    /// straight from the felts of this type.
+    ///
+    /// Returns `None` when the path does not exist or anything encoded before the
+    /// value has a variable length.
+    pub fn felt_span(&self, path: &[&str]) -> Option<FeltSpan<'_>> {
+        let mut offset = 0usize;
+        let mut type_def = self;
+        for segment in path {
+            let (before, child) = match type_def {
+                TypeDef::Struct(s) => {
+                    let index = s.members.iter().position(|m| m.name == *segment)?;
+                    let before = static_sum(s.members[..index].iter().map(|m| &m.type_def))?;
+                    (before, &s.members[index].type_def)
+                }
+                TypeDef::Tuple(tuple) => {
+                    let index: usize = segment.parse().ok()?;
+                    let child = tuple.elements.get(index)?;
+                    (static_sum(&tuple.elements[..index])?, child)
+                }
+                TypeDef::FixedArray(fa) => {
+                    let index: usize = segment.parse().ok()?;
+                    if index >= fa.size as usize {
+                        return None;
+                    }
+                    (
+                        fa.type_def.static_felt_len()?.checked_mul(index)?,
+                        &fa.type_def,
+                    )
+                }
+                _ => return None,
+            };
+            offset = offset.checked_add(before)?;
+            type_def = child;
+        }
+        Some(FeltSpan {
+            offset,
+            len: type_def.static_felt_len(),
+            type_def,
+        })
+    }
+}
+
+impl TableSchema {
+    /// Locates a column, or the value at `path` inside it, in the data of an
+    /// `InsertRecord`, see `TypeDef::felt_span`.
+    pub fn column_span(&self, id: &Felt, path: &[&str]) -> Option<FeltSpan<'_>> {
+        let index = self.columns.iter().position(|c| c.id == *id)?;
+        let before = static_sum(self.columns[..index].iter().map(|c| &c.type_def))?;
+        let span = self.columns[index].type_def.felt_span(path)?;
+        Some(FeltSpan {
+            offset: before.checked_add(span.offset)?,
+            ..span
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArrayDef, CairoSerde, ColumnDef, EnumDef, FixedArrayDef, IntoFeltSource, MemberDef,
+        OptionDef, ParseValue, PrimaryDef, PrimaryTypeDef, StructDef, TupleDef, Value, VariantDef,
+    };
+
+    fn stats() -> TypeDef {
+        let stat = StructDef::new_type_def(
+            "Stat".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("attack".to_string(), vec![], TypeDef::U256),
+                MemberDef::new("defense".to_string(), vec![], TypeDef::U8),
+            ],
+        );
+        FixedArrayDef::new_type_def(stat, 3)
+    }
+
+    #[test]
+    fn computes_static_lengths() {
+        let kind = EnumDef::new_type_def(
+            "Kind".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::ONE,
+                    VariantDef::new("A".to_string(), vec![], TypeDef::U8),
+                ),
+                (
+                    Felt::TWO,
+                    VariantDef::new("B".to_string(), vec![], TypeDef::Felt252),
+                ),
+            ],
+        );
+        assert_eq!(stats().static_felt_len(), Some(9));
+        assert_eq!(kind.static_felt_len(), Some(2));
+        assert_eq!(
+            TupleDef::new_type_def(vec![kind, TypeDef::U512]).static_felt_len(),
+            Some(6)
+        );
+        assert_eq!(OptionDef::new_type_def(TypeDef::U8).static_felt_len(), None);
+        assert_eq!(ArrayDef::new_type_def(TypeDef::U8).static_felt_len(), None);
+    }
+
+    #[test]
+    fn reads_members_without_decoding_predecessors() {
+        let table = TableSchema {
+            id: Felt::ONE,
+            name: "Player".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("id".to_string(), vec![], PrimaryTypeDef::Felt252),
+            columns: vec![
+                ColumnDef::new(Felt::from(1), "level".to_string(), vec![], TypeDef::U8),
+                ColumnDef::new(Felt::from(2), "stats".to_string(), vec![], stats()),
+                ColumnDef::new(
+                    Felt::from(3),
+                    "name".to_string(),
+                    vec![],
+                    TypeDef::ByteArray,
+                ),
+                ColumnDef::new(Felt::from(4), "score".to_string(), vec![], TypeDef::U32),
+            ],
+        };
+        let data: Vec<Felt> = (0..16u64).map(Felt::from).collect();
+
+        let span = table
+            .column_span(&Felt::from(2), &["2", "defense"])
+            .unwrap();
+        assert_eq!((span.offset, span.len), (9, Some(1)));
+        let mut de = CairoSerde(span.slice(&data).unwrap().into_source());
+        assert_eq!(span.type_def.parse(&mut de).unwrap(), Value::U8(9));
+
+        let name = table.column_span(&Felt::from(3), &[]).unwrap();
+        assert_eq!((name.offset, name.len), (10, None));
+        assert_eq!(name.slice(&data).unwrap().len(), 6);
+        assert!(table.column_span(&Felt::from(4), &[]).is_none());
+        assert!(table.column_span(&Felt::from(2), &["3"]).is_none());
+        assert!(table.column_span(&Felt::from(2), &["0", "speed"]).is_none());
+    }
+}
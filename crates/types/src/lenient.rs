@@ -0,0 +1,206 @@
+use crate::parser::{TypeParserError, TypeParserResult};
+use crate::{
+    CairoISerde, ColumnDef, DecodeError, FeltSource, Field, IntoFeltSource, ParseValue, Record,
+    TableSchema,
+};
+use starknet_types_core::felt::Felt;
+
+/// A column that could not be decoded, with the felts left for it in the payload.
+#[derive(Debug)]
+pub struct UndecodedField {
+    pub id: Felt,
+    pub name: String,
+    pub error: TypeParserError,
+    pub felts: Vec<Felt>,
+}
+
+#[derive(Debug, Default)]
+pub struct LenientFields {
+    pub fields: Vec<Field>,
+    pub undecoded: Vec<UndecodedField>,
+}
+
+#[derive(Debug)]
+pub struct LenientRecord {
+    pub record: Record,
+    pub undecoded: Vec<UndecodedField>,
+}
+
+impl LenientFields {
+    pub fn is_complete(&self) -> bool {
+        self.undecoded.is_empty()
+    }
+}
+
+impl UndecodedField {
+    fn new(id: Felt, name: &str, error: TypeParserError, felts: &[Felt]) -> Self {
+        UndecodedField {
+            id,
+            name: name.to_string(),
+            error,
+            felts: felts.to_vec(),
+        }
+    }
+}
+
+/// Decodes a column from its own ISerde payload, as in `InsertField` or the
+/// entries of `InsertsField`, returning the raw payload with the error when it
+/// does not decode.
+pub fn parse_field_lenient(
+    column: &ColumnDef,
+    data: &[Felt],
+) -> Result<Field, Box<UndecodedField>> {
+    let mut deserializer = CairoISerde(data.into_source());
+    let result = column
+        .parse(&mut deserializer)
+        .and_then(|field| match deserializer.next() {
+            Err(e) if e.is_eof() => Ok(field),
+            Ok(_) => Err(DecodeError::NotEof.into()),
+            Err(e) => Err(e.into()),
+        });
+    result.map_err(|error| Box::new(UndecodedField::new(column.id, &column.name, error, data)))
+}
+
+/// Decodes consecutive columns from a single ISerde payload, as in `InsertFields`
+/// or `InsertRecord`, keeping the columns decoded before a failure.
+///
+/// Column values are not length prefixed, so the failing column gets the rest of
+/// the payload as its felts and the columns after it are reported without felts.
+pub fn parse_fields_lenient<'a>(
+    columns: impl IntoIterator<Item = Result<&'a ColumnDef, (Felt, TypeParserError)>>,
+    data: &[Felt],
+) -> LenientFields {
+    let mut deserializer = CairoISerde(data.into_source());
+    let mut lenient = LenientFields::default();
+    let mut columns = columns.into_iter();
+    for column in columns.by_ref() {
+        let start = deserializer.position();
+        let undecoded = match column {
+            Ok(column) => match column.parse(&mut deserializer) {
+                Ok(field) => {
+                    lenient.fields.push(field);
+                    continue;
+                }
+                Err(error) => UndecodedField::new(column.id, &column.name, error, &data[start..]),
+            },
+            Err((id, error)) => UndecodedField::new(id, "", error, &data[start..]),
+        };
+        lenient.undecoded.push(undecoded);
+        break;
+    }
+    for column in columns {
+        let (id, name) = match column {
+            Ok(column) => (column.id, column.name.as_str()),
+            Err((id, _)) => (id, ""),
+        };
+        let error = DecodeError::message("not decoded after a failed column").into();
+        lenient
+            .undecoded
+            .push(UndecodedField::new(id, name, error, &[]));
+    }
+    lenient
+}
+
+impl TableSchema {
+    /// Leniently decodes the given columns of a row, as emitted by `InsertFields`.
+    ///
+    /// Only the primary key conversion is fatal, unknown columns are reported as
+    /// undecoded.
+    pub fn parse_fields_lenient(
+        &self,
+        row: Felt,
+        columns: &[Felt],
+        data: &[Felt],
+    ) -> TypeParserResult<LenientRecord> {
+        let columns = columns.iter().map(|id| {
+            self.get_column(id)
+                .ok_or_else(|| (*id, TypeParserError::unknown_column(*id)))
+        });
+        self.lenient_record(row, parse_fields_lenient(columns, data))
+    }
+
+    /// Leniently decodes all the columns of a row, as emitted by `InsertRecord`.
+    pub fn parse_record_lenient(
+        &self,
+        row: Felt,
+        data: &[Felt],
+    ) -> TypeParserResult<LenientRecord> {
+        let fields = parse_fields_lenient(self.columns.iter().map(Ok), data);
+        self.lenient_record(row, fields)
+    }
+
+    fn lenient_record(&self, row: Felt, fields: LenientFields) -> TypeParserResult<LenientRecord> {
+        Ok(LenientRecord {
+            record: Record {
+                table_id: self.id,
+                table_name: self.name.clone(),
+                attributes: self.attributes.clone(),
+                primary: self.primary.to_primary(row)?,
+                fields: fields.fields,
+            },
+            undecoded: fields.undecoded,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EnumDef, PrimaryDef, PrimaryTypeDef, TypeDef, Value, VariantDef};
+
+    fn table() -> TableSchema {
+        let kind = EnumDef::new_type_def(
+            "Kind".to_string(),
+            vec![],
+            vec![(
+                Felt::from(0x10),
+                VariantDef::new("Fire".to_string(), vec![], TypeDef::None),
+            )],
+        );
+        TableSchema {
+            id: Felt::ONE,
+            name: "Player".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("id".to_string(), vec![], PrimaryTypeDef::Felt252),
+            columns: vec![
+                ColumnDef::new(Felt::from(1), "level".to_string(), vec![], TypeDef::U8),
+                ColumnDef::new(Felt::from(2), "kind".to_string(), vec![], kind),
+                ColumnDef::new(Felt::from(3), "score".to_string(), vec![], TypeDef::U32),
+            ],
+        }
+    }
+
+    #[test]
+    fn keeps_fields_before_failure() {
+        let data = [Felt::from(5), Felt::from(0x20), Felt::from(9)];
+        let lenient = table().parse_record_lenient(Felt::TWO, &data).unwrap();
+        assert_eq!(lenient.record.fields.len(), 1);
+        assert_eq!(lenient.record.fields[0].value, Value::U8(5));
+        assert!(matches!(
+            lenient.undecoded[0].error,
+            TypeParserError::InvalidEnumSelector { .. }
+        ));
+        assert_eq!(lenient.undecoded[0].felts, data[1..]);
+        assert_eq!(lenient.undecoded[1].name, "score");
+        assert!(lenient.undecoded[1].felts.is_empty());
+    }
+
+    #[test]
+    fn isolates_single_column_payloads() {
+        let table = table();
+        let entries = [vec![Felt::from(0x10)], vec![Felt::from(0x20)]];
+        let column = table.get_column(&Felt::from(2)).unwrap();
+        let results: Vec<_> = entries
+            .iter()
+            .map(|data| parse_field_lenient(column, data))
+            .collect();
+        assert!(results[0].is_ok());
+        assert_eq!(results[1].as_ref().unwrap_err().felts, entries[1]);
+
+        let lenient = table
+            .parse_fields_lenient(Felt::TWO, &[Felt::from(3), Felt::from(7)], &[Felt::ONE])
+            .unwrap();
+        assert_eq!(lenient.record.fields[0].value, Value::U32(1));
+        assert_eq!(lenient.undecoded[0].id, Felt::from(7));
+    }
+}
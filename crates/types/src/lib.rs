@@ -1,41 +1,92 @@
+extern crate self as introspect_types;
+
+#[cfg(feature = "abi")]
+pub mod abi;
+#[cfg(feature = "abi")]
+pub mod abi_event;
 pub mod attribute;
+pub mod avro;
 pub mod bytes;
+pub mod cairo_codegen;
+pub mod cost;
 pub mod decode_error;
 pub mod deserialize;
 pub mod deserialize_def;
 pub mod event;
 pub mod felt;
+pub mod from_felts;
+pub mod graphql;
+pub mod indexed;
 pub mod iserde;
+pub mod json;
+pub mod json_schema;
+pub mod layout;
+pub mod lenient;
+pub mod limits;
 pub mod parser;
+pub mod path;
+pub mod plan;
+pub mod projection;
+pub mod protobuf;
 pub mod reference;
+pub mod rust_codegen;
 pub mod schema;
 pub mod serde;
 pub mod serialize;
 pub mod serialize_def;
+pub mod skip;
+pub mod to_type_def;
 pub mod transcode;
 pub mod transcode_def;
 pub mod type_def;
 pub mod types;
+pub mod typescript;
 pub mod utils;
 pub mod value;
-pub use attribute::{Attribute, Attributes};
+pub use attribute::{Attribute, Attributes, KEY_ATTRIBUTE};
+pub use avro::{AvroError, AvroGenerator, avro_can_read};
+pub use cairo_codegen::{CairoCodegenError, CairoGenerator, CairoModule};
+pub use cost::{ColumnCost, EVENT_DATA_FELT_LIMIT, Encoding, FeltCost, TableCost};
 pub use decode_error::{ByteArrayError, DecodeError, DecodeResult};
-pub use deserialize::{CairoDeserialize, CairoDeserializer};
+pub use deserialize::{
+    CairoDeserialize, CairoDeserializeRemaining, CairoDeserializer, EnumVariantEncoding,
+};
 pub use event::{CairoEvent, CairoEventInfo};
 pub use felt::{FeltIterator, FeltSource, IntoFeltSource, SliceFeltSource, VecFeltSource};
+pub use from_felts::{
+    FeltDeserializer, FromFeltsError, from_deserializer, from_felts, from_ifelts,
+};
+pub use graphql::{GraphQLError, GraphQLGenerator, GraphQLSchema};
+pub use indexed::IndexedEnums;
+pub use introspect_rust_macros::{CairoDeserialize, CairoEvent, Introspect};
 pub use iserde::CairoISerde;
+pub use json::{JsonError, JsonErrorKind, from_json, to_json};
+pub use json_schema::{JsonSchemaError, JsonSchemaGenerator};
+pub use layout::FeltSpan;
+pub use lenient::{LenientFields, LenientRecord, UndecodedField};
+pub use limits::{DecodeBudget, DecodeLimits, Limited};
 pub use parser::ParseValue;
+pub use path::{DecodePath, Locate, Location, PathSegment, Traced};
+pub use plan::{DecodePlan, PlanNode};
+pub use projection::{Projection, Selection};
+pub use protobuf::{ProtoError, ProtoGenerator, ProtoMessage, ProtoSchema};
 pub use reference::{DerefDefTrait, GetRefTypeDef};
+pub use rust_codegen::{RustCodegenError, RustGenerator, RustModule};
 pub use schema::{ColumnDef, ColumnInfo, FeltId, FeltIds, PrimaryDef, PrimaryTypeDef, TableSchema};
 pub use serde::CairoSerde;
+pub use skip::SkipValue;
+pub use to_type_def::{ToTypeDef, TypeDefMismatch, type_def_mismatches};
 pub use type_def::{
     ArrayDef, ByteArrayEncodedDef, Bytes31EncodedDef, CustomDef, ElementDef, EnumDef,
     Felt252DictDef, FixedArrayDef, ItemDefTrait, MemberDef, NullableDef, OptionDef, RefDef,
     ResultDef, StructDef, TupleDef, TypeDef, VariantDef,
 };
 pub use types::{ByteArray, Bytes31, EthAddress, felt_to_bytes31_bytes, felt_to_utf8_string};
+pub use typescript::{TsCodegenError, TsGenerator, TsModule};
 pub use utils::{
-    ResultInto, ascii_str_to_felt, ascii_str_to_limbs, bytes31_to_hex_string, felt_to_hex_string,
+    ResultInto, ascii_str_to_be_digits, ascii_str_to_felt, ascii_str_to_limbs,
+    bytes31_to_hex_string, cairo_ident, camel_case, felt_to_hex_string, identifier, pascal_case,
+    proto_name, type_name,
 };
 pub use value::{
     CairoOption, CairoResult, Custom, Encoded31Bytes, EncodedBytes, Enum, Field, IdValue, Member,
@@ -0,0 +1,348 @@
+use crate::bytes::ByteSource;
+use crate::path::DecodePath;
+use crate::{DecodeError, DecodeResult, FeltSource};
+use starknet_types_core::felt::Felt;
+
+/// Upper bound on the number of items reserved up front from a length prefix,
+/// larger collections grow as items are actually decoded.
+pub const MAX_PREALLOCATION: usize = 4096;
+
+#[inline]
+pub fn capped_capacity(count: usize) -> usize {
+    count.min(MAX_PREALLOCATION)
+}
+
+/// Bounds applied while decoding untrusted input.
+///
+/// Limits are opt-in: `ParseValue`, `SkipValue`, `Transcode`, serde
+/// serialization and `TypeDefDeserializer` only enforce them when the source
+/// under `CairoSerde` or `CairoISerde` is wrapped in `Limited`, e.g.
+/// `CairoSerde(Limited::new(source, DecodeLimits::default()))`. Without it array
+/// lengths, input size and nesting depth are unbounded, so deeply nested type
+/// definitions recurse as far as the input goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeLimits {
+    pub max_array_len: usize,
+    pub max_total_felts: usize,
+    pub max_depth: usize,
+    pub max_byte_array_bytes: usize,
+}
+
+impl DecodeLimits {
+    pub const UNLIMITED: DecodeLimits = DecodeLimits {
+        max_array_len: usize::MAX,
+        max_total_felts: usize::MAX,
+        max_depth: usize::MAX,
+        max_byte_array_bytes: usize::MAX,
+    };
+
+    pub fn new(
+        max_array_len: usize,
+        max_total_felts: usize,
+        max_depth: usize,
+        max_byte_array_bytes: usize,
+    ) -> Self {
+        DecodeLimits {
+            max_array_len,
+            max_total_felts,
+            max_depth,
+            max_byte_array_bytes,
+        }
+    }
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        DecodeLimits {
+            max_array_len: 1 << 16,
+            max_total_felts: 1 << 20,
+            max_depth: 64,
+            max_byte_array_bytes: 1 << 20,
+        }
+    }
+}
+
+/// Tracks the usage of a source against its `DecodeLimits`.
+#[derive(Clone, Debug)]
+pub struct DecodeBudget {
+    pub limits: DecodeLimits,
+    depth: usize,
+    consumed: usize,
+}
+
+impl DecodeBudget {
+    pub fn new(limits: DecodeLimits) -> Self {
+        DecodeBudget {
+            limits,
+            depth: 0,
+            consumed: 0,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    #[inline]
+    pub fn consume(&mut self, units: usize, max: usize) -> DecodeResult<()> {
+        let consumed = self.consumed.saturating_add(units);
+        match consumed > max {
+            true => Err(DecodeError::invalid_len("total felts", consumed, Some(max))),
+            false => {
+                self.consumed = consumed;
+                Ok(())
+            }
+        }
+    }
+
+    #[inline]
+    pub fn check_array_len(&self, what: &'static str, len: usize) -> DecodeResult<()> {
+        check_max(what, len, self.limits.max_array_len)
+    }
+
+    #[inline]
+    pub fn check_byte_array_len(&self, len: usize) -> DecodeResult<()> {
+        check_max("byte array", len, self.limits.max_byte_array_bytes)
+    }
+
+    #[inline]
+    pub fn enter(&mut self) -> DecodeResult<()> {
+        check_max("nesting depth", self.depth + 1, self.limits.max_depth)?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn exit(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+}
+
+#[inline]
+fn check_max(what: &'static str, len: usize, max: usize) -> DecodeResult<()> {
+    match len > max {
+        true => Err(DecodeError::invalid_len(what, len, Some(max))),
+        false => Ok(()),
+    }
+}
+
+/// Wraps a `FeltSource` or `ByteSource` with a `DecodeBudget`, so deserializers
+/// built on top of it enforce the configured `DecodeLimits`.
+///
+/// For byte sources the felt limit is applied per 32 bytes read.
+pub struct Limited<S> {
+    source: S,
+    budget: DecodeBudget,
+}
+
+impl<S> Limited<S> {
+    pub fn new(source: S, limits: DecodeLimits) -> Self {
+        Limited {
+            source,
+            budget: DecodeBudget::new(limits),
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<F: FeltSource> FeltSource for Limited<F> {
+    #[inline]
+    fn next(&mut self) -> DecodeResult<Felt> {
+        let max = self.budget.limits.max_total_felts;
+        self.budget.consume(1, max)?;
+        self.source.next()
+    }
+
+    #[inline]
+    fn position(&self) -> usize {
+        self.source.position()
+    }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        Some(&mut self.budget)
+    }
+
+    #[inline]
+    fn path(&mut self) -> Option<&mut DecodePath> {
+        self.source.path()
+    }
+}
+
+impl<B: ByteSource> ByteSource for Limited<B> {
+    #[inline]
+    fn next(&mut self) -> DecodeResult<u8> {
+        let max = self.budget.limits.max_total_felts.saturating_mul(32);
+        self.budget.consume(1, max)?;
+        self.source.next()
+    }
+
+    #[inline]
+    fn nexts<const N: usize>(&mut self) -> DecodeResult<[u8; N]> {
+        let max = self.budget.limits.max_total_felts.saturating_mul(32);
+        self.budget.consume(N, max)?;
+        self.source.nexts::<N>()
+    }
+
+    #[inline]
+    fn position(&self) -> usize {
+        self.source.position()
+    }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        Some(&mut self.budget)
+    }
+
+    #[inline]
+    fn path(&mut self) -> Option<&mut DecodePath> {
+        self.source.path()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deserialize_def::TypeDefDeserializer;
+    use crate::parser::TypeParserError;
+    use crate::serialize::ToCairoDeSeFrom;
+    use crate::serialize_def::CairoTypeSerialization;
+    use crate::transcode::{Transcode, TranscodeError};
+    use crate::{
+        ArrayDef, CairoDeserializer, CairoISerde, CairoSerde, FixedArrayDef, IntoFeltSource,
+        ParseValue, TypeDef,
+    };
+
+    struct Json;
+    impl CairoTypeSerialization for Json {}
+
+    fn limits() -> DecodeLimits {
+        DecodeLimits::new(4, 16, 3, 40)
+    }
+
+    #[test]
+    fn rejects_hostile_array_length() {
+        let felts = vec![Felt::from(u32::MAX)];
+        let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits()));
+        let type_def = ArrayDef::new_type_def(TypeDef::U8);
+        assert!(matches!(
+            type_def.parse(&mut de),
+            Err(TypeParserError::InvalidLength { len, max: Some(4), .. }) if len == u32::MAX as usize
+        ));
+    }
+
+    #[test]
+    fn rejects_total_felts() {
+        let felts = vec![Felt::ONE; 20];
+        let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits()));
+        assert!(de.drain().is_err());
+    }
+
+    #[test]
+    fn rejects_deep_type_defs() {
+        let array = crate::utils::ascii_str_to_felt("array");
+        let felts = vec![
+            array,
+            array,
+            array,
+            array,
+            crate::utils::ascii_str_to_felt("u8"),
+        ];
+        let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits()));
+        assert!(matches!(
+            de.deserialize_type_def(),
+            Err(DecodeError::InvalidLen {
+                what: "nesting depth",
+                ..
+            })
+        ));
+        let mut de = CairoSerde(Limited::new(
+            felts.as_slice().into_source(),
+            DecodeLimits::UNLIMITED,
+        ));
+        assert!(de.deserialize_type_def().is_ok());
+    }
+
+    #[test]
+    fn rejects_long_byte_arrays() {
+        let felts = vec![Felt::TWO, Felt::ONE, Felt::ONE, Felt::ZERO, Felt::ZERO];
+        let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits()));
+        assert!(matches!(
+            de.next_byte_array(),
+            Err(DecodeError::InvalidLen {
+                what: "byte array",
+                ..
+            })
+        ));
+        let felts = vec![Felt::ZERO, Felt::ZERO];
+        let mut de = CairoISerde(Limited::new(felts.as_slice().into_source(), limits()));
+        assert!(matches!(
+            de.next_byte_array(),
+            Err(DecodeError::InvalidLen {
+                what: "byte array",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_deep_values_when_transcoding_and_serializing() {
+        let type_def = ArrayDef::new_type_def(ArrayDef::new_type_def(ArrayDef::new_type_def(
+            ArrayDef::new_type_def(TypeDef::U8),
+        )));
+        let felts = vec![Felt::ONE, Felt::ONE, Felt::ONE, Felt::ONE, Felt::ONE];
+        let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits()));
+        assert!(matches!(
+            type_def.transcode(&mut de, &mut Vec::<u8>::new()),
+            Err(TranscodeError::Deserialize(DecodeError::InvalidLen {
+                what: "nesting depth",
+                ..
+            }))
+        ));
+
+        let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits()));
+        let err = serde_json::to_string(&type_def.to_de_se(&mut de, &Json)).unwrap_err();
+        assert!(err.to_string().contains("nesting depth"), "{err}");
+
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        assert!(type_def.transcode(&mut de, &mut Vec::<u8>::new()).is_ok());
+    }
+
+    #[test]
+    fn rejects_hostile_array_lengths_when_transcoding_and_serializing() {
+        let array = ArrayDef::new_type_def(TypeDef::U8);
+        let hostile = vec![Felt::from(u32::MAX)];
+        let mut de = CairoSerde(Limited::new(hostile.as_slice().into_source(), limits()));
+        assert!(matches!(
+            array.transcode(&mut de, &mut Vec::<u8>::new()),
+            Err(TranscodeError::Deserialize(DecodeError::InvalidLen {
+                what: "array",
+                ..
+            }))
+        ));
+        let mut de = CairoSerde(Limited::new(hostile.as_slice().into_source(), limits()));
+        let err = serde_json::to_string(&array.to_de_se(&mut de, &Json)).unwrap_err();
+        assert!(err.to_string().contains("array"), "{err}");
+
+        let fixed = FixedArrayDef::new_type_def(TypeDef::U8, 5);
+        let felts = vec![Felt::ONE; 5];
+        let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits()));
+        assert!(matches!(
+            fixed.transcode(&mut de, &mut Vec::<u8>::new()),
+            Err(TranscodeError::Deserialize(DecodeError::InvalidLen {
+                what: "fixed size array",
+                ..
+            }))
+        ));
+        let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits()));
+        let err = serde_json::to_string(&fixed.to_de_se(&mut de, &Json)).unwrap_err();
+        assert!(err.to_string().contains("fixed size array"), "{err}");
+    }
+}
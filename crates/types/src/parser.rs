@@ -1,4 +1,6 @@
 use crate::deserialize::CairoDeserializer;
+use crate::limits::capped_capacity;
+use crate::path::{Locate, Location, PathSegment, with_segment};
 use crate::type_def::{ByteArrayEncodedDef, FixedArrayDef, MemberDef, StructDef, TypeDef};
 use crate::utils::ResultInto;
 use crate::value::{Enum, Nullable, Value};
@@ -13,7 +15,7 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum TypeParserError {
     #[error(transparent)]
-    Decode(#[from] DecodeError),
+    Decode(DecodeError),
 
     #[error("type not supported: {0}")]
     UnsupportedType(&'static str),
@@ -24,6 +26,13 @@ pub enum TypeParserError {
     #[error("invalid enum selector for {enum_name}: {selector:?}")]
     InvalidEnumSelector { enum_name: String, selector: Felt },
 
+    #[error("enum variant index out of range for {enum_name}: {index:?} (variants {len})")]
+    EnumIndexOutOfRange {
+        enum_name: String,
+        index: Felt,
+        len: usize,
+    },
+
     #[error("Cannot parse an un expaneded Ref type. ")]
     RefNotSupported,
 
@@ -39,6 +48,15 @@ pub enum TypeParserError {
 
     #[error("invariant violation: {0}")]
     InvariantViolation(&'static str),
+
+    #[error("unknown column: {0:#x}")]
+    UnknownColumn(Felt),
+
+    #[error("{source} at {location}")]
+    Located {
+        location: Box<Location>,
+        source: Box<TypeParserError>,
+    },
 }
 
 impl TypeParserError {
@@ -65,6 +83,15 @@ impl TypeParserError {
         }
     }
 
+    #[inline]
+    pub fn enum_index_out_of_range(enum_name: impl Into<String>, index: Felt, len: usize) -> Self {
+        Self::EnumIndexOutOfRange {
+            enum_name: enum_name.into(),
+            index,
+            len,
+        }
+    }
+
     #[inline]
     pub fn unknown_encoding(enc: impl Into<String>) -> Self {
         Self::UnknownEncoding(enc.into())
@@ -79,7 +106,47 @@ impl TypeParserError {
     pub fn invariant(msg: &'static str) -> Self {
         Self::InvariantViolation(msg)
     }
+
+    #[inline]
+    pub fn unknown_column(id: Felt) -> Self {
+        Self::UnknownColumn(id)
+    }
 }
+impl Locate for TypeParserError {
+    fn is_located(&self) -> bool {
+        matches!(self, Self::Located { .. })
+    }
+
+    fn with_location(self, location: Location) -> Self {
+        Self::Located {
+            location: Box::new(location),
+            source: Box::new(self),
+        }
+    }
+}
+
+impl From<DecodeError> for TypeParserError {
+    fn from(e: DecodeError) -> Self {
+        match e {
+            DecodeError::InvalidLen { what, len, max } => Self::InvalidLength { what, len, max },
+            DecodeError::InvalidEnumSelector { name, value } => Self::InvalidEnumSelector {
+                enum_name: name.into_owned(),
+                selector: value,
+            },
+            DecodeError::EnumIndexOutOfRange { name, index, len } => Self::EnumIndexOutOfRange {
+                enum_name: name.into_owned(),
+                index,
+                len,
+            },
+            DecodeError::Located { location, source } => Self::Located {
+                location,
+                source: Box::new((*source).into()),
+            },
+            e => Self::Decode(e),
+        }
+    }
+}
+
 pub type TypeParserResult<T> = Result<T, TypeParserError>;
 
 pub trait ParseValues<D: CairoDeserializer> {
@@ -107,19 +174,21 @@ pub trait ParseValue<D: CairoDeserializer> {
         deserializer: &mut D,
         count: usize,
     ) -> TypeParserResult<Vec<Self::Value>> {
-        (0..count)
-            .into_iter()
-            .map(|_| self.parse(deserializer))
-            .collect()
+        let mut values = Vec::with_capacity(capped_capacity(count));
+        for _ in 0..count {
+            values.push(self.parse(deserializer)?);
+        }
+        Ok(values)
     }
 }
 
 impl<D: CairoDeserializer> ParseValue<D> for TypeDef {
     type Value = Value;
     fn parse(&self, deserializer: &mut D) -> TypeParserResult<Value> {
-        match self {
+        deserializer.enter_nested()?;
+        let value = match self {
             TypeDef::None => Ok(Value::None),
-            TypeDef::Felt252 => Ok(Value::Felt252(deserializer.next_felt()?)),
+            TypeDef::Felt252 => deserializer.next_felt().map_into(Value::Felt252),
             TypeDef::ShortUtf8 => deserializer
                 .next_bytes31()
                 .map_into(|b| Value::ShortUtf8(b.to_string())),
@@ -163,17 +232,39 @@ impl<D: CairoDeserializer> ParseValue<D> for TypeDef {
             TypeDef::Nullable(nullable) => nullable
                 .parse_value_boxed(deserializer)
                 .map(Value::Nullable),
-        }
+        };
+        deserializer.exit_nested();
+        value.map_err(|e| e.locate(deserializer))
     }
 }
 
+fn parse_elements<D: CairoDeserializer>(
+    type_def: &TypeDef,
+    deserializer: &mut D,
+    count: usize,
+) -> TypeParserResult<Vec<Value>> {
+    let mut values = Vec::with_capacity(capped_capacity(count));
+    for index in 0..count {
+        values.push(with_segment(
+            deserializer,
+            || PathSegment::Index(index),
+            |d| type_def.parse(d),
+        )?);
+    }
+    Ok(values)
+}
+
 impl<D: CairoDeserializer> ParseValue<D> for MemberDef {
     type Value = Member;
     fn parse(&self, deserializer: &mut D) -> TypeParserResult<Member> {
         Ok(Member {
             name: self.name.clone(),
             attributes: self.attributes.clone(),
-            value: self.type_def.parse(deserializer)?,
+            value: with_segment(
+                deserializer,
+                || PathSegment::Member(self.name.clone()),
+                |d| self.type_def.parse(d),
+            )?,
         })
     }
 }
@@ -184,7 +275,11 @@ impl<D: CairoDeserializer> ParseValue<D> for StructDef {
         Ok(Struct {
             name: self.name.clone(),
             attributes: self.attributes.clone(),
-            members: self.members.parse_values(deserializer)?,
+            members: with_segment(
+                deserializer,
+                || PathSegment::Type(self.name.clone()),
+                |d| self.members.parse_values(d),
+            )?,
         })
     }
 }
@@ -192,49 +287,65 @@ impl<D: CairoDeserializer> ParseValue<D> for StructDef {
 impl<D: CairoDeserializer> ParseValue<D> for ArrayDef {
     type Value = Vec<Value>;
     fn parse(&self, deserializer: &mut D) -> TypeParserResult<Vec<Value>> {
-        let count = deserializer.next_u32()?;
-        self.type_def
-            .parse_multiple_values(deserializer, count as usize)
+        let count = deserializer.next_u32()? as usize;
+        deserializer.check_array_len("array", count)?;
+        parse_elements(&self.type_def, deserializer, count)
     }
 }
 
 impl<D: CairoDeserializer> ParseValue<D> for FixedArrayDef {
     type Value = Vec<Value>;
     fn parse(&self, deserializer: &mut D) -> TypeParserResult<Vec<Value>> {
-        self.type_def
-            .parse_multiple_values(deserializer, self.size as usize)
+        deserializer.check_array_len("fixed size array", self.size as usize)?;
+        parse_elements(&self.type_def, deserializer, self.size as usize)
     }
 }
 
 impl<D: CairoDeserializer> ParseValue<D> for TupleDef {
     type Value = Vec<Value>;
     fn parse(&self, deserializer: &mut D) -> TypeParserResult<Vec<Value>> {
-        self.elements.parse_values(deserializer)
+        let mut values = Vec::with_capacity(self.elements.len());
+        for (index, element) in self.elements.iter().enumerate() {
+            values.push(with_segment(
+                deserializer,
+                || PathSegment::Index(index),
+                |d| element.parse(d),
+            )?);
+        }
+        Ok(values)
     }
 }
 
 impl<D: CairoDeserializer> ParseValue<D> for EnumDef {
     type Value = Enum;
     fn parse(&self, deserializer: &mut D) -> TypeParserResult<Enum> {
-        let selector = deserializer.next_felt()?;
-        let field = self
-            .variants
-            .get(&selector)
-            .ok_or(TypeParserError::InvalidEnumSelector {
-                enum_name: self.name.clone(),
-                selector,
-            })?;
-
-        Ok(Enum {
-            name: self.name.clone(),
-            attributes: self.attributes.clone(),
-            variant: field.name.clone(),
-            variant_attributes: field.attributes.clone(),
-            value: field.type_def.parse(deserializer)?,
-        })
+        with_segment(
+            deserializer,
+            || PathSegment::Type(self.name.clone()),
+            |d| parse_enum(self, d),
+        )
     }
 }
 
+fn parse_enum<D: CairoDeserializer>(
+    enum_def: &EnumDef,
+    deserializer: &mut D,
+) -> TypeParserResult<Enum> {
+    let (_, field) = deserializer.next_enum_variant_def(enum_def)?;
+
+    Ok(Enum {
+        name: enum_def.name.clone(),
+        attributes: enum_def.attributes.clone(),
+        variant: field.name.clone(),
+        variant_attributes: field.attributes.clone(),
+        value: with_segment(
+            deserializer,
+            || PathSegment::Variant(field.name.clone()),
+            |d| field.type_def.parse(d),
+        )?,
+    })
+}
+
 impl<D: CairoDeserializer> ParseValue<D> for OptionDef {
     type Value = CairoOption<Value>;
     fn parse(&self, deserializer: &mut D) -> TypeParserResult<CairoOption<Value>> {
@@ -314,7 +425,11 @@ impl<D: CairoDeserializer> ParseValue<D> for ColumnDef {
             id: self.id.clone(),
             name: self.name.clone(),
             attributes: self.attributes.clone(),
-            value: self.type_def.parse(deserializer)?,
+            value: with_segment(
+                deserializer,
+                || PathSegment::Member(self.name.clone()),
+                |d| self.type_def.parse(d),
+            )?,
         })
     }
 }
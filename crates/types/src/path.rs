@@ -0,0 +1,304 @@
+use crate::bytes::ByteSource;
+use crate::limits::DecodeBudget;
+use crate::{CairoDeserializer, DecodeResult, FeltSource};
+use starknet_types_core::felt::Felt;
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    Type(String),
+    Member(String),
+    Index(usize),
+    Variant(String),
+}
+
+/// Breadcrumb of the item being decoded, displayed as `Player.stats[2].kind`.
+///
+/// Type names are only shown when they are the root of the path.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DecodePath(Vec<PathSegment>);
+
+/// Where a decode error occurred: the path and the position of the source
+/// (felts for felt sources, bytes for byte sources).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub path: DecodePath,
+    pub position: Option<usize>,
+}
+
+impl DecodePath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.0.push(segment);
+    }
+
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.0.pop()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for DecodePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Type(name) if i == 0 => write!(f, "{name}")?,
+                PathSegment::Type(_) => continue,
+                PathSegment::Member(name) if first => write!(f, "{name}")?,
+                PathSegment::Member(name) => write!(f, ".{name}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+                PathSegment::Variant(name) => write!(f, "::{name}")?,
+            }
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.path.is_empty(), self.position) {
+            (false, Some(position)) => write!(f, "{} (offset {position})", self.path),
+            (false, None) => write!(f, "{}", self.path),
+            (true, Some(position)) => write!(f, "offset {position}"),
+            (true, None) => write!(f, "unknown location"),
+        }
+    }
+}
+
+/// Errors that can carry the `Location` they occurred at.
+pub trait Locate: Sized {
+    fn is_located(&self) -> bool;
+    fn with_location(self, location: Location) -> Self;
+
+    /// Attaches the current location of the deserializer, unless the error
+    /// already has one or the deserializer is not tracing its path.
+    fn locate<D: CairoDeserializer + ?Sized>(self, deserializer: &mut D) -> Self {
+        match self.is_located() {
+            true => self,
+            false => match deserializer.location() {
+                Some(location) => self.with_location(location),
+                None => self,
+            },
+        }
+    }
+}
+
+/// Runs `f` with `segment` pushed onto the decode path of the deserializer, the
+/// segment is only built when the path is traced.
+pub fn with_segment<D, T, E, F, S>(deserializer: &mut D, segment: S, f: F) -> Result<T, E>
+where
+    D: CairoDeserializer + ?Sized,
+    S: FnOnce() -> PathSegment,
+    F: FnOnce(&mut D) -> Result<T, E>,
+{
+    match deserializer.decode_path() {
+        Some(path) => path.push(segment()),
+        None => return f(deserializer),
+    }
+    let result = f(deserializer);
+    if let Some(path) = deserializer.decode_path() {
+        path.pop();
+    }
+    result
+}
+
+/// Wraps a `FeltSource` or `ByteSource` to trace the decode path, so errors
+/// raised while decoding through it are annotated with their `Location`.
+pub struct Traced<S> {
+    source: S,
+    path: DecodePath,
+}
+
+impl<S> Traced<S> {
+    pub fn new(source: S) -> Self {
+        Traced {
+            source,
+            path: DecodePath::new(),
+        }
+    }
+
+    /// Starts the path at `name`, e.g. the table the payload belongs to.
+    pub fn with_root(source: S, name: impl Into<String>) -> Self {
+        Traced {
+            source,
+            path: DecodePath(vec![PathSegment::Type(name.into())]),
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<F: FeltSource> FeltSource for Traced<F> {
+    #[inline]
+    fn next(&mut self) -> DecodeResult<Felt> {
+        self.source.next()
+    }
+
+    #[inline]
+    fn position(&self) -> usize {
+        self.source.position()
+    }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        self.source.budget()
+    }
+
+    #[inline]
+    fn path(&mut self) -> Option<&mut DecodePath> {
+        Some(&mut self.path)
+    }
+}
+
+impl<B: ByteSource> ByteSource for Traced<B> {
+    #[inline]
+    fn next(&mut self) -> DecodeResult<u8> {
+        self.source.next()
+    }
+
+    #[inline]
+    fn nexts<const N: usize>(&mut self) -> DecodeResult<[u8; N]> {
+        self.source.nexts::<N>()
+    }
+
+    #[inline]
+    fn position(&self) -> usize {
+        self.source.position()
+    }
+
+    #[inline]
+    fn budget(&mut self) -> Option<&mut DecodeBudget> {
+        self.source.budget()
+    }
+
+    #[inline]
+    fn path(&mut self) -> Option<&mut DecodePath> {
+        Some(&mut self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TypeParserError;
+    use crate::serialize::ToCairoDeSeFrom;
+    use crate::serialize_def::CairoTypeSerialization;
+    use crate::transcode::{Transcode, TranscodeError};
+    use crate::{
+        ArrayDef, CairoSerde, DecodeError, EnumDef, IntoFeltSource, MemberDef, ParseValue,
+        StructDef, TypeDef, VariantDef,
+    };
+
+    struct Json;
+    impl CairoTypeSerialization for Json {}
+
+    fn player() -> TypeDef {
+        let kind = EnumDef::new_type_def(
+            "Kind".to_string(),
+            vec![],
+            vec![(
+                Felt::from(0x10),
+                VariantDef::new("Fire".to_string(), vec![], TypeDef::U8),
+            )],
+        );
+        let stat = StructDef::new_type_def(
+            "Stat".to_string(),
+            vec![],
+            vec![MemberDef::new("kind".to_string(), vec![], kind)],
+        );
+        StructDef::new_type_def(
+            "Player".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("level".to_string(), vec![], TypeDef::U8),
+                MemberDef::new("stats".to_string(), vec![], ArrayDef::new_type_def(stat)),
+            ],
+        )
+    }
+
+    fn felts() -> Vec<Felt> {
+        let fire = Felt::from(0x10);
+        vec![
+            Felt::ONE,
+            Felt::from(3),
+            fire,
+            Felt::ONE,
+            fire,
+            Felt::TWO,
+            Felt::from(0x20),
+        ]
+    }
+
+    #[test]
+    fn locates_parse_errors() {
+        let felts = felts();
+        let mut de = CairoSerde(Traced::new(felts.as_slice().into_source()));
+        let err = player().parse(&mut de).unwrap_err();
+        let TypeParserError::Located { location, source } = &err else {
+            panic!("expected located error, got {err:?}");
+        };
+        assert_eq!(location.path.to_string(), "Player.stats[2].kind");
+        assert_eq!(location.position, Some(7));
+        assert!(matches!(
+            **source,
+            TypeParserError::InvalidEnumSelector { .. }
+        ));
+
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        assert!(matches!(
+            player().parse(&mut de),
+            Err(TypeParserError::InvalidEnumSelector { .. })
+        ));
+    }
+
+    #[test]
+    fn locates_transcode_and_serialize_errors() {
+        let felts = &felts()[..5];
+        let mut de = CairoSerde(Traced::with_root(felts.into_source(), "players"));
+        let mut output = Vec::new();
+        let Err(TranscodeError::Deserialize(DecodeError::Located { location, source })) =
+            player().transcode(&mut de, &mut output)
+        else {
+            panic!("expected located error");
+        };
+        assert_eq!(
+            location.to_string(),
+            "players.stats[1].kind::Fire (offset 5)"
+        );
+        assert_eq!(*source, DecodeError::Eof);
+
+        let mut de = CairoSerde(Traced::new(felts.into_source()));
+        let err = serde_json::to_string(&player().to_de_se(&mut de, &Json)).unwrap_err();
+        assert!(
+            err.to_string().contains("at Player.stats[1].kind::Fire"),
+            "{err}"
+        );
+    }
+    #[test]
+    fn located_eof_is_still_eof() {
+        let felts = &felts()[..5];
+        let mut de = CairoSerde(Traced::new(felts.into_source()));
+        let Err(TranscodeError::Deserialize(err)) =
+            player().transcode(&mut de, &mut Vec::<u8>::new())
+        else {
+            panic!("expected deserialize error");
+        };
+        assert!(err.is_located());
+        assert!(err.is_eof());
+        assert!(!DecodeError::NotEof.is_eof());
+    }
+}
@@ -0,0 +1,1246 @@
+use crate::limits::capped_capacity;
+use crate::parser::{TypeParserError, TypeParserResult};
+use crate::path::{Locate, PathSegment, with_segment};
+use crate::serialize::ToCairoDeSeFrom;
+use crate::serialize_def::{CairoSerialize, CairoTypeSerialization, located};
+use crate::transcode::{
+    CairoWrite, Transcode, TranscodeError, TranscodeResult, TranscodeSerializeResult,
+    TranscodeWriter,
+};
+use crate::{
+    Attribute, CairoDeserializer, CairoOption, CairoResult, DecodeError, DecodeResult, Enum,
+    EnumDef, EnumVariantEncoding, Member, Nullable, ParseValue, Struct, TypeDef, Value,
+};
+use serde::Serializer;
+use serde::ser::{SerializeMap, SerializeSeq};
+use starknet_types_core::felt::Felt;
+
+/// A `TypeDef` compiled into a flat program, for schemas decoded often enough
+/// for the tree walk to matter.
+///
+/// Composite types become instructions pointing at the programs of their
+/// children, enum variants are resolved with a sorted selector table and fixed
+/// size arrays of primitives become a single run. The plan drives `ParseValue`,
+/// `Transcode` and `CairoSerialize` with the same output as the `TypeDef` it was
+/// compiled from. Tuples and results are handed to the generic
+/// `CairoTypeSerialization` hooks with their plan nodes as children, enums to
+/// `serialize_enum` with the `EnumDef` they were compiled from.
+#[derive(Clone, Debug)]
+pub struct DecodePlan {
+    ops: Vec<Op>,
+    structs: Vec<StructPlan>,
+    enums: Vec<EnumPlan>,
+}
+
+/// A node of a `DecodePlan`, the unit its interpreters recurse on.
+#[derive(Clone, Copy, Debug)]
+pub struct PlanNode<'a> {
+    plan: &'a DecodePlan,
+    pc: u32,
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    /// A single felt primitive, decoded without going through a `TypeDef`.
+    Primitive(Primitive),
+    /// A fixed size array of primitives.
+    Run {
+        primitive: Primitive,
+        size: u32,
+    },
+    /// Any other type without children, decoded by the `TypeDef` itself.
+    Leaf(Box<TypeDef>),
+    Struct(u32),
+    Tuple(Vec<u32>),
+    /// Elements start at the next instruction, as do the payloads of `FixedArray`,
+    /// `Option`, `Nullable` and the ok payload of `Result`.
+    Array,
+    FixedArray(u32),
+    Enum(u32),
+    Option,
+    Result {
+        err: u32,
+    },
+    Nullable,
+}
+
+#[derive(Clone, Debug)]
+struct StructPlan {
+    name: String,
+    attributes: Vec<Attribute>,
+    members: Vec<FieldPlan>,
+}
+
+#[derive(Clone, Debug)]
+struct FieldPlan {
+    name: String,
+    attributes: Vec<Attribute>,
+    start: u32,
+}
+
+#[derive(Clone, Debug)]
+struct VariantPlan {
+    selector: Felt,
+    field: FieldPlan,
+}
+
+#[derive(Clone, Debug)]
+struct EnumPlan {
+    name: String,
+    attributes: Vec<Attribute>,
+    /// Variants in declaration order, so index encoded variants are a lookup.
+    variants: Vec<VariantPlan>,
+    /// Raw limbs of the selectors, sorted for binary search, with the index of
+    /// their variant. Unlike `Felt::cmp`, comparing limbs needs no conversion out
+    /// of Montgomery form.
+    selectors: Vec<([u64; 4], u32)>,
+    /// Handed to `serialize_enum`, which takes the definition.
+    enum_def: EnumDef,
+}
+
+/// Types decoded from a single felt, the bulk of most schemas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Primitive {
+    Felt252,
+    ClassHash,
+    ContractAddress,
+    EthAddress,
+    StorageAddress,
+    StorageBaseAddress,
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+impl Primitive {
+    fn of(type_def: &TypeDef) -> Option<Self> {
+        Some(match type_def {
+            TypeDef::Felt252 => Primitive::Felt252,
+            TypeDef::ClassHash => Primitive::ClassHash,
+            TypeDef::ContractAddress => Primitive::ContractAddress,
+            TypeDef::EthAddress => Primitive::EthAddress,
+            TypeDef::StorageAddress => Primitive::StorageAddress,
+            TypeDef::StorageBaseAddress => Primitive::StorageBaseAddress,
+            TypeDef::Bool => Primitive::Bool,
+            TypeDef::U8 => Primitive::U8,
+            TypeDef::U16 => Primitive::U16,
+            TypeDef::U32 => Primitive::U32,
+            TypeDef::U64 => Primitive::U64,
+            TypeDef::U128 => Primitive::U128,
+            TypeDef::I8 => Primitive::I8,
+            TypeDef::I16 => Primitive::I16,
+            TypeDef::I32 => Primitive::I32,
+            TypeDef::I64 => Primitive::I64,
+            TypeDef::I128 => Primitive::I128,
+            _ => return None,
+        })
+    }
+
+    /// Same as `TypeDef::parse`, including the nesting check and location.
+    #[inline]
+    fn parse<D: CairoDeserializer>(self, deserializer: &mut D) -> TypeParserResult<Value> {
+        deserializer.enter_nested()?;
+        let value = self.next_value(deserializer);
+        deserializer.exit_nested();
+        value.map_err(|e| TypeParserError::from(e).locate(deserializer))
+    }
+
+    #[inline]
+    fn next_value<D: CairoDeserializer>(self, deserializer: &mut D) -> DecodeResult<Value> {
+        Ok(match self {
+            Primitive::Felt252 => Value::Felt252(deserializer.next_felt()?),
+            Primitive::ClassHash => Value::ClassHash(deserializer.next_felt()?),
+            Primitive::ContractAddress => Value::ContractAddress(deserializer.next_felt()?),
+            Primitive::EthAddress => Value::EthAddress(deserializer.next_felt()?),
+            Primitive::StorageAddress => Value::StorageAddress(deserializer.next_felt()?),
+            Primitive::StorageBaseAddress => Value::StorageBaseAddress(deserializer.next_felt()?),
+            Primitive::Bool => Value::Bool(deserializer.next_bool()?),
+            Primitive::U8 => Value::U8(deserializer.next_u8()?),
+            Primitive::U16 => Value::U16(deserializer.next_u16()?),
+            Primitive::U32 => Value::U32(deserializer.next_u32()?),
+            Primitive::U64 => Value::U64(deserializer.next_u64()?),
+            Primitive::U128 => Value::U128(deserializer.next_u128()?),
+            Primitive::I8 => Value::I8(deserializer.next_i8()?),
+            Primitive::I16 => Value::I16(deserializer.next_i16()?),
+            Primitive::I32 => Value::I32(deserializer.next_i32()?),
+            Primitive::I64 => Value::I64(deserializer.next_i64()?),
+            Primitive::I128 => Value::I128(deserializer.next_i128()?),
+        })
+    }
+
+    /// Same as `TypeDef::transcode`, including the nesting check and location.
+    #[inline]
+    fn transcode<In: CairoDeserializer, Out: CairoWrite>(
+        self,
+        input: &mut In,
+        output: &mut Out,
+    ) -> Result<(), TranscodeError<DecodeError, <Out as CairoWrite>::Error>> {
+        input.enter_nested().map_de()?;
+        let result = self.transcode_value(input, output);
+        input.exit_nested();
+        result.map_err(|e| e.locate(input))
+    }
+
+    #[inline]
+    fn transcode_value<In: CairoDeserializer, Out: CairoWrite>(
+        self,
+        input: &mut In,
+        output: &mut Out,
+    ) -> Result<(), TranscodeError<DecodeError, <Out as CairoWrite>::Error>> {
+        match self {
+            Primitive::Felt252
+            | Primitive::ClassHash
+            | Primitive::ContractAddress
+            | Primitive::StorageAddress
+            | Primitive::StorageBaseAddress => output.transcode_felt(input),
+            Primitive::Bool | Primitive::U8 => output.transcode_bytes::<1>(input),
+            Primitive::U16 => output.transcode_bytes::<2>(input),
+            Primitive::U32 => output.transcode_bytes::<4>(input),
+            Primitive::U64 => output.transcode_bytes::<8>(input),
+            Primitive::U128 => output.transcode_bytes::<16>(input),
+            Primitive::EthAddress => output.transcode_bytes::<20>(input),
+            Primitive::I8 => input.next_i8().and_then_tc(|v| output.write_byte(v as u8)),
+            Primitive::I16 => input
+                .next_i16()
+                .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
+            Primitive::I32 => input
+                .next_i32()
+                .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
+            Primitive::I64 => input
+                .next_i64()
+                .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
+            Primitive::I128 => input
+                .next_i128()
+                .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
+        }
+    }
+}
+
+impl<'a, C: CairoTypeSerialization> CairoSerialize<'a, C> for Primitive {
+    /// Same as `TypeDef::serialize_cairo`, including the nesting check.
+    fn serialize_cairo<S: Serializer>(
+        &self,
+        data: &mut impl CairoDeserializer,
+        cairo_se: &C,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        data.enter_nested().map_err(|e| located(data, e))?;
+        let result = match self {
+            Primitive::Felt252
+            | Primitive::ClassHash
+            | Primitive::ContractAddress
+            | Primitive::StorageAddress
+            | Primitive::StorageBaseAddress => data
+                .next_felt_bytes()
+                .map(|value| cairo_se.serialize_felt(serializer, &value)),
+            Primitive::EthAddress => data
+                .next_eth_address()
+                .map(|value| cairo_se.serialize_eth_address(serializer, &value.0)),
+            Primitive::Bool => data.next_bool().map(|v| serializer.serialize_bool(v)),
+            Primitive::U8 => data.next_u8().map(|v| serializer.serialize_u8(v)),
+            Primitive::U16 => data.next_u16().map(|v| serializer.serialize_u16(v)),
+            Primitive::U32 => data.next_u32().map(|v| serializer.serialize_u32(v)),
+            Primitive::U64 => data.next_u64().map(|v| serializer.serialize_u64(v)),
+            Primitive::U128 => data.next_u128().map(|v| serializer.serialize_u128(v)),
+            Primitive::I8 => data.next_i8().map(|v| serializer.serialize_i8(v)),
+            Primitive::I16 => data.next_i16().map(|v| serializer.serialize_i16(v)),
+            Primitive::I32 => data.next_i32().map(|v| serializer.serialize_i32(v)),
+            Primitive::I64 => data.next_i64().map(|v| serializer.serialize_i64(v)),
+            Primitive::I128 => data.next_i128().map(|v| serializer.serialize_i128(v)),
+        };
+        data.exit_nested();
+        result.map_err(|e| located(data, e))?
+    }
+}
+
+fn is_leaf(type_def: &TypeDef) -> bool {
+    !matches!(
+        type_def,
+        TypeDef::Struct(_)
+            | TypeDef::Tuple(_)
+            | TypeDef::Array(_)
+            | TypeDef::FixedArray(_)
+            | TypeDef::Enum(_)
+            | TypeDef::Option(_)
+            | TypeDef::Result(_)
+            | TypeDef::Nullable(_)
+            | TypeDef::Felt252Dict(_)
+            | TypeDef::Ref(_)
+    )
+}
+
+impl EnumPlan {
+    fn next_variant<D: CairoDeserializer + ?Sized>(
+        &self,
+        deserializer: &mut D,
+    ) -> DecodeResult<&VariantPlan> {
+        let variant = deserializer.next_enum_variant()?;
+        match deserializer.enum_variant_encoding() {
+            EnumVariantEncoding::Selector => self
+                .selectors
+                .binary_search_by(|(selector, _)| selector.cmp(&variant.to_raw()))
+                .map(|i| &self.variants[self.selectors[i].1 as usize])
+                .map_err(|_| DecodeError::invalid_enum_selector(self.name.clone(), variant)),
+            EnumVariantEncoding::Index => usize::try_from(variant)
+                .ok()
+                .and_then(|i| self.variants.get(i))
+                .ok_or_else(|| {
+                    DecodeError::enum_index_out_of_range(
+                        self.name.clone(),
+                        variant,
+                        self.variants.len(),
+                    )
+                }),
+        }
+    }
+}
+
+impl DecodePlan {
+    /// Compiles `type_def`, failing on types that cannot be decoded (`Ref` and
+    /// `Felt252Dict`) instead of when they are reached in a payload.
+    pub fn compile(type_def: &TypeDef) -> TypeParserResult<Self> {
+        let mut plan = DecodePlan {
+            ops: Vec::new(),
+            structs: Vec::new(),
+            enums: Vec::new(),
+        };
+        plan.compile_node(type_def)?;
+        Ok(plan)
+    }
+
+    pub fn root(&self) -> PlanNode<'_> {
+        self.node(0)
+    }
+
+    /// Number of instructions in the program.
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    fn node(&self, pc: u32) -> PlanNode<'_> {
+        PlanNode { plan: self, pc }
+    }
+
+    fn push(&mut self, op: Op) -> u32 {
+        self.ops.push(op);
+        (self.ops.len() - 1) as u32
+    }
+
+    fn compile_fields<'a>(
+        &mut self,
+        fields: impl IntoIterator<Item = (&'a String, &'a Vec<Attribute>, &'a TypeDef)>,
+    ) -> TypeParserResult<Vec<FieldPlan>> {
+        fields
+            .into_iter()
+            .map(|(name, attributes, type_def)| {
+                Ok(FieldPlan {
+                    name: name.clone(),
+                    attributes: attributes.clone(),
+                    start: self.compile_node(type_def)?,
+                })
+            })
+            .collect()
+    }
+
+    fn compile_node(&mut self, type_def: &TypeDef) -> TypeParserResult<u32> {
+        if let Some(primitive) = Primitive::of(type_def) {
+            return Ok(self.push(Op::Primitive(primitive)));
+        }
+        // Nodes with children are pushed as a placeholder and patched once the
+        // children are compiled, so every node starts before its children.
+        let pc = match type_def {
+            TypeDef::Ref(_) => return Err(TypeParserError::RefNotSupported),
+            TypeDef::Felt252Dict(_) => return Err(TypeParserError::Unimplemented("Felt252Dict")),
+            type_def if is_leaf(type_def) => {
+                return Ok(self.push(Op::Leaf(Box::new(type_def.clone()))));
+            }
+            TypeDef::FixedArray(fa) => match Primitive::of(&fa.type_def) {
+                Some(primitive) => {
+                    let size = fa.size;
+                    return Ok(self.push(Op::Run { primitive, size }));
+                }
+                None => self.push(Op::Array),
+            },
+            _ => self.push(Op::Array),
+        };
+        let op = match type_def {
+            TypeDef::Struct(s) => {
+                let members = self.compile_fields(
+                    s.members
+                        .iter()
+                        .map(|m| (&m.name, &m.attributes, &m.type_def)),
+                )?;
+                self.structs.push(StructPlan {
+                    name: s.name.clone(),
+                    attributes: s.attributes.clone(),
+                    members,
+                });
+                Op::Struct((self.structs.len() - 1) as u32)
+            }
+            TypeDef::Tuple(tuple) => Op::Tuple(
+                tuple
+                    .elements
+                    .iter()
+                    .map(|element| self.compile_node(element))
+                    .collect::<TypeParserResult<_>>()?,
+            ),
+            TypeDef::Array(a) => {
+                self.compile_node(&a.type_def)?;
+                Op::Array
+            }
+            TypeDef::FixedArray(fa) => {
+                self.compile_node(&fa.type_def)?;
+                Op::FixedArray(fa.size)
+            }
+            TypeDef::Enum(e) => {
+                let variants = e
+                    .order
+                    .iter()
+                    .map(|selector| {
+                        e.variants
+                            .get(selector)
+                            .ok_or(TypeParserError::invariant("enum order without variant"))
+                    })
+                    .collect::<TypeParserResult<Vec<_>>>()?;
+                let fields = self.compile_fields(
+                    variants
+                        .into_iter()
+                        .map(|v| (&v.name, &v.attributes, &v.type_def)),
+                )?;
+                let variants = e
+                    .order
+                    .iter()
+                    .zip(fields)
+                    .map(|(selector, field)| VariantPlan {
+                        selector: *selector,
+                        field,
+                    })
+                    .collect();
+                let mut selectors: Vec<([u64; 4], u32)> = e
+                    .order
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| (s.to_raw(), i as u32))
+                    .collect();
+                selectors.sort_unstable();
+                self.enums.push(EnumPlan {
+                    name: e.name.clone(),
+                    attributes: e.attributes.clone(),
+                    variants,
+                    selectors,
+                    enum_def: e.clone(),
+                });
+                Op::Enum((self.enums.len() - 1) as u32)
+            }
+            TypeDef::Option(option) => {
+                self.compile_node(&option.type_def)?;
+                Op::Option
+            }
+            TypeDef::Result(result) => {
+                self.compile_node(&result.ok)?;
+                Op::Result {
+                    err: self.compile_node(&result.err)?,
+                }
+            }
+            TypeDef::Nullable(nullable) => {
+                self.compile_node(&nullable.type_def)?;
+                Op::Nullable
+            }
+            _ => unreachable!("leaves are compiled above"),
+        };
+        self.ops[pc as usize] = op;
+        Ok(pc)
+    }
+}
+
+impl<'a> PlanNode<'a> {
+    fn op(&self) -> &'a Op {
+        &self.plan.ops[self.pc as usize]
+    }
+
+    fn next(&self) -> PlanNode<'a> {
+        self.plan.node(self.pc + 1)
+    }
+
+    fn parse_elements<D: CairoDeserializer>(
+        &self,
+        deserializer: &mut D,
+        count: usize,
+    ) -> TypeParserResult<Vec<Value>> {
+        let element = self.next();
+        let mut values = Vec::with_capacity(capped_capacity(count));
+        for index in 0..count {
+            values.push(with_segment(
+                deserializer,
+                || PathSegment::Index(index),
+                |d| element.parse(d),
+            )?);
+        }
+        Ok(values)
+    }
+
+    fn parse_struct<D: CairoDeserializer>(
+        &self,
+        struct_plan: &StructPlan,
+        deserializer: &mut D,
+    ) -> TypeParserResult<Value> {
+        let members = with_segment(
+            deserializer,
+            || PathSegment::Type(struct_plan.name.clone()),
+            |d| {
+                let mut members = Vec::with_capacity(struct_plan.members.len());
+                for member in &struct_plan.members {
+                    members.push(Member {
+                        name: member.name.clone(),
+                        attributes: member.attributes.clone(),
+                        value: with_segment(
+                            d,
+                            || PathSegment::Member(member.name.clone()),
+                            |d| self.plan.node(member.start).parse(d),
+                        )?,
+                    });
+                }
+                Ok::<_, TypeParserError>(members)
+            },
+        )?;
+        Ok(Value::Struct(Struct {
+            name: struct_plan.name.clone(),
+            attributes: struct_plan.attributes.clone(),
+            members,
+        }))
+    }
+
+    fn parse_enum<D: CairoDeserializer>(
+        &self,
+        enum_plan: &EnumPlan,
+        deserializer: &mut D,
+    ) -> TypeParserResult<Value> {
+        with_segment(
+            deserializer,
+            || PathSegment::Type(enum_plan.name.clone()),
+            |d| {
+                let variant = &enum_plan.next_variant(d)?.field;
+                Ok(Value::Enum(Box::new(Enum {
+                    name: enum_plan.name.clone(),
+                    attributes: enum_plan.attributes.clone(),
+                    variant: variant.name.clone(),
+                    variant_attributes: variant.attributes.clone(),
+                    value: with_segment(
+                        d,
+                        || PathSegment::Variant(variant.name.clone()),
+                        |d| self.plan.node(variant.start).parse(d),
+                    )?,
+                })))
+            },
+        )
+    }
+
+    fn parse_nested<D: CairoDeserializer>(&self, deserializer: &mut D) -> TypeParserResult<Value> {
+        if let Op::Leaf(leaf) = self.op() {
+            return leaf.parse(deserializer);
+        }
+        deserializer.enter_nested()?;
+        let value = self.parse_op(deserializer);
+        deserializer.exit_nested();
+        value.map_err(|e| e.locate(deserializer))
+    }
+
+    fn parse_op<D: CairoDeserializer>(&self, deserializer: &mut D) -> TypeParserResult<Value> {
+        let plan = self.plan;
+        match self.op() {
+            Op::Primitive(primitive) => primitive.parse(deserializer),
+            Op::Leaf(leaf) => leaf.parse(deserializer),
+            Op::Run { primitive, size } => {
+                deserializer.check_array_len("fixed size array", *size as usize)?;
+                let mut values = Vec::with_capacity(*size as usize);
+                for index in 0..*size as usize {
+                    values.push(with_segment(
+                        deserializer,
+                        || PathSegment::Index(index),
+                        |d| primitive.parse(d),
+                    )?);
+                }
+                Ok(Value::FixedArray(values))
+            }
+            Op::Struct(index) => self.parse_struct(&plan.structs[*index as usize], deserializer),
+            Op::Tuple(elements) => {
+                let mut values = Vec::with_capacity(elements.len());
+                for (index, start) in elements.iter().enumerate() {
+                    values.push(with_segment(
+                        deserializer,
+                        || PathSegment::Index(index),
+                        |d| plan.node(*start).parse(d),
+                    )?);
+                }
+                Ok(Value::Tuple(values))
+            }
+            Op::Array => {
+                let count = deserializer.next_u32()? as usize;
+                deserializer.check_array_len("array", count)?;
+                self.parse_elements(deserializer, count).map(Value::Array)
+            }
+            Op::FixedArray(size) => {
+                deserializer.check_array_len("fixed size array", *size as usize)?;
+                self.parse_elements(deserializer, *size as usize)
+                    .map(Value::FixedArray)
+            }
+            Op::Enum(index) => self.parse_enum(&plan.enums[*index as usize], deserializer),
+            Op::Option => {
+                let option = match deserializer.next_option_is_some()? {
+                    true => CairoOption::Some(self.next().parse(deserializer)?),
+                    false => CairoOption::None,
+                };
+                Ok(Value::Option(Box::new(option)))
+            }
+            Op::Result { err, .. } => {
+                let result = match deserializer.next_result_is_ok()? {
+                    true => CairoResult::Ok(self.next().parse(deserializer)?),
+                    false => CairoResult::Err(plan.node(*err).parse(deserializer)?),
+                };
+                Ok(Value::Result(Box::new(result)))
+            }
+            Op::Nullable => {
+                let nullable = match deserializer.next_nullable_is_null()? {
+                    true => Nullable::Null,
+                    false => Nullable::NotNull(self.next().parse(deserializer)?),
+                };
+                Ok(Value::Nullable(Box::new(nullable)))
+            }
+        }
+    }
+
+    fn transcode_elements<In, Out>(
+        &self,
+        input: &mut In,
+        output: &mut Out,
+        count: usize,
+    ) -> Result<(), TranscodeError<DecodeError, <Out as CairoWrite>::Error>>
+    where
+        In: CairoDeserializer,
+        Out: CairoWrite,
+    {
+        let element = self.next();
+        for index in 0..count {
+            with_segment(
+                input,
+                || PathSegment::Index(index),
+                |input| element.transcode(input, &mut *output),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn transcode_op<In, Out>(
+        &self,
+        input: &mut In,
+        output: &mut Out,
+    ) -> Result<(), TranscodeError<DecodeError, <Out as CairoWrite>::Error>>
+    where
+        In: CairoDeserializer,
+        Out: CairoWrite,
+    {
+        let plan = self.plan;
+        match self.op() {
+            Op::Primitive(primitive) => primitive.transcode(input, output),
+            Op::Leaf(leaf) => leaf.transcode(input, output),
+            Op::Run { primitive, size } => {
+                input
+                    .check_array_len("fixed size array", *size as usize)
+                    .map_de()?;
+                for index in 0..*size as usize {
+                    with_segment(
+                        input,
+                        || PathSegment::Index(index),
+                        |input| primitive.transcode(input, &mut *output),
+                    )?;
+                }
+                Ok(())
+            }
+            Op::Struct(index) => {
+                let struct_plan = &plan.structs[*index as usize];
+                with_segment(
+                    input,
+                    || PathSegment::Type(struct_plan.name.clone()),
+                    |input| {
+                        for member in &struct_plan.members {
+                            with_segment(
+                                input,
+                                || PathSegment::Member(member.name.clone()),
+                                |input| plan.node(member.start).transcode(input, &mut *output),
+                            )?;
+                        }
+                        Ok(())
+                    },
+                )
+            }
+            Op::Tuple(elements) => {
+                for (index, start) in elements.iter().enumerate() {
+                    with_segment(
+                        input,
+                        || PathSegment::Index(index),
+                        |input| plan.node(*start).transcode(input, &mut *output),
+                    )?;
+                }
+                Ok(())
+            }
+            Op::Array => {
+                let len = input.next_u32().map_de()?;
+                input.check_array_len("array", len as usize).map_de()?;
+                output.write_bytes(&len.to_be_bytes()).map_se()?;
+                self.transcode_elements(input, output, len as usize)
+            }
+            Op::FixedArray(size) => {
+                input
+                    .check_array_len("fixed size array", *size as usize)
+                    .map_de()?;
+                self.transcode_elements(input, output, *size as usize)
+            }
+            Op::Enum(index) => {
+                let enum_plan = &plan.enums[*index as usize];
+                with_segment(
+                    input,
+                    || PathSegment::Type(enum_plan.name.clone()),
+                    |input| {
+                        let VariantPlan { selector, field } =
+                            enum_plan.next_variant(input).map_de()?;
+                        output.write_felt(*selector).map_se()?;
+                        with_segment(
+                            input,
+                            || PathSegment::Variant(field.name.clone()),
+                            |input| plan.node(field.start).transcode(input, output),
+                        )
+                    },
+                )
+            }
+            Op::Option => {
+                let is_some = input.next_option_is_some().map_de()?;
+                output.write_byte(is_some as u8).map_se()?;
+                match is_some {
+                    true => self.next().transcode(input, output),
+                    false => Ok(()),
+                }
+            }
+            Op::Result { err, .. } => {
+                let is_ok = input.next_result_is_ok().map_de()?;
+                output.write_byte(!is_ok as u8).map_se()?;
+                match is_ok {
+                    true => self.next().transcode(input, output),
+                    false => plan.node(*err).transcode(input, output),
+                }
+            }
+            Op::Nullable => {
+                let is_null = input.next_nullable_is_null().map_de()?;
+                output.write_byte(!is_null as u8).map_se()?;
+                match is_null {
+                    true => Ok(()),
+                    false => self.next().transcode(input, output),
+                }
+            }
+        }
+    }
+
+    fn serialize_elements<C: CairoTypeSerialization, S: Serializer>(
+        &self,
+        data: &mut impl CairoDeserializer,
+        cairo_se: &C,
+        serializer: S,
+        len: usize,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let element = self.next();
+        let inner = element.to_de_se(data, cairo_se);
+        for index in 0..len {
+            with_segment(
+                data,
+                || PathSegment::Index(index),
+                |_| seq.serialize_element(&inner),
+            )?;
+        }
+        seq.end()
+    }
+
+    fn serialize_op<C: CairoTypeSerialization, S: Serializer>(
+        &self,
+        data: &mut impl CairoDeserializer,
+        cairo_se: &C,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let plan = self.plan;
+        match self.op() {
+            Op::Primitive(primitive) => primitive.serialize_cairo(data, cairo_se, serializer),
+            Op::Leaf(leaf) => leaf.serialize_cairo(data, cairo_se, serializer),
+            Op::Run { primitive, size } => {
+                data.check_array_len("fixed size array", *size as usize)
+                    .map_err(|e| located(data, e))?;
+                let mut seq = serializer.serialize_seq(Some(*size as usize))?;
+                let inner = primitive.to_de_se(data, cairo_se);
+                for index in 0..*size as usize {
+                    with_segment(
+                        data,
+                        || PathSegment::Index(index),
+                        |_| seq.serialize_element(&inner),
+                    )?;
+                }
+                seq.end()
+            }
+            Op::Struct(index) => {
+                let struct_plan = &plan.structs[*index as usize];
+                let mut map = serializer.serialize_map(Some(struct_plan.members.len()))?;
+                with_segment(
+                    data,
+                    || PathSegment::Type(struct_plan.name.clone()),
+                    |data| {
+                        for member in &struct_plan.members {
+                            with_segment(
+                                data,
+                                || PathSegment::Member(member.name.clone()),
+                                |data| {
+                                    map.serialize_entry(
+                                        &member.name,
+                                        &plan.node(member.start).to_de_se(data, cairo_se),
+                                    )
+                                },
+                            )?;
+                        }
+                        Ok(())
+                    },
+                )?;
+                map.end()
+            }
+            Op::Tuple(elements) => {
+                let elements: Vec<_> = elements.iter().map(|start| plan.node(*start)).collect();
+                cairo_se.serialize_tuple_elements(data, serializer, &elements)
+            }
+            Op::Array => {
+                let len = data.next_u32().map_err(|e| located(data, e))? as usize;
+                data.check_array_len("array", len)
+                    .map_err(|e| located(data, e))?;
+                self.serialize_elements(data, cairo_se, serializer, len)
+            }
+            Op::FixedArray(size) => {
+                data.check_array_len("fixed size array", *size as usize)
+                    .map_err(|e| located(data, e))?;
+                self.serialize_elements(data, cairo_se, serializer, *size as usize)
+            }
+            Op::Enum(index) => {
+                let enum_plan = &plan.enums[*index as usize];
+                with_segment(
+                    data,
+                    || PathSegment::Type(enum_plan.name.clone()),
+                    |data| {
+                        let selector = enum_plan
+                            .next_variant(data)
+                            .map_err(|e| located(data, e))?
+                            .selector;
+                        cairo_se.serialize_enum(data, serializer, &enum_plan.enum_def, selector)
+                    },
+                )
+            }
+            Op::Option => match data.next_option_is_some().map_err(|e| located(data, e))? {
+                true => serializer.serialize_some(&self.next().to_de_se(data, cairo_se)),
+                false => serializer.serialize_none(),
+            },
+            Op::Result { err } => {
+                let is_ok = data.next_result_is_ok().map_err(|e| located(data, e))?;
+                let (ok, err) = (self.next(), plan.node(*err));
+                cairo_se.serialize_result_value(data, serializer, &ok, &err, is_ok)
+            }
+            Op::Nullable => match data.next_nullable_is_null().map_err(|e| located(data, e))? {
+                true => serializer.serialize_none(),
+                false => serializer.serialize_some(&self.next().to_de_se(data, cairo_se)),
+            },
+        }
+    }
+}
+
+impl<D: CairoDeserializer> ParseValue<D> for PlanNode<'_> {
+    type Value = Value;
+    #[inline]
+    fn parse(&self, deserializer: &mut D) -> TypeParserResult<Value> {
+        match self.op() {
+            Op::Primitive(primitive) => primitive.parse(deserializer),
+            _ => self.parse_nested(deserializer),
+        }
+    }
+}
+
+impl<D: CairoDeserializer> ParseValue<D> for DecodePlan {
+    type Value = Value;
+    fn parse(&self, deserializer: &mut D) -> TypeParserResult<Value> {
+        self.root().parse(deserializer)
+    }
+}
+
+impl<In, Out> Transcode<In, Out> for PlanNode<'_>
+where
+    In: CairoDeserializer,
+    Out: CairoWrite,
+{
+    type SerializeError = <Out as TranscodeWriter<In>>::SerializeError;
+    type DeserializeError = DecodeError;
+
+    #[inline]
+    fn transcode(
+        &self,
+        input: &mut In,
+        output: &mut Out,
+    ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
+        match self.op() {
+            Op::Primitive(primitive) => primitive.transcode(input, output),
+            Op::Leaf(leaf) => leaf.transcode(input, output),
+            _ => {
+                input.enter_nested().map_de()?;
+                let result = self.transcode_op(input, output);
+                input.exit_nested();
+                result.map_err(|e| e.locate(input))
+            }
+        }
+    }
+}
+
+impl<In, Out> Transcode<In, Out> for DecodePlan
+where
+    In: CairoDeserializer,
+    Out: CairoWrite,
+{
+    type SerializeError = <Out as TranscodeWriter<In>>::SerializeError;
+    type DeserializeError = DecodeError;
+
+    fn transcode(
+        &self,
+        input: &mut In,
+        output: &mut Out,
+    ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
+        self.root().transcode(input, output)
+    }
+}
+
+impl<'a, C: CairoTypeSerialization> CairoSerialize<'a, C> for PlanNode<'_> {
+    fn serialize_cairo<S: Serializer>(
+        &self,
+        data: &mut impl CairoDeserializer,
+        cairo_se: &C,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self.op() {
+            Op::Primitive(primitive) => primitive.serialize_cairo(data, cairo_se, serializer),
+            Op::Leaf(leaf) => leaf.serialize_cairo(data, cairo_se, serializer),
+            _ => {
+                data.enter_nested().map_err(|e| located(data, e))?;
+                let result = self.serialize_op(data, cairo_se, serializer);
+                data.exit_nested();
+                result
+            }
+        }
+    }
+}
+
+impl<'a, C: CairoTypeSerialization> CairoSerialize<'a, C> for DecodePlan {
+    fn serialize_cairo<S: Serializer>(
+        &self,
+        data: &mut impl CairoDeserializer,
+        cairo_se: &C,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.root().serialize_cairo(data, cairo_se, serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcode::Transcode;
+    use crate::{
+        ArrayDef, CairoSerde, DecodeLimits, EnumDef, FixedArrayDef, IndexedEnums, IntoFeltSource,
+        Limited, MemberDef, NullableDef, OptionDef, RefDef, ResultDef, SliceFeltSource, StructDef,
+        TupleDef, VariantDef,
+    };
+    use std::cell::RefCell;
+
+    struct Json;
+    impl CairoTypeSerialization for Json {}
+
+    fn type_def() -> TypeDef {
+        let kind = EnumDef::new_type_def(
+            "Kind".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::from(0x20),
+                    VariantDef::new("Pair".to_string(), vec![], TypeDef::U256),
+                ),
+                (
+                    Felt::from(0x10),
+                    VariantDef::new("Empty".to_string(), vec![], TypeDef::None),
+                ),
+            ],
+        );
+        StructDef::new_type_def(
+            "Item".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("kinds".to_string(), vec![], ArrayDef::new_type_def(kind)),
+                MemberDef::new(
+                    "maybe".to_string(),
+                    vec![],
+                    OptionDef::new_type_def(TypeDef::U8),
+                ),
+                MemberDef::new(
+                    "outcome".to_string(),
+                    vec![],
+                    ResultDef::new_type_def(TypeDef::U16, TypeDef::Bool),
+                ),
+                MemberDef::new(
+                    "boxed".to_string(),
+                    vec![],
+                    NullableDef::new_type_def(TypeDef::U8),
+                ),
+                MemberDef::new(
+                    "fixed".to_string(),
+                    vec![],
+                    FixedArrayDef::new_type_def(TypeDef::U16, 2),
+                ),
+                MemberDef::new(
+                    "pair".to_string(),
+                    vec![],
+                    TupleDef::new_type_def(vec![TypeDef::Felt252, TypeDef::Utf8String]),
+                ),
+            ],
+        )
+    }
+
+    fn felts() -> Vec<Felt> {
+        [2, 0x20, 1, 2, 0x10, 0, 7, 1, 1, 1, 3, 4, 5, 9, 0, 0x6162, 2]
+            .into_iter()
+            .map(Felt::from)
+            .collect()
+    }
+
+    #[test]
+    fn matches_the_tree_walker() {
+        let type_def = type_def();
+        let plan = DecodePlan::compile(&type_def).unwrap();
+        let felts = felts();
+
+        let walked = type_def
+            .parse(&mut CairoSerde(felts.as_slice().into_source()))
+            .unwrap();
+        let planned = plan
+            .parse(&mut CairoSerde(felts.as_slice().into_source()))
+            .unwrap();
+        assert_eq!(planned, walked);
+
+        let mut walked = Vec::new();
+        let mut planned = Vec::new();
+        type_def
+            .transcode(&mut CairoSerde(felts.as_slice().into_source()), &mut walked)
+            .unwrap();
+        plan.transcode(
+            &mut CairoSerde(felts.as_slice().into_source()),
+            &mut planned,
+        )
+        .unwrap();
+        assert_eq!(planned, walked);
+
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        let walked = serde_json::to_value(type_def.to_de_se(&mut de, &Json)).unwrap();
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        let planned = serde_json::to_value(plan.to_de_se(&mut de, &Json)).unwrap();
+        assert_eq!(planned, walked);
+    }
+
+    /// Tags variants and keys tuple elements by index, so the composite hooks
+    /// show up in the output.
+    struct Tagged;
+    impl CairoTypeSerialization for Tagged {
+        fn serialize_tuple_elements<'a, S: Serializer, T: CairoSerialize<'a, Self>>(
+            &'a self,
+            data: &mut impl CairoDeserializer,
+            serializer: S,
+            elements: &'a [T],
+        ) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(elements.len()))?;
+            for (index, element) in elements.iter().enumerate() {
+                map.serialize_entry(&index.to_string(), &element.to_de_se(data, self))?;
+            }
+            map.end()
+        }
+
+        fn serialize_variant_value<'a, S: Serializer, T: CairoSerialize<'a, Self>>(
+            &'a self,
+            data: &mut impl CairoDeserializer,
+            serializer: S,
+            name: &str,
+            value: &'a T,
+        ) -> Result<S::Ok, S::Error> {
+            let mut map = serializer.serialize_map(Some(2))?;
+            map.serialize_entry("variant", name)?;
+            map.serialize_entry("value", &value.to_de_se(data, self))?;
+            map.end()
+        }
+    }
+
+    #[test]
+    fn uses_the_serialization_hooks() {
+        let type_def = type_def();
+        let plan = DecodePlan::compile(&type_def).unwrap();
+        let felts = felts();
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        let walked = serde_json::to_value(type_def.to_de_se(&mut de, &Tagged)).unwrap();
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        let planned = serde_json::to_value(plan.to_de_se(&mut de, &Tagged)).unwrap();
+        assert_eq!(planned, walked);
+        assert_eq!(planned["outcome"]["variant"], "Err");
+        assert_eq!(planned["kinds"][1]["variant"], "Empty");
+        assert!(planned["pair"]["1"].is_string());
+    }
+
+    /// Records the schema type the generic hooks are handed.
+    #[derive(Default)]
+    struct Recorded(RefCell<Vec<&'static str>>);
+    impl CairoTypeSerialization for Recorded {
+        fn serialize_tuple_elements<'a, S: Serializer, T: CairoSerialize<'a, Self>>(
+            &'a self,
+            data: &mut impl CairoDeserializer,
+            serializer: S,
+            elements: &'a [T],
+        ) -> Result<S::Ok, S::Error> {
+            self.0.borrow_mut().push(std::any::type_name::<T>());
+            let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+            for element in elements {
+                seq.serialize_element(&element.to_de_se(data, self))?;
+            }
+            seq.end()
+        }
+
+        fn serialize_variant_value<'a, S: Serializer, T: CairoSerialize<'a, Self>>(
+            &'a self,
+            data: &mut impl CairoDeserializer,
+            serializer: S,
+            name: &str,
+            value: &'a T,
+        ) -> Result<S::Ok, S::Error> {
+            self.0.borrow_mut().push(std::any::type_name::<T>());
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry(name, &value.to_de_se(data, self))?;
+            map.end()
+        }
+    }
+
+    #[test]
+    fn hands_plan_nodes_to_the_hooks() {
+        let plan = DecodePlan::compile(&type_def()).unwrap();
+        let felts = felts();
+        let recorded = Recorded::default();
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        serde_json::to_value(plan.to_de_se(&mut de, &recorded)).unwrap();
+        let schemas = recorded.0.into_inner();
+        assert_eq!(schemas.len(), 4);
+        // The tuple and result get plan nodes, the enum payloads the variant
+        // `TypeDef`s `serialize_enum` resolves.
+        assert!(schemas[0].contains("TypeDef"), "{schemas:?}");
+        assert!(schemas[1].contains("TypeDef"), "{schemas:?}");
+        assert!(schemas[2].contains("PlanNode"), "{schemas:?}");
+        assert!(schemas[3].contains("PlanNode"), "{schemas:?}");
+    }
+
+    /// Names enums in full and tags their variants, overriding only
+    /// `serialize_enum`.
+    struct QualifiedEnums;
+    impl CairoTypeSerialization for QualifiedEnums {
+        fn serialize_enum<'a, S: Serializer>(
+            &'a self,
+            data: &mut impl CairoDeserializer,
+            serializer: S,
+            enum_def: &'a EnumDef,
+            variant: Felt,
+        ) -> Result<S::Ok, S::Error> {
+            let variant = enum_def
+                .get_variant(&variant)
+                .map_err(|e| located(data, e))?;
+            let mut map = serializer.serialize_map(Some(2))?;
+            map.serialize_entry("enum", &format!("{}::{}", enum_def.name, variant.name))?;
+            map.serialize_entry("value", &variant.type_def.to_de_se(data, self))?;
+            map.end()
+        }
+    }
+
+    #[test]
+    fn routes_enums_through_serialize_enum() {
+        let type_def = type_def();
+        let plan = DecodePlan::compile(&type_def).unwrap();
+        let felts = felts();
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        let walked = serde_json::to_value(type_def.to_de_se(&mut de, &QualifiedEnums)).unwrap();
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        let planned = serde_json::to_value(plan.to_de_se(&mut de, &QualifiedEnums)).unwrap();
+        assert_eq!(planned, walked);
+        assert_eq!(planned["kinds"][0]["enum"], "Kind::Pair");
+        assert_eq!(planned["kinds"][1]["enum"], "Kind::Empty");
+    }
+
+    #[test]
+    fn checks_array_lengths_when_transcoding_and_serializing() {
+        let limits = DecodeLimits::new(4, 16, 8, 40);
+        for (type_def, felts) in [
+            (
+                ArrayDef::new_type_def(TypeDef::U16),
+                vec![Felt::from(u32::MAX)],
+            ),
+            (
+                FixedArrayDef::new_type_def(TypeDef::U16, 5),
+                vec![Felt::ONE; 5],
+            ),
+            (
+                FixedArrayDef::new_type_def(OptionDef::new_type_def(TypeDef::U8), 5),
+                vec![Felt::ONE; 10],
+            ),
+        ] {
+            let plan = DecodePlan::compile(&type_def).unwrap();
+            let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits));
+            assert!(matches!(
+                plan.transcode(&mut de, &mut Vec::<u8>::new()),
+                Err(TranscodeError::Deserialize(DecodeError::InvalidLen { .. }))
+            ));
+            let mut de = CairoSerde(Limited::new(felts.as_slice().into_source(), limits));
+            let err = serde_json::to_string(&plan.to_de_se(&mut de, &Json)).unwrap_err();
+            assert!(err.to_string().contains("array"), "{err}");
+        }
+    }
+
+    fn indexed(felts: &[Felt]) -> IndexedEnums<CairoSerde<SliceFeltSource<'_>>> {
+        IndexedEnums(CairoSerde(felts.into_source()))
+    }
+
+    #[test]
+    fn matches_walker_errors_and_indexed_enums() {
+        let type_def = type_def();
+        let plan = DecodePlan::compile(&type_def).unwrap();
+        let mut felts = felts();
+        felts[1] = Felt::from(0x30);
+        assert!(matches!(
+            plan.parse(&mut CairoSerde(felts.as_slice().into_source())),
+            Err(TypeParserError::InvalidEnumSelector { .. })
+        ));
+        felts[1] = Felt::ZERO;
+        felts[4] = Felt::ONE;
+        let planned = plan.parse(&mut indexed(&felts)).unwrap();
+        assert_eq!(planned, type_def.parse(&mut indexed(&felts)).unwrap());
+        assert!(matches!(
+            DecodePlan::compile(&RefDef::new_type_def(Felt::ONE)),
+            Err(TypeParserError::RefNotSupported)
+        ));
+    }
+}
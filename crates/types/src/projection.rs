@@ -0,0 +1,282 @@
+use crate::parser::{TypeParserError, TypeParserResult};
+use crate::path::{PathSegment, with_segment};
+use crate::skip::SkipValue;
+use crate::{
+    CairoDeserializer, CairoISerde, ColumnDef, DecodeError, FeltSource, Field, IntoFeltSource,
+    Member, ParseValue, Record, Struct, TableSchema, TypeDef, Value,
+};
+use starknet_types_core::felt::Felt;
+use std::collections::HashMap;
+
+/// Part of a value to materialize: the whole value or some members of a struct.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selection {
+    All,
+    Members(HashMap<String, Selection>),
+}
+
+/// Columns, or member paths into columns, to decode from a record. Everything
+/// else is skipped without being built.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Projection {
+    columns: HashMap<Felt, Selection>,
+}
+
+impl Selection {
+    fn select(&mut self, path: &[&str]) {
+        match self {
+            Selection::All => {}
+            _ if path.is_empty() => *self = Selection::All,
+            Selection::Members(members) => members
+                .entry(path[0].to_string())
+                .or_insert_with(|| Selection::Members(HashMap::new()))
+                .select(&path[1..]),
+        }
+    }
+}
+
+impl Projection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn columns(ids: impl IntoIterator<Item = Felt>) -> Self {
+        ids.into_iter()
+            .fold(Self::new(), |projection, id| projection.with_column(id))
+    }
+
+    pub fn with_column(mut self, id: Felt) -> Self {
+        self.columns.insert(id, Selection::All);
+        self
+    }
+
+    /// Selects a member nested in the struct value of a column, e.g.
+    /// `["position", "x"]`.
+    pub fn with_path(mut self, id: Felt, path: &[&str]) -> Self {
+        self.columns
+            .entry(id)
+            .or_insert_with(|| Selection::Members(HashMap::new()))
+            .select(path);
+        self
+    }
+
+    pub fn selection(&self, id: &Felt) -> Option<&Selection> {
+        self.columns.get(id)
+    }
+
+    pub fn contains(&self, id: &Felt) -> bool {
+        self.columns.contains_key(id)
+    }
+}
+
+pub fn parse_selected<D: CairoDeserializer>(
+    type_def: &TypeDef,
+    selection: &Selection,
+    deserializer: &mut D,
+) -> TypeParserResult<Value> {
+    let (struct_def, selected) = match (type_def, selection) {
+        (_, Selection::All) => return type_def.parse(deserializer),
+        (TypeDef::Struct(struct_def), Selection::Members(selected)) => (struct_def, selected),
+        (type_def, Selection::Members(_)) => {
+            return Err(TypeParserError::unsupported_type(type_def.item_name()));
+        }
+    };
+    with_segment(
+        deserializer,
+        || PathSegment::Type(struct_def.name.clone()),
+        |d| {
+            let mut members = Vec::with_capacity(selected.len());
+            for member in &struct_def.members {
+                match selected.get(&member.name) {
+                    Some(selection) => members.push(Member {
+                        name: member.name.clone(),
+                        attributes: member.attributes.clone(),
+                        value: with_segment(
+                            d,
+                            || PathSegment::Member(member.name.clone()),
+                            |d| parse_selected(&member.type_def, selection, d),
+                        )?,
+                    }),
+                    None => {
+                        member.skip(d)?;
+                    }
+                }
+            }
+            match members.len() == selected.len() {
+                true => Ok(Value::Struct(Struct {
+                    name: struct_def.name.clone(),
+                    attributes: struct_def.attributes.clone(),
+                    members,
+                })),
+                false => Err(TypeParserError::invariant(
+                    "projection selects members missing from the struct",
+                )),
+            }
+        },
+    )
+}
+
+fn parse_columns<'a, D: CairoDeserializer + FeltSource>(
+    columns: impl IntoIterator<Item = TypeParserResult<&'a ColumnDef>>,
+    deserializer: &mut D,
+    projection: Option<&Projection>,
+) -> TypeParserResult<Vec<Field>> {
+    let mut fields = Vec::new();
+    for column in columns {
+        let column = column?;
+        let selection = match projection {
+            Some(projection) => projection.selection(&column.id),
+            None => Some(&Selection::All),
+        };
+        let Some(selection) = selection else {
+            column.skip(deserializer)?;
+            continue;
+        };
+        fields.push(Field {
+            id: column.id,
+            name: column.name.clone(),
+            attributes: column.attributes.clone(),
+            value: with_segment(
+                deserializer,
+                || PathSegment::Member(column.name.clone()),
+                |d| parse_selected(&column.type_def, selection, d),
+            )?,
+        });
+    }
+    match deserializer.next() {
+        Err(e) if e.is_eof() => Ok(fields),
+        Ok(_) => Err(DecodeError::NotEof.into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl TableSchema {
+    /// Decodes all the columns of a row, as emitted by `InsertRecord`.
+    pub fn parse_record(&self, row: Felt, data: &[Felt]) -> TypeParserResult<Record> {
+        self.parse_record_inner(row, self.columns.iter().map(Ok), data, None)
+    }
+
+    /// Decodes the projected columns of a row emitted by `InsertRecord`.
+    pub fn parse_record_projected(
+        &self,
+        row: Felt,
+        data: &[Felt],
+        projection: &Projection,
+    ) -> TypeParserResult<Record> {
+        self.parse_record_inner(row, self.columns.iter().map(Ok), data, Some(projection))
+    }
+
+    /// Decodes the projected columns of a row emitted by `InsertFields`.
+    pub fn parse_fields_projected(
+        &self,
+        row: Felt,
+        columns: &[Felt],
+        data: &[Felt],
+        projection: &Projection,
+    ) -> TypeParserResult<Record> {
+        let columns = columns.iter().map(|id| {
+            self.get_column(id)
+                .ok_or_else(|| TypeParserError::unknown_column(*id))
+        });
+        self.parse_record_inner(row, columns, data, Some(projection))
+    }
+
+    fn parse_record_inner<'a>(
+        &self,
+        row: Felt,
+        columns: impl IntoIterator<Item = TypeParserResult<&'a ColumnDef>>,
+        data: &[Felt],
+        projection: Option<&Projection>,
+    ) -> TypeParserResult<Record> {
+        let primary = self.primary.to_primary(row)?;
+        let mut deserializer = CairoISerde(data.into_source());
+        Ok(Record {
+            table_id: self.id,
+            table_name: self.name.clone(),
+            attributes: self.attributes.clone(),
+            primary,
+            fields: parse_columns(columns, &mut deserializer, projection)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemberDef, PrimaryDef, PrimaryTypeDef, StructDef};
+
+    fn table() -> TableSchema {
+        let position = StructDef::new_type_def(
+            "Position".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("x".to_string(), vec![], TypeDef::U32),
+                MemberDef::new("y".to_string(), vec![], TypeDef::U32),
+            ],
+        );
+        TableSchema {
+            id: Felt::ONE,
+            name: "Player".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("id".to_string(), vec![], PrimaryTypeDef::Felt252),
+            columns: vec![
+                ColumnDef::new(
+                    Felt::from(1),
+                    "name".to_string(),
+                    vec![],
+                    TypeDef::ByteArray,
+                ),
+                ColumnDef::new(Felt::from(2), "position".to_string(), vec![], position),
+                ColumnDef::new(Felt::from(3), "score".to_string(), vec![], TypeDef::U64),
+            ],
+        }
+    }
+
+    fn data() -> Vec<Felt> {
+        let mut name = [0u8; 32];
+        name[0] = 3;
+        name[2] = 2;
+        name[30] = b'a';
+        name[31] = b'b';
+        vec![
+            Felt::from_bytes_be(&name),
+            Felt::from(4),
+            Felt::from(5),
+            Felt::from(6),
+        ]
+    }
+
+    #[test]
+    fn projects_columns_and_members() {
+        let table = table();
+        let full = table.parse_record(Felt::TWO, &data()).unwrap();
+        assert_eq!(full.fields.len(), 3);
+        assert_eq!(full.fields[0].value, Value::ByteArray(b"ab".to_vec()));
+
+        let projection = Projection::columns([Felt::from(3)]).with_path(Felt::from(2), &["y"]);
+        let record = table
+            .parse_record_projected(Felt::TWO, &data(), &projection)
+            .unwrap();
+        assert_eq!(record.fields.len(), 2);
+        let Value::Struct(position) = &record.fields[0].value else {
+            panic!("expected struct");
+        };
+        assert_eq!(position.members.len(), 1);
+        assert_eq!(position.members[0].value, Value::U32(5));
+        assert_eq!(record.fields[1], full.fields[2]);
+    }
+
+    #[test]
+    fn rejects_invalid_projections() {
+        let table = table();
+        let projection = Projection::new().with_path(Felt::from(3), &["value"]);
+        assert!(matches!(
+            table.parse_record_projected(Felt::TWO, &data(), &projection),
+            Err(TypeParserError::UnsupportedType(_))
+        ));
+        assert!(matches!(
+            table.parse_fields_projected(Felt::TWO, &[Felt::from(9)], &[], &projection),
+            Err(TypeParserError::UnknownColumn(_))
+        ));
+    }
+}
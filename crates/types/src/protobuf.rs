@@ -0,0 +1,843 @@
+use crate::path::{Locate, PathSegment, with_segment};
+use crate::transcode::{
+    CairoWrite, Transcode, TranscodeError, TranscodeResult, TranscodeSerializeResult,
+    TranscodeWriter,
+};
+use crate::{
+    CairoDeserializer, DecodeError, DecodeResult, EnumDef, StructDef, TableSchema, TypeDef,
+    pascal_case, proto_name,
+};
+use starknet_types_core::felt::Felt;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
+use thiserror::Error;
+
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+pub const RESERVED_FIELD_NUMBERS: RangeInclusive<u32> = 19_000..=19_999;
+pub const PRIMARY_FIELD_NUMBER: u32 = 1;
+pub const UNIT_MESSAGE: &str = "Unit";
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ProtoError {
+    #[error("{0} is not supported in protobuf messages")]
+    Unsupported(&'static str),
+
+    #[error("conflicting definitions for protobuf message {0}")]
+    ConflictingMessage(String),
+
+    #[error("field number {number} is used twice in message {message}")]
+    DuplicateFieldNumber { message: String, number: u32 },
+}
+
+pub type ProtoResult<T> = Result<T, ProtoError>;
+
+/// Field number of a table column.
+///
+/// Ids between the primary's number and the reserved range are used as is.
+/// Others, such as selectors, are folded from their low 64 bits into the
+/// numbers above the reserved range, which literal ids never take, so a folded
+/// id can't collide with a literal one.
+pub fn column_field_number(id: Felt) -> u32 {
+    let bytes = id.to_bytes_be();
+    let low = u64::from_be_bytes(bytes[24..].try_into().unwrap());
+    match u32::try_from(low) {
+        Ok(n) if id == Felt::from(n) && is_literal_field_number(n) => n,
+        _ => {
+            let first = RESERVED_FIELD_NUMBERS.end() + 1;
+            let span = (MAX_FIELD_NUMBER - first + 1) as u64;
+            first + (low % span) as u32
+        }
+    }
+}
+
+fn is_literal_field_number(n: u32) -> bool {
+    n > PRIMARY_FIELD_NUMBER && n < *RESERVED_FIELD_NUMBERS.start()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Scalar {
+    Bytes,
+    String,
+    Bool,
+    Uint32,
+    Uint64,
+    Sint32,
+    Sint64,
+}
+
+impl Scalar {
+    fn name(self) -> &'static str {
+        match self {
+            Scalar::Bytes => "bytes",
+            Scalar::String => "string",
+            Scalar::Bool => "bool",
+            Scalar::Uint32 => "uint32",
+            Scalar::Uint64 => "uint64",
+            Scalar::Sint32 => "sint32",
+            Scalar::Sint64 => "sint64",
+        }
+    }
+
+    fn wire_type(self) -> u8 {
+        match self {
+            Scalar::Bytes | Scalar::String => LEN,
+            _ => VARINT,
+        }
+    }
+}
+
+/// How a type is laid out as a protobuf field.
+enum Shape<'a> {
+    Scalar(Scalar),
+    /// Structs, enums, tuples, results and `None`, encoded as nested messages.
+    Message,
+    Repeated(&'a TypeDef),
+    Optional(&'a TypeDef),
+}
+
+/// Where a field appears. Repeated and optional types are wrapped in a message
+/// with a single `value` field anywhere but as a plain field.
+#[derive(Clone, Copy, PartialEq)]
+enum Position {
+    Plain,
+    Element,
+    Optional,
+    OneOf,
+}
+
+impl<'a> Shape<'a> {
+    fn of(type_def: &'a TypeDef) -> ProtoResult<Self> {
+        Ok(match type_def {
+            TypeDef::Felt252
+            | TypeDef::ClassHash
+            | TypeDef::ContractAddress
+            | TypeDef::StorageAddress
+            | TypeDef::StorageBaseAddress
+            | TypeDef::EthAddress
+            | TypeDef::Bytes31
+            | TypeDef::Bytes31Encoded(_)
+            | TypeDef::ByteArray
+            | TypeDef::ByteArrayEncoded(_)
+            | TypeDef::Custom(_)
+            | TypeDef::U128
+            | TypeDef::U256
+            | TypeDef::U512
+            | TypeDef::I128 => Shape::Scalar(Scalar::Bytes),
+            TypeDef::ShortUtf8 | TypeDef::Utf8String => Shape::Scalar(Scalar::String),
+            TypeDef::Bool => Shape::Scalar(Scalar::Bool),
+            TypeDef::U8 | TypeDef::U16 | TypeDef::U32 => Shape::Scalar(Scalar::Uint32),
+            TypeDef::U64 => Shape::Scalar(Scalar::Uint64),
+            TypeDef::I8 | TypeDef::I16 | TypeDef::I32 => Shape::Scalar(Scalar::Sint32),
+            TypeDef::I64 => Shape::Scalar(Scalar::Sint64),
+            TypeDef::None
+            | TypeDef::Tuple(_)
+            | TypeDef::Struct(_)
+            | TypeDef::Enum(_)
+            | TypeDef::Result(_) => Shape::Message,
+            TypeDef::Array(array) => Shape::Repeated(&array.type_def),
+            TypeDef::FixedArray(fixed) => Shape::Repeated(&fixed.type_def),
+            TypeDef::Option(option) => Shape::Optional(&option.type_def),
+            TypeDef::Nullable(nullable) => Shape::Optional(&nullable.type_def),
+            TypeDef::Felt252Dict(_) => return Err(ProtoError::Unsupported("Felt252Dict")),
+            TypeDef::Ref(_) => return Err(ProtoError::Unsupported("Ref")),
+        })
+    }
+
+    fn is_wrapped(&self, position: Position) -> bool {
+        position != Position::Plain && matches!(self, Shape::Repeated(_) | Shape::Optional(_))
+    }
+}
+
+/// Generated proto3 messages, rendered as a `.proto` file through `Display`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProtoSchema {
+    pub package: Option<String>,
+    pub messages: BTreeMap<String, String>,
+}
+
+impl Display for ProtoSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "syntax = \"proto3\";\n\n")?;
+        if let Some(package) = &self.package {
+            write!(f, "package {package};\n\n")?;
+        }
+        let messages: Vec<&str> = self.messages.values().map(String::as_str).collect();
+        writeln!(f, "{}", messages.join("\n\n"))
+    }
+}
+
+/// Builds proto3 messages from `TypeDef`s and tables into a `ProtoSchema`.
+///
+/// Struct members and enum variants are numbered from 1 in declaration order,
+/// table columns with `column_field_number`. Enums become a `oneof`, options
+/// and nullables `optional` fields and arrays `repeated` fields. Felts, wide
+/// integers and byte arrays are big endian `bytes`. `Ref`s must be expanded
+/// beforehand and `Felt252Dict`s are not supported.
+#[derive(Default)]
+pub struct ProtoGenerator {
+    schema: ProtoSchema,
+}
+
+impl ProtoGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_package(package: &str) -> Self {
+        ProtoGenerator {
+            schema: ProtoSchema {
+                package: Some(package.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn finish(self) -> ProtoSchema {
+        self.schema
+    }
+
+    /// Adds the message of a type, returning its name. Types that are not
+    /// messages are wrapped in a message `name` with a single `value` field.
+    pub fn message(&mut self, type_def: &TypeDef, name: &str) -> ProtoResult<String> {
+        let name = proto_name(name);
+        match Shape::of(type_def)? {
+            Shape::Message => self.type_name(type_def, &name),
+            _ => self.wrapper(type_def, &name),
+        }
+    }
+
+    /// Adds the message of a table: the primary as field 1 followed by the
+    /// columns.
+    pub fn table(&mut self, table: &TableSchema) -> ProtoResult<String> {
+        let name = proto_name(&pascal_case(&table.name));
+        let mut numbers = HashMap::new();
+        let mut fields = vec![self.field(
+            &(&table.primary.type_def).into(),
+            &proto_name(&table.primary.name),
+            PRIMARY_FIELD_NUMBER,
+            Position::Plain,
+            &name,
+        )?];
+        for column in &table.columns {
+            let number = column_field_number(column.id);
+            if numbers.insert(number, &column.name).is_some() {
+                return Err(ProtoError::DuplicateFieldNumber {
+                    message: name,
+                    number,
+                });
+            }
+            let context = format!("{name}{}", pascal_case(&column.name));
+            fields.push(self.field(
+                &column.type_def,
+                &proto_name(&column.name),
+                number,
+                Position::Plain,
+                &context,
+            )?);
+        }
+        self.add_message(&name, fields)?;
+        Ok(name)
+    }
+
+    fn field(
+        &mut self,
+        type_def: &TypeDef,
+        name: &str,
+        number: u32,
+        position: Position,
+        context: &str,
+    ) -> ProtoResult<String> {
+        let shape = Shape::of(type_def)?;
+        if shape.is_wrapped(position) {
+            let wrapper = self.wrapper(type_def, context)?;
+            return Ok(format!("{wrapper} {name} = {number};"));
+        }
+        Ok(match shape {
+            Shape::Repeated(element) => {
+                let element = self.element_type(element, Position::Element, context, "Item")?;
+                format!("repeated {element} {name} = {number};")
+            }
+            Shape::Optional(inner) => {
+                let inner = self.element_type(inner, Position::Optional, context, "Value")?;
+                format!("optional {inner} {name} = {number};")
+            }
+            _ => format!("{} {name} = {number};", self.type_name(type_def, context)?),
+        })
+    }
+
+    fn element_type(
+        &mut self,
+        type_def: &TypeDef,
+        position: Position,
+        context: &str,
+        suffix: &str,
+    ) -> ProtoResult<String> {
+        let context = format!("{context}{suffix}");
+        match Shape::of(type_def)?.is_wrapped(position) {
+            true => self.wrapper(type_def, &context),
+            false => self.type_name(type_def, &context),
+        }
+    }
+
+    fn wrapper(&mut self, type_def: &TypeDef, name: &str) -> ProtoResult<String> {
+        let field = self.field(type_def, "value", 1, Position::Plain, name)?;
+        self.add_message(name, vec![field])?;
+        Ok(name.to_string())
+    }
+
+    /// Name of a scalar or message type, adding the message definitions.
+    fn type_name(&mut self, type_def: &TypeDef, context: &str) -> ProtoResult<String> {
+        if let Shape::Scalar(scalar) = Shape::of(type_def)? {
+            return Ok(scalar.name().to_string());
+        }
+        match type_def {
+            TypeDef::Struct(struct_def) => self.struct_message(struct_def),
+            TypeDef::Enum(enum_def) => self.enum_message(enum_def),
+            TypeDef::Tuple(tuple) => {
+                let fields = tuple
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(i, element)| {
+                        let context = format!("{context}Item{i}");
+                        let name = format!("item_{i}");
+                        self.field(element, &name, i as u32 + 1, Position::Plain, &context)
+                    })
+                    .collect::<ProtoResult<_>>()?;
+                self.add_message(context, fields)?;
+                Ok(context.to_string())
+            }
+            TypeDef::Result(result) => {
+                let fields = vec![
+                    "oneof result {".to_string(),
+                    format!(
+                        "  {}",
+                        self.field(
+                            &result.ok,
+                            "ok",
+                            1,
+                            Position::OneOf,
+                            &format!("{context}Ok")
+                        )?
+                    ),
+                    format!(
+                        "  {}",
+                        self.field(
+                            &result.err,
+                            "err",
+                            2,
+                            Position::OneOf,
+                            &format!("{context}Err")
+                        )?
+                    ),
+                    "}".to_string(),
+                ];
+                self.add_message(context, fields)?;
+                Ok(context.to_string())
+            }
+            _ => {
+                self.add_message(UNIT_MESSAGE, vec![])?;
+                Ok(UNIT_MESSAGE.to_string())
+            }
+        }
+    }
+
+    fn struct_message(&mut self, struct_def: &StructDef) -> ProtoResult<String> {
+        let name = proto_name(&struct_def.name);
+        let fields = struct_def
+            .members
+            .iter()
+            .enumerate()
+            .map(|(i, member)| {
+                let context = format!("{name}{}", pascal_case(&member.name));
+                let field_name = proto_name(&member.name);
+                self.field(
+                    &member.type_def,
+                    &field_name,
+                    i as u32 + 1,
+                    Position::Plain,
+                    &context,
+                )
+            })
+            .collect::<ProtoResult<_>>()?;
+        self.add_message(&name, fields)?;
+        Ok(name)
+    }
+
+    fn enum_message(&mut self, enum_def: &EnumDef) -> ProtoResult<String> {
+        let name = proto_name(&enum_def.name);
+        let mut fields = vec!["oneof variant {".to_string()];
+        for (i, selector) in enum_def.order.iter().enumerate() {
+            let Some(variant) = enum_def.variants.get(selector) else {
+                continue;
+            };
+            let context = format!("{name}{}", pascal_case(&variant.name));
+            let field_name = proto_name(&variant.name);
+            let field = self.field(
+                &variant.type_def,
+                &field_name,
+                i as u32 + 1,
+                Position::OneOf,
+                &context,
+            )?;
+            fields.push(format!("  {field}"));
+        }
+        fields.push("}".to_string());
+        self.add_message(&name, fields)?;
+        Ok(name)
+    }
+
+    fn add_message(&mut self, name: &str, fields: Vec<String>) -> ProtoResult<()> {
+        let mut definition = format!("message {name} {{\n");
+        for field in fields {
+            definition.push_str(&format!("  {field}\n"));
+        }
+        definition.push('}');
+        match self.schema.messages.get(name) {
+            Some(existing) if *existing != definition => {
+                Err(ProtoError::ConflictingMessage(name.to_string()))
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.schema.messages.insert(name.to_string(), definition);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl TypeDef {
+    /// proto3 schema of the message of the type, see `ProtoGenerator::message`.
+    pub fn proto_schema(&self, name: &str) -> ProtoResult<ProtoSchema> {
+        let mut generator = ProtoGenerator::new();
+        generator.message(self, name)?;
+        Ok(generator.finish())
+    }
+}
+
+impl TableSchema {
+    /// proto3 schema of the record message of the table.
+    pub fn proto_schema(&self) -> ProtoResult<ProtoSchema> {
+        let mut generator = ProtoGenerator::new();
+        generator.table(self)?;
+        Ok(generator.finish())
+    }
+}
+
+const VARINT: u8 = 0;
+const LEN: u8 = 2;
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn write_tag(buf: &mut Vec<u8>, number: u32, wire_type: u8) {
+    write_varint(buf, ((number as u64) << 3) | wire_type as u64);
+}
+
+fn write_len(buf: &mut Vec<u8>, number: u32, bytes: &[u8]) {
+    write_tag(buf, number, LEN);
+    write_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn unsupported(what: &'static str) -> DecodeError {
+    DecodeError::message(format!("{what} is not supported in protobuf messages"))
+}
+
+/// Transcodes Cairo serialized felts into the protobuf encoding of the message
+/// `ProtoGenerator` builds for the same type or table.
+///
+/// A table record is read as its primary felt followed by the column values,
+/// as in the row and data of an `InsertRecord`. The message is buffered and
+/// written to the output in one go.
+#[derive(Clone, Copy, Debug)]
+pub enum ProtoMessage<'a> {
+    Type(&'a TypeDef),
+    Record(&'a TableSchema),
+}
+
+impl<In, Out> Transcode<In, Out> for ProtoMessage<'_>
+where
+    In: CairoDeserializer,
+    Out: CairoWrite,
+{
+    type SerializeError = <Out as TranscodeWriter<In>>::SerializeError;
+    type DeserializeError = DecodeError;
+
+    fn transcode(
+        &self,
+        input: &mut In,
+        output: &mut Out,
+    ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
+        let mut buf = Vec::new();
+        match self {
+            ProtoMessage::Type(type_def) => match Shape::of(type_def) {
+                Ok(Shape::Message) => encode_message(type_def, input, &mut buf),
+                _ => encode_field(type_def, 1, Position::Plain, input, &mut buf),
+            },
+            ProtoMessage::Record(table) => encode_record(table, input, &mut buf),
+        }
+        .map_err(|e| e.locate(input))
+        .map_de()?;
+        output.write_bytes(&buf).map_se()?;
+        Ok(())
+    }
+}
+
+fn encode_record<In: CairoDeserializer>(
+    table: &TableSchema,
+    input: &mut In,
+    buf: &mut Vec<u8>,
+) -> DecodeResult<()> {
+    let primary = TypeDef::from(&table.primary.type_def);
+    with_segment(
+        input,
+        || PathSegment::Member(table.primary.name.clone()),
+        |input| encode_field(&primary, PRIMARY_FIELD_NUMBER, Position::Plain, input, buf),
+    )?;
+    for column in &table.columns {
+        with_segment(
+            input,
+            || PathSegment::Member(column.name.clone()),
+            |input| {
+                let number = column_field_number(column.id);
+                encode_field(&column.type_def, number, Position::Plain, input, buf)
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn encode_field<In: CairoDeserializer>(
+    type_def: &TypeDef,
+    number: u32,
+    position: Position,
+    input: &mut In,
+    buf: &mut Vec<u8>,
+) -> DecodeResult<()> {
+    let shape = match Shape::of(type_def) {
+        Ok(shape) => shape,
+        Err(_) => return Err(unsupported(type_def.item_name())),
+    };
+    if shape.is_wrapped(position) {
+        let mut wrapper = Vec::new();
+        encode_field(type_def, 1, Position::Plain, input, &mut wrapper)?;
+        write_len(buf, number, &wrapper);
+        return Ok(());
+    }
+    match shape {
+        Shape::Scalar(scalar) => encode_scalar(type_def, scalar, number, input, buf),
+        Shape::Message => {
+            let mut message = Vec::new();
+            encode_message(type_def, input, &mut message)?;
+            write_len(buf, number, &message);
+            Ok(())
+        }
+        Shape::Repeated(element) => {
+            let len = match type_def {
+                TypeDef::FixedArray(fixed) => fixed.size as usize,
+                _ => input.next_u32()? as usize,
+            };
+            for index in 0..len {
+                with_segment(
+                    input,
+                    || PathSegment::Index(index),
+                    |input| encode_field(element, number, Position::Element, input, buf),
+                )?;
+            }
+            Ok(())
+        }
+        Shape::Optional(inner) => {
+            let present = match type_def {
+                TypeDef::Nullable(_) => !input.next_nullable_is_null()?,
+                _ => input.next_option_is_some()?,
+            };
+            match present {
+                true => encode_field(inner, number, Position::Optional, input, buf),
+                false => Ok(()),
+            }
+        }
+    }
+}
+
+fn encode_scalar<In: CairoDeserializer>(
+    type_def: &TypeDef,
+    scalar: Scalar,
+    number: u32,
+    input: &mut In,
+    buf: &mut Vec<u8>,
+) -> DecodeResult<()> {
+    if scalar.wire_type() == LEN {
+        let bytes = match type_def {
+            TypeDef::EthAddress => input.next_bytes::<20>()?.to_vec(),
+            TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => input.next_bytes::<31>()?.to_vec(),
+            TypeDef::ShortUtf8 => input.next_short_string()?.into_bytes(),
+            TypeDef::U128 => input.next_bytes::<16>()?.to_vec(),
+            TypeDef::I128 => input.next_i128()?.to_be_bytes().to_vec(),
+            TypeDef::U256 => input.next_u256()?.to_big_endian().to_vec(),
+            TypeDef::U512 => input.next_u512()?.to_big_endian().to_vec(),
+            TypeDef::ByteArray
+            | TypeDef::Utf8String
+            | TypeDef::ByteArrayEncoded(_)
+            | TypeDef::Custom(_) => input.next_byte_array_bytes()?,
+            _ => input.next_felt()?.to_bytes_be().to_vec(),
+        };
+        write_len(buf, number, &bytes);
+        return Ok(());
+    }
+    let value = match type_def {
+        TypeDef::Bool => input.next_bool()? as u64,
+        TypeDef::U8 => input.next_u8()? as u64,
+        TypeDef::U16 => input.next_u16()? as u64,
+        TypeDef::U32 => input.next_u32()? as u64,
+        TypeDef::U64 => input.next_u64()?,
+        TypeDef::I8 => zigzag(input.next_i8()? as i64),
+        TypeDef::I16 => zigzag(input.next_i16()? as i64),
+        TypeDef::I32 => zigzag(input.next_i32()? as i64),
+        _ => zigzag(input.next_i64()?),
+    };
+    write_tag(buf, number, VARINT);
+    write_varint(buf, value);
+    Ok(())
+}
+
+/// `sint32` and `sint64` encoding, which agree for values in the `i32` range.
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn encode_message<In: CairoDeserializer>(
+    type_def: &TypeDef,
+    input: &mut In,
+    buf: &mut Vec<u8>,
+) -> DecodeResult<()> {
+    match type_def {
+        TypeDef::Struct(struct_def) => with_segment(
+            input,
+            || PathSegment::Type(struct_def.name.clone()),
+            |input| {
+                for (i, member) in struct_def.members.iter().enumerate() {
+                    with_segment(
+                        input,
+                        || PathSegment::Member(member.name.clone()),
+                        |input| {
+                            let number = i as u32 + 1;
+                            encode_field(&member.type_def, number, Position::Plain, input, buf)
+                        },
+                    )?;
+                }
+                Ok(())
+            },
+        ),
+        TypeDef::Enum(enum_def) => with_segment(
+            input,
+            || PathSegment::Type(enum_def.name.clone()),
+            |input| {
+                let (selector, variant) = input.next_enum_variant_def(enum_def)?;
+                let number = match enum_def.order.iter().position(|s| *s == selector) {
+                    Some(index) => index as u32 + 1,
+                    None => {
+                        return Err(DecodeError::invalid_enum_selector(
+                            enum_def.name.clone(),
+                            selector,
+                        ));
+                    }
+                };
+                with_segment(
+                    input,
+                    || PathSegment::Variant(variant.name.clone()),
+                    |input| encode_field(&variant.type_def, number, Position::OneOf, input, buf),
+                )
+            },
+        ),
+        TypeDef::Tuple(tuple) => {
+            for (index, element) in tuple.elements.iter().enumerate() {
+                with_segment(
+                    input,
+                    || PathSegment::Index(index),
+                    |input| {
+                        let number = index as u32 + 1;
+                        encode_field(element, number, Position::Plain, input, buf)
+                    },
+                )?;
+            }
+            Ok(())
+        }
+        TypeDef::Result(result) => match input.next_result_is_ok()? {
+            true => encode_field(&result.ok, 1, Position::OneOf, input, buf),
+            false => encode_field(&result.err, 2, Position::OneOf, input, buf),
+        },
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArrayDef, CairoSerde, ColumnDef, IntoFeltSource, MemberDef, OptionDef, PrimaryDef,
+        PrimaryTypeDef, VariantDef,
+    };
+
+    fn direction() -> TypeDef {
+        EnumDef::new_type_def(
+            "Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::ONE,
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::TWO,
+                    VariantDef::new(
+                        "Jump".to_string(),
+                        vec![],
+                        OptionDef::new_type_def(TypeDef::U8),
+                    ),
+                ),
+            ],
+        )
+    }
+
+    fn player() -> TableSchema {
+        TableSchema {
+            id: Felt::ONE,
+            name: "player".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("owner".to_string(), vec![], PrimaryTypeDef::Felt252),
+            columns: vec![
+                ColumnDef::new(Felt::TWO, "level".to_string(), vec![], TypeDef::I8),
+                ColumnDef::new(
+                    Felt::from(7),
+                    "moves".to_string(),
+                    vec![],
+                    ArrayDef::new_type_def(direction()),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn generates_proto_messages() {
+        let proto = player().proto_schema().unwrap().to_string();
+        for expected in [
+            "syntax = \"proto3\";",
+            "message Player {\n  bytes owner = 1;\n  sint32 level = 2;\n  repeated Direction moves = 7;\n}",
+            "message Direction {\n  oneof variant {\n    Unit Up = 1;\n    DirectionJump Jump = 2;\n  }\n}",
+            "message DirectionJump {\n  optional uint32 value = 1;\n}",
+            "message Unit {\n}",
+        ] {
+            assert!(proto.contains(expected), "missing {expected:?} in\n{proto}");
+        }
+
+        let selector = column_field_number(Felt::from_hex_unchecked(
+            "0x2d4f1f2a4f0a3f6b5c2ee1e1e51e1e3d9c1e6e1b1d1f1e1a1c1b1d1e1f1a1b",
+        ));
+        assert!(selector > *RESERVED_FIELD_NUMBERS.end() && selector <= MAX_FIELD_NUMBER);
+        assert!(!RESERVED_FIELD_NUMBERS.contains(&column_field_number(Felt::from(19_500))));
+
+        let mut low_ids = player();
+        low_ids.columns = (0..=3u32)
+            .map(|id| ColumnDef::new(Felt::from(id), format!("c{id}"), vec![], TypeDef::U8))
+            .collect();
+        let numbers: Vec<u32> = (0..=3u32)
+            .map(|id| column_field_number(Felt::from(id)))
+            .collect();
+        assert_eq!(&numbers[2..], &[2, 3]);
+        assert!(
+            numbers[..2]
+                .iter()
+                .all(|n| *n > *RESERVED_FIELD_NUMBERS.end())
+        );
+        assert_ne!(numbers[0], numbers[1]);
+        assert!(low_ids.proto_schema().is_ok());
+
+        let mut clash = player();
+        clash.columns[1].id = Felt::TWO;
+        assert_eq!(
+            clash.proto_schema(),
+            Err(ProtoError::DuplicateFieldNumber {
+                message: "Player".to_string(),
+                number: 2
+            })
+        );
+        let position = StructDef::new_type_def(
+            "Position".to_string(),
+            vec![],
+            vec![MemberDef::new(
+                "cells".to_string(),
+                vec![],
+                ArrayDef::new_type_def(ArrayDef::new_type_def(TypeDef::Bool)),
+            )],
+        );
+        assert!(
+            position
+                .proto_schema("Position")
+                .unwrap()
+                .to_string()
+                .contains("message PositionCellsItem {\n  repeated bool value = 1;\n}")
+        );
+    }
+
+    #[test]
+    fn encodes_protobuf_wire_format() {
+        let felts = vec![
+            Felt::from(0x1234),
+            -Felt::ONE,
+            Felt::THREE,
+            Felt::ONE,
+            Felt::TWO,
+            Felt::ZERO,
+            Felt::from(150),
+            Felt::TWO,
+            Felt::ONE,
+        ];
+        let mut input = CairoSerde(felts.into_source());
+        let bytes: Vec<u8> = ProtoMessage::Record(&player())
+            .transcode_complete(&mut input)
+            .unwrap();
+        let mut owner = vec![0x0a, 32];
+        owner.extend(Felt::from(0x1234).to_bytes_be());
+        let expected = [
+            owner,
+            // level: sint32 -1
+            vec![0x10, 0x01],
+            // moves[0]: Up, an empty Unit
+            vec![0x3a, 2, 0x0a, 0],
+            // moves[1]: Jump(Some(150))
+            vec![0x3a, 5, 0x12, 3, 0x08, 0x96, 0x01],
+            // moves[2]: Jump(None)
+            vec![0x3a, 2, 0x12, 0],
+        ]
+        .concat();
+        assert_eq!(bytes, expected);
+
+        let felts = vec![Felt::from(300)];
+        let mut input = CairoSerde(felts.into_source());
+        let mut output = Vec::new();
+        ProtoMessage::Type(&TypeDef::U32)
+            .transcode(&mut input, &mut output)
+            .unwrap();
+        assert_eq!(output, vec![0x08, 0xac, 0x02]);
+
+        let TypeDef::Enum(mut unordered) = direction() else {
+            unreachable!()
+        };
+        unordered.order.retain(|s| *s != Felt::ONE);
+        let felts = vec![Felt::ONE];
+        let mut input = CairoSerde(felts.into_source());
+        assert!(matches!(
+            ProtoMessage::Type(&TypeDef::Enum(unordered)).transcode(&mut input, &mut Vec::new()),
+            Err(TranscodeError::Deserialize(
+                DecodeError::InvalidEnumSelector { .. }
+            ))
+        ));
+    }
+}
@@ -11,6 +11,12 @@ pub trait GetRefTypeDef {
     fn get_type_def(&self, id: Felt) -> Option<TypeDef>;
 }
 
+impl GetRefTypeDef for HashMap<Felt, TypeDef> {
+    fn get_type_def(&self, id: Felt) -> Option<TypeDef> {
+        self.get(&id).cloned()
+    }
+}
+
 pub trait DerefDefTrait<TD> {
     fn deref_def(&self, def: TD) -> Option<TD>;
 }
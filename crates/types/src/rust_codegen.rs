@@ -0,0 +1,586 @@
+use crate::{EnumDef, GetRefTypeDef, StructDef, TableSchema, TypeDef, identifier, type_name};
+use starknet_types_core::felt::Felt;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use thiserror::Error;
+
+const DERIVES: &str = "#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]";
+
+const KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+#[derive(Debug, Error, PartialEq)]
+pub enum RustCodegenError {
+    #[error("unknown type reference: {0:#x}")]
+    UnknownRef(Felt),
+
+    #[error("conflicting definitions for Rust type {0}")]
+    ConflictingType(String),
+
+    #[error("{0} has no Rust mapping")]
+    Unsupported(&'static str),
+}
+
+pub type RustCodegenResult<T> = Result<T, RustCodegenError>;
+
+/// Rust field name of a Cairo member, escaping keywords.
+pub fn rust_field_name(name: &str) -> String {
+    let name = identifier(name);
+    match KEYWORDS.contains(&name.as_str()) {
+        true => format!("r#{name}"),
+        false => name,
+    }
+}
+
+/// Generated Rust source: the imports it needs and one item per named type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RustModule {
+    pub imports: BTreeSet<(&'static str, &'static str)>,
+    pub items: BTreeMap<String, String>,
+}
+
+impl Display for RustModule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut paths: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (path, name) in &self.imports {
+            paths.entry(path).or_default().push(name);
+        }
+        for (path, names) in paths {
+            match names.as_slice() {
+                [name] => writeln!(f, "use {path}::{name};")?,
+                names => writeln!(f, "use {path}::{{{}}};", names.join(", "))?,
+            }
+        }
+        for item in self.items.values() {
+            write!(f, "\n{item}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How a value of a type is named and read in the generated code.
+struct Mapped {
+    ty: String,
+    read: String,
+    /// Whether `read` is the `CairoDeserialize` impl of `ty`, so containers
+    /// can use the deserializer helpers.
+    derived: bool,
+    /// Whether `ty` holds a type being defined without indirection, so the
+    /// member holding it must be boxed.
+    recursive: bool,
+}
+
+impl Mapped {
+    fn new(ty: &str, read: &str, derived: bool) -> Self {
+        Mapped {
+            ty: ty.to_string(),
+            read: read.to_string(),
+            derived,
+            recursive: false,
+        }
+    }
+
+    /// Boxes a member holding one of its enclosing types.
+    fn boxed(self) -> Self {
+        match self.recursive {
+            true => Mapped {
+                ty: format!("Box<{}>", self.ty),
+                read: format!("Box::new({})", self.read),
+                derived: false,
+                recursive: false,
+            },
+            false => self,
+        }
+    }
+}
+
+/// Emits Rust mirror types of `TypeDef`s and tables, for use from `build.rs`.
+///
+/// Structs and enums become items named after their definitions, deriving
+/// serde and implementing `CairoDeserialize` for any `CairoDeserializer`, so
+/// the same types decode `CairoSerde` and `CairoISerde` data. Felts and
+/// addresses map to `Felt`, `bytes31`, `ByteArray` and `EthAddress` to the
+/// introspect types, `u256`/`u512` to `primitive_types`, options and nullables
+/// to `Option`, fixed arrays to `Vec` and tuples to Rust tuples. Members that
+/// hold one of their enclosing types outside a `Vec` are boxed.
+#[derive(Default)]
+pub struct RustGenerator<'a> {
+    refs: Option<&'a dyn GetRefTypeDef>,
+    in_progress: HashSet<String>,
+    module: RustModule,
+}
+
+impl<'a> RustGenerator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_refs(refs: &'a dyn GetRefTypeDef) -> Self {
+        RustGenerator {
+            refs: Some(refs),
+            ..Default::default()
+        }
+    }
+
+    pub fn finish(self) -> RustModule {
+        self.module
+    }
+
+    /// Rust type of `type_def`, adding the items it needs to the module.
+    pub fn rust_type(&mut self, type_def: &TypeDef) -> RustCodegenResult<String> {
+        self.map(type_def).map(|mapped| mapped.ty)
+    }
+
+    /// Adds a struct named after the table, holding the primary followed by
+    /// the columns and decoding them in that order.
+    pub fn table(&mut self, table: &TableSchema) -> RustCodegenResult<String> {
+        let name = type_name(&table.name);
+        let fields = std::iter::once((&table.primary.name, TypeDef::from(&table.primary.type_def)))
+            .chain(
+                table
+                    .columns
+                    .iter()
+                    .map(|column| (&column.name, column.type_def.clone())),
+            )
+            .map(|(field, type_def)| Ok((rust_field_name(field), self.map(&type_def)?)))
+            .collect::<RustCodegenResult<Vec<_>>>()?;
+        self.add_struct(&name, &fields)?;
+        Ok(name)
+    }
+
+    fn map(&mut self, type_def: &TypeDef) -> RustCodegenResult<Mapped> {
+        Ok(match type_def {
+            TypeDef::None => Mapped::new("()", "()", false),
+            TypeDef::Felt252
+            | TypeDef::ClassHash
+            | TypeDef::ContractAddress
+            | TypeDef::StorageAddress
+            | TypeDef::StorageBaseAddress => {
+                self.import("starknet_types_core::felt", "Felt");
+                Mapped::new("Felt", "deserializer.next_felt()?", true)
+            }
+            TypeDef::EthAddress => {
+                self.import("introspect_types", "EthAddress");
+                Mapped::new("EthAddress", "deserializer.next_eth_address()?", true)
+            }
+            TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => {
+                self.import("introspect_types", "Bytes31");
+                Mapped::new("Bytes31", "deserializer.next_bytes31()?", true)
+            }
+            TypeDef::ShortUtf8 => Mapped::new("String", "deserializer.next_short_string()?", false),
+            TypeDef::Utf8String => Mapped::new("String", "deserializer.next_string()?", true),
+            TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) => {
+                self.import("introspect_types", "ByteArray");
+                Mapped::new("ByteArray", "deserializer.next_byte_array()?", true)
+            }
+            TypeDef::Bool => Mapped::new("bool", "deserializer.next_bool()?", true),
+            TypeDef::U8 => Mapped::new("u8", "deserializer.next_u8()?", true),
+            TypeDef::U16 => Mapped::new("u16", "deserializer.next_u16()?", true),
+            TypeDef::U32 => Mapped::new("u32", "deserializer.next_u32()?", true),
+            TypeDef::U64 => Mapped::new("u64", "deserializer.next_u64()?", true),
+            TypeDef::U128 => Mapped::new("u128", "deserializer.next_u128()?", true),
+            TypeDef::I8 => Mapped::new("i8", "deserializer.next_i8()?", true),
+            TypeDef::I16 => Mapped::new("i16", "deserializer.next_i16()?", true),
+            TypeDef::I32 => Mapped::new("i32", "deserializer.next_i32()?", true),
+            TypeDef::I64 => Mapped::new("i64", "deserializer.next_i64()?", true),
+            TypeDef::I128 => Mapped::new("i128", "deserializer.next_i128()?", true),
+            TypeDef::U256 => {
+                self.import("primitive_types", "U256");
+                Mapped::new("U256", "deserializer.next_u256()?", true)
+            }
+            TypeDef::U512 => {
+                self.import("primitive_types", "U512");
+                Mapped::new("U512", "deserializer.next_u512()?", true)
+            }
+            TypeDef::Custom(_) => {
+                self.import("starknet_types_core::felt", "Felt");
+                Mapped::new("Vec<Felt>", "deserializer.next_array()?", true)
+            }
+            TypeDef::Array(array) => {
+                let item = self.map(&array.type_def)?;
+                let read = match item.derived {
+                    true => "deserializer.next_array()?".to_string(),
+                    false => read_items("deserializer.next_u32()? as usize", &item.read),
+                };
+                Mapped {
+                    ty: format!("Vec<{}>", item.ty),
+                    read,
+                    derived: item.derived,
+                    recursive: false,
+                }
+            }
+            TypeDef::FixedArray(fixed) => {
+                let item = self.map(&fixed.type_def)?;
+                let read = match item.derived {
+                    true => format!("deserializer.next_fixed_size_array({})?", fixed.size),
+                    false => read_items(&fixed.size.to_string(), &item.read),
+                };
+                Mapped {
+                    ty: format!("Vec<{}>", item.ty),
+                    read,
+                    derived: false,
+                    recursive: false,
+                }
+            }
+            TypeDef::Tuple(tuple) => {
+                let elements = tuple
+                    .elements
+                    .iter()
+                    .map(|element| self.map(element))
+                    .collect::<RustCodegenResult<Vec<_>>>()?;
+                let join = |f: fn(&Mapped) -> &str| match elements.as_slice() {
+                    [element] => format!("({},)", f(element)),
+                    elements => format!(
+                        "({})",
+                        elements.iter().map(f).collect::<Vec<_>>().join(", ")
+                    ),
+                };
+                Mapped {
+                    ty: join(|m| m.ty.as_str()),
+                    read: join(|m| m.read.as_str()),
+                    derived: false,
+                    recursive: elements.iter().any(|m| m.recursive),
+                }
+            }
+            TypeDef::Option(option) => {
+                let inner = self.map(&option.type_def)?;
+                let read = match inner.derived {
+                    true => "deserializer.next_option()?".to_string(),
+                    false => format!(
+                        "match deserializer.next_option_is_some()? {{ true => Some({}), false => None }}",
+                        inner.read
+                    ),
+                };
+                Mapped {
+                    recursive: inner.recursive,
+                    ..Mapped::new(&format!("Option<{}>", inner.ty), &read, false)
+                }
+            }
+            TypeDef::Nullable(nullable) => {
+                let inner = self.map(&nullable.type_def)?;
+                let read = match inner.derived {
+                    true => "deserializer.next_nullable()?".to_string(),
+                    false => format!(
+                        "match deserializer.next_nullable_is_null()? {{ true => None, false => Some({}) }}",
+                        inner.read
+                    ),
+                };
+                Mapped {
+                    recursive: inner.recursive,
+                    ..Mapped::new(&format!("Option<{}>", inner.ty), &read, false)
+                }
+            }
+            TypeDef::Result(result) => {
+                let (ok, err) = (self.map(&result.ok)?, self.map(&result.err)?);
+                let read = match ok.derived && err.derived {
+                    true => "deserializer.next_result()?".to_string(),
+                    false => format!(
+                        "match deserializer.next_result_is_ok()? {{ true => Ok({}), false => Err({}) }}",
+                        ok.read, err.read
+                    ),
+                };
+                Mapped {
+                    recursive: ok.recursive || err.recursive,
+                    ..Mapped::new(&format!("Result<{}, {}>", ok.ty, err.ty), &read, false)
+                }
+            }
+            TypeDef::Struct(struct_def) => self.struct_type(struct_def)?,
+            TypeDef::Enum(enum_def) => self.enum_type(enum_def)?,
+            TypeDef::Ref(reference) => {
+                let type_def = self
+                    .refs
+                    .and_then(|refs| refs.get_type_def(reference.id))
+                    .ok_or(RustCodegenError::UnknownRef(reference.id))?;
+                self.map(&type_def)?
+            }
+            TypeDef::Felt252Dict(_) => return Err(RustCodegenError::Unsupported("Felt252Dict")),
+        })
+    }
+
+    fn struct_type(&mut self, struct_def: &StructDef) -> RustCodegenResult<Mapped> {
+        let name = type_name(&struct_def.name);
+        let mapped = Mapped::new(&name, &format!("{name}::deserialize(deserializer)?"), true);
+        if !self.in_progress.insert(name.clone()) {
+            return Ok(Mapped {
+                recursive: true,
+                ..mapped
+            });
+        }
+        let fields = struct_def
+            .members
+            .iter()
+            .map(|member| {
+                let mapped = self.map(&member.type_def)?.boxed();
+                Ok((rust_field_name(&member.name), mapped))
+            })
+            .collect::<RustCodegenResult<Vec<_>>>();
+        self.in_progress.remove(&name);
+        self.add_struct(&name, &fields?)?;
+        Ok(mapped)
+    }
+
+    fn enum_type(&mut self, enum_def: &EnumDef) -> RustCodegenResult<Mapped> {
+        let name = type_name(&enum_def.name);
+        let mapped = Mapped::new(&name, &format!("{name}::deserialize(deserializer)?"), true);
+        if !self.in_progress.insert(name.clone()) {
+            return Ok(Mapped {
+                recursive: true,
+                ..mapped
+            });
+        }
+        let variants = enum_def
+            .order
+            .iter()
+            .filter_map(|selector| Some((selector, enum_def.variants.get(selector)?)))
+            .map(|(selector, variant)| {
+                let payload = match variant.type_def {
+                    TypeDef::None => None,
+                    ref type_def => Some(self.map(type_def)?.boxed()),
+                };
+                Ok((*selector, type_name(&variant.name), payload))
+            })
+            .collect::<RustCodegenResult<Vec<_>>>();
+        self.in_progress.remove(&name);
+        let variants = variants?;
+
+        self.import("starknet_types_core::felt", "Felt");
+        self.import("introspect_types", "DecodeError");
+        self.import("introspect_types", "EnumVariantEncoding");
+        let definitions: String = variants
+            .iter()
+            .map(|(_, variant, payload)| match payload {
+                Some(payload) => format!("    {variant}({}),\n", payload.ty),
+                None => format!("    {variant},\n"),
+            })
+            .collect();
+        let selectors: Vec<String> = variants
+            .iter()
+            .map(|(selector, _, _)| format!("Felt::from_hex_unchecked(\"{selector:#x}\")"))
+            .collect();
+        let arms: String = variants
+            .iter()
+            .enumerate()
+            .map(|(index, (_, variant, payload))| match payload {
+                Some(payload) => format!(
+                    "            Some({index}) => Ok({name}::{variant}({})),\n",
+                    payload.read
+                ),
+                None => format!("            Some({index}) => Ok({name}::{variant}),\n"),
+            })
+            .collect();
+        let item = format!(
+            "{DERIVES}\npub enum {name} {{\n{definitions}}}\n\n\
+             impl {name} {{\n    pub const SELECTORS: [Felt; {count}] = [{selectors}];\n}}\n\n\
+             impl<D: CairoDeserializer> CairoDeserialize<D> for {name} {{\n\
+             \x20   fn deserialize(deserializer: &mut D) -> DecodeResult<Self> {{\n\
+             \x20       let variant = deserializer.next_enum_variant()?;\n\
+             \x20       let encoding = deserializer.enum_variant_encoding();\n\
+             \x20       let index = match encoding {{\n\
+             \x20           EnumVariantEncoding::Selector => Self::SELECTORS.iter().position(|selector| *selector == variant),\n\
+             \x20           EnumVariantEncoding::Index => u64::try_from(variant).ok().map(|index| index as usize),\n\
+             \x20       }};\n\
+             \x20       match index {{\n{arms}\
+             \x20           _ => Err(match encoding {{\n\
+             \x20               EnumVariantEncoding::Selector => DecodeError::invalid_enum_selector(\"{name}\", variant),\n\
+             \x20               EnumVariantEncoding::Index => DecodeError::enum_index_out_of_range(\"{name}\", variant, {count}),\n\
+             \x20           }}),\n\
+             \x20       }}\n\
+             \x20   }}\n\
+             }}\n",
+            count = selectors.len(),
+            selectors = selectors.join(", "),
+        );
+        self.add_item(&name, item)?;
+        Ok(mapped)
+    }
+
+    fn add_struct(&mut self, name: &str, fields: &[(String, Mapped)]) -> RustCodegenResult<()> {
+        let definitions: String = fields
+            .iter()
+            .map(|(field, mapped)| format!("    pub {field}: {},\n", mapped.ty))
+            .collect();
+        let reads: String = fields
+            .iter()
+            .map(|(field, mapped)| format!("            {field}: {},\n", mapped.read))
+            .collect();
+        let item = format!(
+            "{DERIVES}\npub struct {name} {{\n{definitions}}}\n\n\
+             impl<D: CairoDeserializer> CairoDeserialize<D> for {name} {{\n\
+             \x20   fn deserialize(deserializer: &mut D) -> DecodeResult<Self> {{\n\
+             \x20       Ok({name} {{\n{reads}\
+             \x20       }})\n\
+             \x20   }}\n\
+             }}\n"
+        );
+        self.add_item(name, item)
+    }
+
+    fn add_item(&mut self, name: &str, item: String) -> RustCodegenResult<()> {
+        self.import("introspect_types", "CairoDeserialize");
+        self.import("introspect_types", "CairoDeserializer");
+        self.import("introspect_types", "DecodeResult");
+        match self.module.items.get(name) {
+            Some(existing) if *existing != item => {
+                Err(RustCodegenError::ConflictingType(name.to_string()))
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.module.items.insert(name.to_string(), item);
+                Ok(())
+            }
+        }
+    }
+
+    fn import(&mut self, path: &'static str, name: &'static str) {
+        self.module.imports.insert((path, name));
+    }
+}
+
+/// Reads `len` items one by one, for items without a `CairoDeserialize` impl.
+fn read_items(len: &str, read: &str) -> String {
+    format!(
+        "{{ let len = {len}; deserializer.check_array_len(\"array\", len)?; \
+         (0..len).map(|_| Ok({read})).collect::<DecodeResult<Vec<_>>>()? }}"
+    )
+}
+
+impl TypeDef {
+    /// Rust items mirroring the type, see `RustGenerator`.
+    pub fn rust_module(&self) -> RustCodegenResult<RustModule> {
+        let mut generator = RustGenerator::new();
+        generator.rust_type(self)?;
+        Ok(generator.finish())
+    }
+}
+
+impl TableSchema {
+    /// Rust struct mirroring a record of the table, see `RustGenerator::table`.
+    pub fn rust_module(&self) -> RustCodegenResult<RustModule> {
+        let mut generator = RustGenerator::new();
+        generator.table(self)?;
+        Ok(generator.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArrayDef, ColumnDef, MemberDef, OptionDef, PrimaryDef, PrimaryTypeDef, RefDef, StructDef,
+        TupleDef, VariantDef,
+    };
+    use std::collections::HashMap;
+
+    fn direction() -> TypeDef {
+        EnumDef::new_type_def(
+            "game::Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::from(0x10),
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::from(0x20),
+                    VariantDef::new("jump".to_string(), vec![], TypeDef::U8),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn generates_structs_and_enums() {
+        let position = StructDef::new_type_def(
+            "game::Position".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("type".to_string(), vec![], TypeDef::U256),
+                MemberDef::new(
+                    "moves".to_string(),
+                    vec![],
+                    ArrayDef::new_type_def(OptionDef::new_type_def(TupleDef::new_type_def(vec![
+                        TypeDef::Bool,
+                        TypeDef::ShortUtf8,
+                    ]))),
+                ),
+                MemberDef::new("facing".to_string(), vec![], direction()),
+            ],
+        );
+        let module = position.rust_module().unwrap();
+        let source = module.to_string();
+        assert!(source.starts_with(
+            "use introspect_types::{CairoDeserialize, CairoDeserializer, DecodeError, \
+             DecodeResult, EnumVariantEncoding};\nuse primitive_types::U256;\n\
+             use starknet_types_core::felt::Felt;\n"
+        ));
+        assert!(source.contains(
+            "pub struct Position {\n    pub r#type: U256,\n    \
+             pub moves: Vec<Option<(bool, String)>>,\n    pub facing: Direction,\n}"
+        ));
+        assert!(source.contains("            r#type: deserializer.next_u256()?,\n"));
+        assert!(source.contains(
+            "(0..len).map(|_| Ok(match deserializer.next_option_is_some()? { \
+             true => Some((deserializer.next_bool()?, deserializer.next_short_string()?)), \
+             false => None }))"
+        ));
+        assert!(source.contains("pub enum Direction {\n    Up,\n    Jump(u8),\n}"));
+        assert!(source.contains(
+            "pub const SELECTORS: [Felt; 2] = \
+             [Felt::from_hex_unchecked(\"0x10\"), Felt::from_hex_unchecked(\"0x20\")];"
+        ));
+        assert!(
+            source
+                .contains("            Some(1) => Ok(Direction::Jump(deserializer.next_u8()?)),\n")
+        );
+    }
+
+    #[test]
+    fn generates_tables_and_resolves_refs() {
+        let refs = HashMap::from([(Felt::from(7), direction())]);
+        let table = TableSchema {
+            id: Felt::ONE,
+            name: "player_state".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("owner".to_string(), vec![], PrimaryTypeDef::ContractAddress),
+            columns: vec![
+                ColumnDef::new(
+                    Felt::ONE,
+                    "last".to_string(),
+                    vec![],
+                    ArrayDef::new_type_def(RefDef::new_type_def(Felt::from(7))),
+                ),
+                ColumnDef::new(Felt::TWO, "name".to_string(), vec![], TypeDef::ByteArray),
+            ],
+        };
+        let mut generator = RustGenerator::with_refs(&refs);
+        assert_eq!(generator.table(&table).unwrap(), "PlayerState");
+        let module = generator.finish();
+        assert_eq!(
+            module.items.keys().collect::<Vec<_>>(),
+            vec!["Direction", "PlayerState"]
+        );
+        assert!(module.items["PlayerState"].contains(
+            "    pub owner: Felt,\n    pub last: Vec<Direction>,\n    pub name: ByteArray,\n"
+        ));
+        assert!(
+            module.items["PlayerState"].contains("            last: deserializer.next_array()?,\n")
+        );
+
+        assert_eq!(
+            table.rust_module(),
+            Err(RustCodegenError::UnknownRef(Felt::from(7)))
+        );
+        let conflicting = TupleDef::new_type_def(vec![
+            direction(),
+            EnumDef::new_type_def("Direction".to_string(), vec![], vec![]),
+        ]);
+        assert_eq!(
+            conflicting.rust_module(),
+            Err(RustCodegenError::ConflictingType("Direction".to_string()))
+        );
+    }
+}
@@ -252,6 +252,12 @@ impl PrimaryDef {
     }
 }
 
+impl TableSchema {
+    pub fn get_column(&self, id: &Felt) -> Option<&ColumnDef> {
+        self.columns.iter().find(|column| column.id == *id)
+    }
+}
+
 impl ColumnDef {
     pub fn new(id: Felt, name: String, attributes: Vec<Attribute>, type_def: TypeDef) -> Self {
         ColumnDef {
@@ -1,5 +1,7 @@
-use crate::deserialize::CairoDeserializer;
+use crate::deserialize::{CairoDeserialize, CairoDeserializeRemaining, CairoDeserializer};
 use crate::felt::IntoFeltSource;
+use crate::limits::DecodeBudget;
+use crate::path::DecodePath;
 use crate::{DecodeResult, FeltSource};
 use starknet_types_core::felt::Felt;
 
@@ -28,6 +30,24 @@ impl<I: FeltSource> CairoDeserializer for CairoSerde<I> {
     fn next_felt(&mut self) -> DecodeResult<Felt> {
         self.0.next()
     }
+
+    fn decode_budget(&mut self) -> Option<&mut DecodeBudget> {
+        self.0.budget()
+    }
+
+    fn decode_path(&mut self) -> Option<&mut DecodePath> {
+        self.0.path()
+    }
+
+    fn source_position(&self) -> Option<usize> {
+        Some(self.0.position())
+    }
+}
+
+impl<F: FeltSource> CairoDeserializeRemaining for CairoSerde<F> {
+    fn deserialize_remaining<T: CairoDeserialize<Self>>(&mut self) -> DecodeResult<Vec<T>> {
+        self.next_array()
+    }
 }
 
 impl<I: FeltSource> FeltSource for CairoSerde<I> {
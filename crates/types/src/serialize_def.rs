@@ -1,10 +1,18 @@
+use crate::path::{Locate, PathSegment, with_segment};
 use crate::serialize::{CairoSeFrom, ToCairoDeSeFrom};
-use crate::{CairoDeserializer, EnumDef, PrimaryTypeDef, ResultDef, TupleDef, TypeDef, VariantDef};
+use crate::{
+    CairoDeserializer, DecodeError, EnumDef, PrimaryTypeDef, ResultDef, TupleDef, TypeDef,
+    VariantDef,
+};
 use primitive_types::{U256, U512};
 use serde::ser::{Error as SerError, SerializeMap, SerializeSeq, SerializeTuple};
 use serde::{Serialize, Serializer};
 use starknet_types_core::felt::Felt;
 
+pub(crate) fn located<E: SerError>(data: &mut impl CairoDeserializer, err: DecodeError) -> E {
+    E::custom(err.locate(data))
+}
+
 pub trait CairoTypeSerialization: Sized {
     fn serialize_byte_array<S: Serializer>(
         &self,
@@ -46,9 +54,25 @@ pub trait CairoTypeSerialization: Sized {
         serializer: S,
         tuple: &'a TupleDef,
     ) -> Result<S::Ok, S::Error> {
-        let mut seq = serializer.serialize_tuple(tuple.elements.len())?;
-        for element in &tuple.elements {
-            seq.serialize_element(&element.to_de_se(data, self))?;
+        self.serialize_tuple_elements(data, serializer, &tuple.elements)
+    }
+
+    /// `serialize_tuple` over any `CairoSerialize` schema, the hook `DecodePlan`s
+    /// call with their plan nodes. Override it rather than `serialize_tuple` to
+    /// change both paths.
+    fn serialize_tuple_elements<'a, S: Serializer, T: CairoSerialize<'a, Self>>(
+        &'a self,
+        data: &mut impl CairoDeserializer,
+        serializer: S,
+        elements: &'a [T],
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_tuple(elements.len())?;
+        for (index, element) in elements.iter().enumerate() {
+            with_segment(
+                data,
+                || PathSegment::Index(index),
+                |data| seq.serialize_element(&element.to_de_se(data, self)),
+            )?;
         }
         seq.end()
     }
@@ -60,8 +84,9 @@ pub trait CairoTypeSerialization: Sized {
         enum_def: &'a EnumDef,
         variant: Felt,
     ) -> Result<S::Ok, S::Error> {
-        let VariantDef { name, type_def, .. } =
-            enum_def.get_variant(&variant).map_err(S::Error::custom)?;
+        let VariantDef { name, type_def, .. } = enum_def
+            .get_variant(&variant)
+            .map_err(|e| located(data, e))?;
         self.serialize_variant(data, serializer, name, type_def)
     }
 
@@ -71,9 +96,25 @@ pub trait CairoTypeSerialization: Sized {
         serializer: S,
         name: &str,
         type_def: &'a TypeDef,
+    ) -> Result<S::Ok, S::Error> {
+        self.serialize_variant_value(data, serializer, name, type_def)
+    }
+
+    /// `serialize_variant` over any `CairoSerialize` schema, see
+    /// `serialize_tuple_elements`.
+    fn serialize_variant_value<'a, S: Serializer, T: CairoSerialize<'a, Self>>(
+        &'a self,
+        data: &mut impl CairoDeserializer,
+        serializer: S,
+        name: &str,
+        value: &'a T,
     ) -> Result<S::Ok, S::Error> {
         let mut map = serializer.serialize_map(Some(1))?;
-        map.serialize_entry(&name, &type_def.to_de_se(data, self))?;
+        with_segment(
+            data,
+            || PathSegment::Variant(name.to_string()),
+            |data| map.serialize_entry(&name, &value.to_de_se(data, self)),
+        )?;
         map.end()
     }
 
@@ -83,10 +124,23 @@ pub trait CairoTypeSerialization: Sized {
         serializer: S,
         result: &'a ResultDef,
         is_ok: bool,
+    ) -> Result<S::Ok, S::Error> {
+        self.serialize_result_value(data, serializer, &result.ok, &result.err, is_ok)
+    }
+
+    /// `serialize_result` over any `CairoSerialize` schema, see
+    /// `serialize_tuple_elements`.
+    fn serialize_result_value<'a, S: Serializer, T: CairoSerialize<'a, Self>>(
+        &'a self,
+        data: &mut impl CairoDeserializer,
+        serializer: S,
+        ok: &'a T,
+        err: &'a T,
+        is_ok: bool,
     ) -> Result<S::Ok, S::Error> {
         match is_ok {
-            true => self.serialize_variant(data, serializer, "Ok", &result.ok),
-            false => self.serialize_variant(data, serializer, "Err", &result.err),
+            true => self.serialize_variant_value(data, serializer, "Ok", ok),
+            false => self.serialize_variant_value(data, serializer, "Err", err),
         }
     }
 }
@@ -114,144 +168,186 @@ impl<'a, C: CairoTypeSerialization> CairoSerialize<'a, C> for TypeDef {
         cairo_se: &C,
         serializer: S,
     ) -> Result<S::Ok, S::Error> {
-        match self {
-            TypeDef::None => serializer.serialize_unit(),
-            TypeDef::Felt252
-            | TypeDef::ClassHash
-            | TypeDef::ContractAddress
-            | TypeDef::StorageAddress
-            | TypeDef::StorageBaseAddress => {
-                let value = data.next_felt_bytes().map_err(S::Error::custom)?;
-                cairo_se.serialize_felt(serializer, &value)
-            }
-            TypeDef::ShortUtf8 => {
-                let value = data.next_short_string().map_err(S::Error::custom)?;
-                cairo_se.serialize_string(serializer, &value)
-            }
-            TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => {
-                let value = data.next_bytes::<31>().map_err(S::Error::custom)?;
-                serializer.serialize_bytes(&value)
-            }
-            TypeDef::Bool => {
-                let value = data.next_bool().map_err(S::Error::custom)?;
-                serializer.serialize_bool(value)
-            }
-            TypeDef::U8 => {
-                let value = data.next_u8().map_err(S::Error::custom)?;
-                serializer.serialize_u8(value)
-            }
-            TypeDef::U16 => {
-                let value = data.next_u16().map_err(S::Error::custom)?;
-                serializer.serialize_u16(value)
-            }
-            TypeDef::U32 => {
-                let value = data.next_u32().map_err(S::Error::custom)?;
-                serializer.serialize_u32(value)
-            }
-            TypeDef::U64 => {
-                let value = data.next_u64().map_err(S::Error::custom)?;
-                serializer.serialize_u64(value)
-            }
-            TypeDef::U128 => {
-                let value = data.next_u128().map_err(S::Error::custom)?;
-                serializer.serialize_u128(value)
-            }
-            TypeDef::U256 => {
-                let value = data.next_u256().map_err(S::Error::custom)?;
-                cairo_se.serialize_u256(serializer, value)
-            }
-            TypeDef::U512 => {
-                let value = data.next_u512().map_err(S::Error::custom)?;
-                cairo_se.serialize_u512(serializer, value)
-            }
-            TypeDef::I8 => {
-                let value = data.next_i8().map_err(S::Error::custom)?;
-                serializer.serialize_i8(value)
-            }
-            TypeDef::I16 => {
-                let value = data.next_i16().map_err(S::Error::custom)?;
-                serializer.serialize_i16(value)
-            }
-            TypeDef::I32 => {
-                let value = data.next_i32().map_err(S::Error::custom)?;
-                serializer.serialize_i32(value)
-            }
-            TypeDef::I64 => {
-                let value = data.next_i64().map_err(S::Error::custom)?;
-                serializer.serialize_i64(value)
-            }
-            TypeDef::I128 => {
-                let value = data.next_i128().map_err(S::Error::custom)?;
-                serializer.serialize_i128(value)
-            }
-            TypeDef::EthAddress => {
-                let value = data.next_eth_address().map_err(S::Error::custom)?;
-                cairo_se.serialize_eth_address(serializer, &value.0)
-            }
-            TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) | TypeDef::Custom(_) => {
-                let value = data.next_byte_array_bytes().map_err(S::Error::custom)?;
-                cairo_se.serialize_byte_array(serializer, &value)
-            }
-            TypeDef::Utf8String => {
-                let value = data.next_string().map_err(S::Error::custom)?;
-                cairo_se.serialize_string(serializer, &value)
-            }
-            TypeDef::Tuple(tuple) => cairo_se.serialize_tuple(data, serializer, tuple),
-            TypeDef::Array(array_def) => {
-                let len = data.next_u32().map_err(S::Error::custom)? as usize;
-                let mut seq = serializer.serialize_seq(Some(len))?;
-                let inner = array_def.type_def.to_de_se(data, cairo_se);
-                for _ in 0..len {
-                    seq.serialize_element(&inner)?;
-                }
-                seq.end()
-            }
-            TypeDef::FixedArray(fixed_array_def) => {
-                let len = fixed_array_def.size as usize;
-                let mut seq = serializer.serialize_seq(Some(len))?;
-                let inner = fixed_array_def.type_def.to_de_se(data, cairo_se);
-                for _ in 0..len {
-                    seq.serialize_element(&inner)?;
-                }
-                seq.end()
-            }
-            TypeDef::Felt252Dict(_) => Err(S::Error::custom(
-                "Felt252Dict transcoding is not implemented",
-            )),
-            TypeDef::Struct(struct_def) => {
-                let mut map = serializer.serialize_map(Some(struct_def.members.len()))?;
-                for member in &struct_def.members {
-                    map.serialize_entry(&member.name, &member.type_def.to_de_se(data, cairo_se))?;
-                }
-                map.end()
-            }
-            TypeDef::Enum(enum_def) => {
-                let selector = data.next_enum_variant().map_err(S::Error::custom)?;
-                cairo_se.serialize_enum(data, serializer, enum_def, selector)
-            }
-            TypeDef::Ref(_) => Err(S::Error::custom(
-                "TypeDef Ref needs to be expanded before serializing",
-            )),
-            TypeDef::Option(option_def) => {
-                match data.next_option_is_some().map_err(S::Error::custom)? {
-                    true => {
-                        serializer.serialize_some(&option_def.type_def.to_de_se(data, cairo_se))
+        data.enter_nested().map_err(|e| located(data, e))?;
+        let result = serialize_type_def(self, data, cairo_se, serializer);
+        data.exit_nested();
+        result
+    }
+}
+
+fn serialize_type_def<C: CairoTypeSerialization, S: Serializer>(
+    type_def: &TypeDef,
+    data: &mut impl CairoDeserializer,
+    cairo_se: &C,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match type_def {
+        TypeDef::None => serializer.serialize_unit(),
+        TypeDef::Felt252
+        | TypeDef::ClassHash
+        | TypeDef::ContractAddress
+        | TypeDef::StorageAddress
+        | TypeDef::StorageBaseAddress => {
+            let value = data.next_felt_bytes().map_err(|e| located(data, e))?;
+            cairo_se.serialize_felt(serializer, &value)
+        }
+        TypeDef::ShortUtf8 => {
+            let value = data.next_short_string().map_err(|e| located(data, e))?;
+            cairo_se.serialize_string(serializer, &value)
+        }
+        TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => {
+            let value = data.next_bytes::<31>().map_err(|e| located(data, e))?;
+            serializer.serialize_bytes(&value)
+        }
+        TypeDef::Bool => {
+            let value = data.next_bool().map_err(|e| located(data, e))?;
+            serializer.serialize_bool(value)
+        }
+        TypeDef::U8 => {
+            let value = data.next_u8().map_err(|e| located(data, e))?;
+            serializer.serialize_u8(value)
+        }
+        TypeDef::U16 => {
+            let value = data.next_u16().map_err(|e| located(data, e))?;
+            serializer.serialize_u16(value)
+        }
+        TypeDef::U32 => {
+            let value = data.next_u32().map_err(|e| located(data, e))?;
+            serializer.serialize_u32(value)
+        }
+        TypeDef::U64 => {
+            let value = data.next_u64().map_err(|e| located(data, e))?;
+            serializer.serialize_u64(value)
+        }
+        TypeDef::U128 => {
+            let value = data.next_u128().map_err(|e| located(data, e))?;
+            serializer.serialize_u128(value)
+        }
+        TypeDef::U256 => {
+            let value = data.next_u256().map_err(|e| located(data, e))?;
+            cairo_se.serialize_u256(serializer, value)
+        }
+        TypeDef::U512 => {
+            let value = data.next_u512().map_err(|e| located(data, e))?;
+            cairo_se.serialize_u512(serializer, value)
+        }
+        TypeDef::I8 => {
+            let value = data.next_i8().map_err(|e| located(data, e))?;
+            serializer.serialize_i8(value)
+        }
+        TypeDef::I16 => {
+            let value = data.next_i16().map_err(|e| located(data, e))?;
+            serializer.serialize_i16(value)
+        }
+        TypeDef::I32 => {
+            let value = data.next_i32().map_err(|e| located(data, e))?;
+            serializer.serialize_i32(value)
+        }
+        TypeDef::I64 => {
+            let value = data.next_i64().map_err(|e| located(data, e))?;
+            serializer.serialize_i64(value)
+        }
+        TypeDef::I128 => {
+            let value = data.next_i128().map_err(|e| located(data, e))?;
+            serializer.serialize_i128(value)
+        }
+        TypeDef::EthAddress => {
+            let value = data.next_eth_address().map_err(|e| located(data, e))?;
+            cairo_se.serialize_eth_address(serializer, &value.0)
+        }
+        TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) | TypeDef::Custom(_) => {
+            let value = data.next_byte_array_bytes().map_err(|e| located(data, e))?;
+            cairo_se.serialize_byte_array(serializer, &value)
+        }
+        TypeDef::Utf8String => {
+            let value = data.next_string().map_err(|e| located(data, e))?;
+            cairo_se.serialize_string(serializer, &value)
+        }
+        TypeDef::Tuple(tuple) => cairo_se.serialize_tuple(data, serializer, tuple),
+        TypeDef::Array(array_def) => {
+            let len = data.next_u32().map_err(|e| located(data, e))? as usize;
+            data.check_array_len("array", len)
+                .map_err(|e| located(data, e))?;
+            let mut seq = serializer.serialize_seq(Some(len))?;
+            let inner = array_def.type_def.to_de_se(data, cairo_se);
+            for index in 0..len {
+                with_segment(
+                    data,
+                    || PathSegment::Index(index),
+                    |_| seq.serialize_element(&inner),
+                )?;
+            }
+            seq.end()
+        }
+        TypeDef::FixedArray(fixed_array_def) => {
+            let len = fixed_array_def.size as usize;
+            data.check_array_len("fixed size array", len)
+                .map_err(|e| located(data, e))?;
+            let mut seq = serializer.serialize_seq(Some(len))?;
+            let inner = fixed_array_def.type_def.to_de_se(data, cairo_se);
+            for index in 0..len {
+                with_segment(
+                    data,
+                    || PathSegment::Index(index),
+                    |_| seq.serialize_element(&inner),
+                )?;
+            }
+            seq.end()
+        }
+        TypeDef::Felt252Dict(_) => Err(S::Error::custom(
+            "Felt252Dict transcoding is not implemented",
+        )),
+        TypeDef::Struct(struct_def) => {
+            let mut map = serializer.serialize_map(Some(struct_def.members.len()))?;
+            with_segment(
+                data,
+                || PathSegment::Type(struct_def.name.clone()),
+                |data| {
+                    for member in &struct_def.members {
+                        with_segment(
+                            data,
+                            || PathSegment::Member(member.name.clone()),
+                            |data| {
+                                map.serialize_entry(
+                                    &member.name,
+                                    &member.type_def.to_de_se(data, cairo_se),
+                                )
+                            },
+                        )?;
                     }
-                    false => serializer.serialize_none(),
-                }
-            }
-            TypeDef::Result(result_def) => {
-                let is_ok = data.next_result_is_ok().map_err(S::Error::custom)?;
-                cairo_se.serialize_result(data, serializer, result_def, is_ok)
+                    Ok(())
+                },
+            )?;
+            map.end()
+        }
+        TypeDef::Enum(enum_def) => with_segment(
+            data,
+            || PathSegment::Type(enum_def.name.clone()),
+            |data| {
+                let (selector, _) = data
+                    .next_enum_variant_def(enum_def)
+                    .map_err(|e| located(data, e))?;
+                cairo_se.serialize_enum(data, serializer, enum_def, selector)
+            },
+        ),
+        TypeDef::Ref(_) => Err(S::Error::custom(
+            "TypeDef Ref needs to be expanded before serializing",
+        )),
+        TypeDef::Option(option_def) => {
+            match data.next_option_is_some().map_err(|e| located(data, e))? {
+                true => serializer.serialize_some(&option_def.type_def.to_de_se(data, cairo_se)),
+                false => serializer.serialize_none(),
             }
-            TypeDef::Nullable(nullable_def) => {
-                let is_null = data.next_nullable_is_null().map_err(S::Error::custom)?;
-                match is_null {
-                    true => serializer.serialize_none(),
-                    false => {
-                        serializer.serialize_some(&nullable_def.type_def.to_de_se(data, cairo_se))
-                    }
-                }
+        }
+        TypeDef::Result(result_def) => {
+            let is_ok = data.next_result_is_ok().map_err(|e| located(data, e))?;
+            cairo_se.serialize_result(data, serializer, result_def, is_ok)
+        }
+        TypeDef::Nullable(nullable_def) => {
+            let is_null = data.next_nullable_is_null().map_err(|e| located(data, e))?;
+            match is_null {
+                true => serializer.serialize_none(),
+                false => serializer.serialize_some(&nullable_def.type_def.to_de_se(data, cairo_se)),
             }
         }
     }
@@ -270,63 +366,63 @@ impl<'a, C: CairoTypeSerialization> CairoSerialize<'a, C> for PrimaryTypeDef {
             | PrimaryTypeDef::ContractAddress
             | PrimaryTypeDef::StorageAddress
             | PrimaryTypeDef::StorageBaseAddress => {
-                let value = data.next_felt_bytes().map_err(S::Error::custom)?;
+                let value = data.next_felt_bytes().map_err(|e| located(data, e))?;
                 cairo_se.serialize_felt(serializer, &value)
             }
             PrimaryTypeDef::ShortUtf8 => {
-                let value = data.next_short_string().map_err(S::Error::custom)?;
+                let value = data.next_short_string().map_err(|e| located(data, e))?;
                 serializer.serialize_str(&value)
             }
             PrimaryTypeDef::Bytes31 | PrimaryTypeDef::Bytes31Encoded(_) => {
-                let value = data.next_bytes::<31>().map_err(S::Error::custom)?;
+                let value = data.next_bytes::<31>().map_err(|e| located(data, e))?;
                 serializer.serialize_bytes(&value)
             }
             PrimaryTypeDef::Bool => {
-                let value = data.next_bool().map_err(S::Error::custom)?;
+                let value = data.next_bool().map_err(|e| located(data, e))?;
                 serializer.serialize_bool(value)
             }
             PrimaryTypeDef::U8 => {
-                let value = data.next_u8().map_err(S::Error::custom)?;
+                let value = data.next_u8().map_err(|e| located(data, e))?;
                 serializer.serialize_u8(value)
             }
             PrimaryTypeDef::U16 => {
-                let value = data.next_u16().map_err(S::Error::custom)?;
+                let value = data.next_u16().map_err(|e| located(data, e))?;
                 serializer.serialize_u16(value)
             }
             PrimaryTypeDef::U32 => {
-                let value = data.next_u32().map_err(S::Error::custom)?;
+                let value = data.next_u32().map_err(|e| located(data, e))?;
                 serializer.serialize_u32(value)
             }
             PrimaryTypeDef::U64 => {
-                let value = data.next_u64().map_err(S::Error::custom)?;
+                let value = data.next_u64().map_err(|e| located(data, e))?;
                 serializer.serialize_u64(value)
             }
             PrimaryTypeDef::U128 => {
-                let value = data.next_u128().map_err(S::Error::custom)?;
+                let value = data.next_u128().map_err(|e| located(data, e))?;
                 serializer.serialize_u128(value)
             }
             PrimaryTypeDef::I8 => {
-                let value = data.next_i8().map_err(S::Error::custom)?;
+                let value = data.next_i8().map_err(|e| located(data, e))?;
                 serializer.serialize_i8(value)
             }
             PrimaryTypeDef::I16 => {
-                let value = data.next_i16().map_err(S::Error::custom)?;
+                let value = data.next_i16().map_err(|e| located(data, e))?;
                 serializer.serialize_i16(value)
             }
             PrimaryTypeDef::I32 => {
-                let value = data.next_i32().map_err(S::Error::custom)?;
+                let value = data.next_i32().map_err(|e| located(data, e))?;
                 serializer.serialize_i32(value)
             }
             PrimaryTypeDef::I64 => {
-                let value = data.next_i64().map_err(S::Error::custom)?;
+                let value = data.next_i64().map_err(|e| located(data, e))?;
                 serializer.serialize_i64(value)
             }
             PrimaryTypeDef::I128 => {
-                let value = data.next_i128().map_err(S::Error::custom)?;
+                let value = data.next_i128().map_err(|e| located(data, e))?;
                 serializer.serialize_i128(value)
             }
             PrimaryTypeDef::EthAddress => {
-                let value = data.next_eth_address().map_err(S::Error::custom)?;
+                let value = data.next_eth_address().map_err(|e| located(data, e))?;
                 cairo_se.serialize_eth_address(serializer, &value.0)
             }
         }
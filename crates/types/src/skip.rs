@@ -0,0 +1,304 @@
+use crate::parser::{TypeParserError, TypeParserResult};
+use crate::path::{Locate, PathSegment, with_segment};
+use crate::type_def::{ByteArrayEncodedDef, FixedArrayDef, MemberDef, StructDef, TypeDef};
+use crate::{
+    ArrayDef, Bytes31EncodedDef, CairoDeserializer, ColumnDef, CustomDef, EnumDef, NullableDef,
+    OptionDef, ResultDef, TupleDef, VariantDef,
+};
+
+/// Advances a deserializer past a value without building it, returning the
+/// number of felts consumed.
+pub trait SkipValue<D: CairoDeserializer> {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize>;
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for TypeDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        deserializer.enter_nested()?;
+        let felts = skip_type_def(self, deserializer);
+        deserializer.exit_nested();
+        felts.map_err(|e| e.locate(deserializer))
+    }
+}
+
+fn skip_type_def<D: CairoDeserializer>(
+    type_def: &TypeDef,
+    deserializer: &mut D,
+) -> TypeParserResult<usize> {
+    match type_def {
+        TypeDef::None => Ok(0),
+        TypeDef::Felt252
+        | TypeDef::ClassHash
+        | TypeDef::ContractAddress
+        | TypeDef::StorageAddress
+        | TypeDef::StorageBaseAddress => deserializer.next_felt().map(|_| 1),
+        TypeDef::ShortUtf8 | TypeDef::Bytes31 => deserializer.next_bytes::<31>().map(|_| 1),
+        TypeDef::Bytes31Encoded(b) => return b.skip(deserializer),
+        TypeDef::Bool => deserializer.next_bool().map(|_| 1),
+        TypeDef::U8 => deserializer.next_u8().map(|_| 1),
+        TypeDef::U16 => deserializer.next_u16().map(|_| 1),
+        TypeDef::U32 => deserializer.next_u32().map(|_| 1),
+        TypeDef::U64 => deserializer.next_u64().map(|_| 1),
+        TypeDef::U128 => deserializer.next_u128().map(|_| 1),
+        TypeDef::U256 => deserializer.next_u256().map(|_| 2),
+        TypeDef::U512 => deserializer.next_u512().map(|_| 4),
+        TypeDef::I8 => deserializer.next_i8().map(|_| 1),
+        TypeDef::I16 => deserializer.next_i16().map(|_| 1),
+        TypeDef::I32 => deserializer.next_i32().map(|_| 1),
+        TypeDef::I64 => deserializer.next_i64().map(|_| 1),
+        TypeDef::I128 => deserializer.next_i128().map(|_| 1),
+        TypeDef::EthAddress => deserializer.next_eth_address().map(|_| 1),
+        TypeDef::ByteArray | TypeDef::Utf8String => deserializer.skip_byte_array(),
+        TypeDef::ByteArrayEncoded(bae) => return bae.skip(deserializer),
+        TypeDef::Tuple(tuple) => return tuple.skip(deserializer),
+        TypeDef::Array(a) => return a.skip(deserializer),
+        TypeDef::FixedArray(fa) => return fa.skip(deserializer),
+        TypeDef::Felt252Dict(_) => return Err(TypeParserError::Unimplemented("Felt252Dict")),
+        TypeDef::Struct(s) => return s.skip(deserializer),
+        TypeDef::Enum(e) => return e.skip(deserializer),
+        TypeDef::Ref(_) => return Err(TypeParserError::RefNotSupported),
+        TypeDef::Custom(custom) => return custom.skip(deserializer),
+        TypeDef::Option(option) => return option.skip(deserializer),
+        TypeDef::Result(r) => return r.skip(deserializer),
+        TypeDef::Nullable(nullable) => return nullable.skip(deserializer),
+    }
+    .map_err(Into::into)
+}
+
+fn skip_elements<D: CairoDeserializer>(
+    type_def: &TypeDef,
+    deserializer: &mut D,
+    count: usize,
+) -> TypeParserResult<usize> {
+    let mut felts = 0;
+    for index in 0..count {
+        felts += with_segment(
+            deserializer,
+            || PathSegment::Index(index),
+            |d| type_def.skip(d),
+        )?;
+    }
+    Ok(felts)
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for MemberDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        with_segment(
+            deserializer,
+            || PathSegment::Member(self.name.clone()),
+            |d| self.type_def.skip(d),
+        )
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for StructDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        with_segment(
+            deserializer,
+            || PathSegment::Type(self.name.clone()),
+            |d| {
+                self.members
+                    .iter()
+                    .try_fold(0, |felts, m| Ok(felts + m.skip(d)?))
+            },
+        )
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for ArrayDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        let count = deserializer.next_u32()? as usize;
+        deserializer.check_array_len("array", count)?;
+        Ok(1 + skip_elements(&self.type_def, deserializer, count)?)
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for FixedArrayDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        deserializer.check_array_len("fixed size array", self.size as usize)?;
+        skip_elements(&self.type_def, deserializer, self.size as usize)
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for TupleDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        let mut felts = 0;
+        for (index, element) in self.elements.iter().enumerate() {
+            felts += with_segment(
+                deserializer,
+                || PathSegment::Index(index),
+                |d| element.skip(d),
+            )?;
+        }
+        Ok(felts)
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for EnumDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        with_segment(
+            deserializer,
+            || PathSegment::Type(self.name.clone()),
+            |d| {
+                let (_, variant) = d.next_enum_variant_def(self)?;
+                Ok(1 + variant.skip(d)?)
+            },
+        )
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for VariantDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        with_segment(
+            deserializer,
+            || PathSegment::Variant(self.name.clone()),
+            |d| self.type_def.skip(d),
+        )
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for OptionDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        match deserializer.next_option_is_some()? {
+            true => Ok(1 + self.type_def.skip(deserializer)?),
+            false => Ok(1),
+        }
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for ResultDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        match deserializer.next_result_is_ok()? {
+            true => Ok(1 + self.ok.skip(deserializer)?),
+            false => Ok(1 + self.err.skip(deserializer)?),
+        }
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for NullableDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        match deserializer.next_nullable_is_null()? {
+            true => Ok(1),
+            false => Ok(1 + self.type_def.skip(deserializer)?),
+        }
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for CustomDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        let count = deserializer.next_u32()? as usize;
+        deserializer.check_array_len("array", count)?;
+        for _ in 0..count {
+            deserializer.next_felt()?;
+        }
+        Ok(1 + count)
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for ByteArrayEncodedDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        Ok(deserializer.skip_byte_array()?)
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for Bytes31EncodedDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        deserializer.next_bytes31()?;
+        Ok(1)
+    }
+}
+
+impl<D: CairoDeserializer> SkipValue<D> for ColumnDef {
+    fn skip(&self, deserializer: &mut D) -> TypeParserResult<usize> {
+        with_segment(
+            deserializer,
+            || PathSegment::Member(self.name.clone()),
+            |d| self.type_def.skip(d),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CairoISerde, CairoSerde, FeltSource, IntoFeltSource, ParseValue, Value};
+    use starknet_types_core::felt::Felt;
+
+    fn type_def() -> TypeDef {
+        let kind = EnumDef::new_type_def(
+            "Kind".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::from(0x10),
+                    VariantDef::new("Empty".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::from(0x20),
+                    VariantDef::new("Pair".to_string(), vec![], TypeDef::U256),
+                ),
+            ],
+        );
+        StructDef::new_type_def(
+            "Item".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("kind".to_string(), vec![], kind),
+                MemberDef::new(
+                    "maybe".to_string(),
+                    vec![],
+                    OptionDef::new_type_def(TypeDef::U8),
+                ),
+                MemberDef::new(
+                    "boxed".to_string(),
+                    vec![],
+                    NullableDef::new_type_def(TypeDef::U8),
+                ),
+                MemberDef::new(
+                    "fixed".to_string(),
+                    vec![],
+                    FixedArrayDef::new_type_def(TypeDef::U16, 2),
+                ),
+                MemberDef::new("name".to_string(), vec![], TypeDef::Utf8String),
+                MemberDef::new("tail".to_string(), vec![], TypeDef::Felt252),
+            ],
+        )
+    }
+
+    #[test]
+    fn skips_serde_values() {
+        let felts: Vec<Felt> = [0x20, 1, 2, 0, 7, 1, 3, 4, 5, 1, 0x61, 0x62, 1, 9, 10]
+            .into_iter()
+            .map(Felt::from)
+            .collect();
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        assert_eq!(type_def().skip(&mut de).unwrap(), 14);
+        assert_eq!(de.next_felt().unwrap(), Felt::from(10));
+
+        let mut de = CairoSerde(felts.as_slice().into_source());
+        type_def().parse(&mut de).unwrap();
+        assert_eq!(de.position(), 14);
+    }
+
+    #[test]
+    fn skips_packed_byte_arrays() {
+        let first = [0u8; 32];
+        let mut last = [0u8; 32];
+        last[0] = 3;
+        last[2] = 4;
+        let felts = vec![
+            Felt::from_bytes_be(&first),
+            Felt::from_bytes_be(&last),
+            Felt::from(5),
+        ];
+        let mut de = CairoISerde(felts.as_slice().into_source());
+        assert_eq!(TypeDef::ByteArray.skip(&mut de).unwrap(), 2);
+        assert_eq!(de.position(), 2);
+
+        let mut de = CairoISerde(felts.as_slice().into_source());
+        let Value::ByteArray(bytes) = TypeDef::ByteArray.parse(&mut de).unwrap() else {
+            panic!("expected byte array");
+        };
+        assert_eq!(bytes.len(), 35);
+    }
+}
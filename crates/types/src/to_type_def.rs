@@ -0,0 +1,578 @@
+use crate::type_def::TypeName;
+use crate::{
+    ArrayDef, ByteArray, ByteArrayEncodedDef, Bytes31, Bytes31EncodedDef, EthAddress,
+    FixedArrayDef, GetRefTypeDef, OptionDef, ResultDef, TupleDef, TypeDef,
+};
+use primitive_types::{U256, U512};
+use starknet_types_core::felt::Felt;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The `TypeDef` the Cairo side would declare for a Rust type, derived with
+/// `#[derive(Introspect)]` for structs and enums.
+pub trait ToTypeDef {
+    fn to_type_def() -> TypeDef;
+
+    /// Differences between the type and a `TypeDef` decoded from chain, `refs`
+    /// resolving the `Ref`s it holds.
+    fn type_def_mismatches(
+        found: &TypeDef,
+        refs: Option<&dyn GetRefTypeDef>,
+    ) -> Vec<TypeDefMismatch> {
+        type_def_mismatches(&Self::to_type_def(), found, refs)
+    }
+}
+
+macro_rules! to_type_def {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(impl ToTypeDef for $ty {
+            fn to_type_def() -> TypeDef {
+                TypeDef::$variant
+            }
+        })*
+    };
+}
+
+to_type_def!(
+    () => None,
+    Felt => Felt252,
+    bool => Bool,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    u128 => U128,
+    U256 => U256,
+    U512 => U512,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    i128 => I128,
+    EthAddress => EthAddress,
+    Bytes31 => Bytes31,
+    ByteArray => ByteArray,
+    String => Utf8String,
+);
+
+impl<T: ToTypeDef> ToTypeDef for Vec<T> {
+    fn to_type_def() -> TypeDef {
+        ArrayDef::new_type_def(T::to_type_def())
+    }
+}
+
+impl<T: ToTypeDef, const N: usize> ToTypeDef for [T; N] {
+    fn to_type_def() -> TypeDef {
+        FixedArrayDef::new_type_def(T::to_type_def(), N as u32)
+    }
+}
+
+impl<T: ToTypeDef> ToTypeDef for Option<T> {
+    fn to_type_def() -> TypeDef {
+        OptionDef::new_type_def(T::to_type_def())
+    }
+}
+
+impl<T: ToTypeDef, E: ToTypeDef> ToTypeDef for Result<T, E> {
+    fn to_type_def() -> TypeDef {
+        ResultDef::new_type_def(T::to_type_def(), E::to_type_def())
+    }
+}
+
+/// Transparent, the `Introspect` derive rejecting types that box themselves.
+impl<T: ToTypeDef> ToTypeDef for Box<T> {
+    fn to_type_def() -> TypeDef {
+        T::to_type_def()
+    }
+}
+
+macro_rules! tuple_to_type_def {
+    ($($name:ident),+) => {
+        impl<$($name: ToTypeDef),+> ToTypeDef for ($($name,)+) {
+            fn to_type_def() -> TypeDef {
+                TupleDef::new_type_def(vec![$($name::to_type_def()),+])
+            }
+        }
+    };
+}
+
+tuple_to_type_def!(A);
+tuple_to_type_def!(A, B);
+tuple_to_type_def!(A, B, C);
+tuple_to_type_def!(A, B, C, D);
+tuple_to_type_def!(A, B, C, D, E);
+tuple_to_type_def!(A, B, C, D, E, F);
+tuple_to_type_def!(A, B, C, D, E, F, G);
+tuple_to_type_def!(A, B, C, D, E, F, G, H);
+
+/// Applies `#[raw]`: strings become the raw byte types.
+pub fn raw_type_def(type_def: TypeDef) -> TypeDef {
+    match type_def {
+        TypeDef::Utf8String => TypeDef::ByteArray,
+        TypeDef::ShortUtf8 => TypeDef::Bytes31,
+        type_def => type_def,
+    }
+}
+
+/// Applies `#[encoded("...")]` to string and byte types.
+pub fn encoded_type_def(type_def: TypeDef, encoding: &str) -> TypeDef {
+    match type_def {
+        TypeDef::Utf8String | TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) => {
+            ByteArrayEncodedDef::new_type_def(encoding.to_string())
+        }
+        TypeDef::ShortUtf8 | TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => {
+            Bytes31EncodedDef::new_type_def(encoding.to_string())
+        }
+        type_def => type_def,
+    }
+}
+
+/// A difference between two `TypeDef`s, at a path of member, variant and
+/// element names from the root.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeDefMismatch {
+    pub path: String,
+    pub expected: String,
+    pub found: String,
+}
+
+impl Display for TypeDefMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.path.is_empty() {
+            true => write!(f, "expected {}, found {}", self.expected, self.found),
+            false => write!(
+                f,
+                "{}: expected {}, found {}",
+                self.path, self.expected, self.found
+            ),
+        }
+    }
+}
+
+/// Every difference between `expected` and `found`, resolving references on
+/// either side with `refs`. Attributes are not compared, and a pair of types
+/// already being compared higher up through references is assumed to match.
+pub fn type_def_mismatches(
+    expected: &TypeDef,
+    found: &TypeDef,
+    refs: Option<&dyn GetRefTypeDef>,
+) -> Vec<TypeDefMismatch> {
+    let mut mismatches = Vec::new();
+    compare(
+        String::new(),
+        expected,
+        found,
+        refs,
+        &mut Vec::new(),
+        &mut mismatches,
+    );
+    mismatches
+}
+
+fn child(path: &str, segment: &str) -> String {
+    match path.is_empty() {
+        true => segment.to_string(),
+        false => format!("{path}.{segment}"),
+    }
+}
+
+fn push(mismatches: &mut Vec<TypeDefMismatch>, path: String, expected: String, found: String) {
+    mismatches.push(TypeDefMismatch {
+        path,
+        expected,
+        found,
+    });
+}
+
+fn resolve(type_def: &TypeDef, refs: Option<&dyn GetRefTypeDef>) -> Option<TypeDef> {
+    match type_def {
+        TypeDef::Ref(reference) => refs?.get_type_def(reference.id),
+        _ => None,
+    }
+}
+
+/// `resolving` holds the pairs whose references are being resolved on the
+/// current path, so recursive types stop where they repeat.
+fn compare(
+    path: String,
+    expected: &TypeDef,
+    found: &TypeDef,
+    refs: Option<&dyn GetRefTypeDef>,
+    resolving: &mut Vec<(TypeDef, TypeDef)>,
+    mismatches: &mut Vec<TypeDefMismatch>,
+) {
+    let (resolved_expected, resolved_found) = (resolve(expected, refs), resolve(found, refs));
+    if resolved_expected.is_some() || resolved_found.is_some() {
+        let pair = (expected.clone(), found.clone());
+        if resolving.contains(&pair) {
+            return;
+        }
+        resolving.push(pair);
+        compare(
+            path,
+            resolved_expected.as_ref().unwrap_or(expected),
+            resolved_found.as_ref().unwrap_or(found),
+            refs,
+            resolving,
+            mismatches,
+        );
+        resolving.pop();
+        return;
+    }
+    match (expected, found) {
+        (TypeDef::Struct(expected), TypeDef::Struct(found)) => {
+            if expected.name != found.name {
+                push(
+                    mismatches,
+                    child(&path, "name"),
+                    expected.name.clone(),
+                    found.name.clone(),
+                );
+            }
+            if expected.members.len() != found.members.len() {
+                push(
+                    mismatches,
+                    path.clone(),
+                    format!("{} members", expected.members.len()),
+                    format!("{} members", found.members.len()),
+                );
+            }
+            for (expected, found) in expected.members.iter().zip(&found.members) {
+                let path = child(&path, &expected.name);
+                if expected.name != found.name {
+                    push(
+                        mismatches,
+                        path.clone(),
+                        expected.name.clone(),
+                        found.name.clone(),
+                    );
+                }
+                compare(
+                    path,
+                    &expected.type_def,
+                    &found.type_def,
+                    refs,
+                    resolving,
+                    mismatches,
+                );
+            }
+        }
+        (TypeDef::Enum(expected), TypeDef::Enum(found)) => {
+            if expected.name != found.name {
+                push(
+                    mismatches,
+                    child(&path, "name"),
+                    expected.name.clone(),
+                    found.name.clone(),
+                );
+            }
+            if expected.order.len() != found.order.len() {
+                push(
+                    mismatches,
+                    path.clone(),
+                    format!("{} variants", expected.order.len()),
+                    format!("{} variants", found.order.len()),
+                );
+            }
+            for (expected_selector, found_selector) in expected.order.iter().zip(&found.order) {
+                let (Some(expected), Some(found)) = (
+                    expected.variants.get(expected_selector),
+                    found.variants.get(found_selector),
+                ) else {
+                    continue;
+                };
+                let path = child(&path, &expected.name);
+                if expected.name != found.name {
+                    push(
+                        mismatches,
+                        path.clone(),
+                        expected.name.clone(),
+                        found.name.clone(),
+                    );
+                }
+                if expected_selector != found_selector {
+                    push(
+                        mismatches,
+                        child(&path, "selector"),
+                        format!("{expected_selector:#x}"),
+                        format!("{found_selector:#x}"),
+                    );
+                }
+                compare(
+                    path,
+                    &expected.type_def,
+                    &found.type_def,
+                    refs,
+                    resolving,
+                    mismatches,
+                );
+            }
+        }
+        (TypeDef::Tuple(expected), TypeDef::Tuple(found)) => {
+            if expected.elements.len() != found.elements.len() {
+                push(
+                    mismatches,
+                    path.clone(),
+                    format!("{} elements", expected.elements.len()),
+                    format!("{} elements", found.elements.len()),
+                );
+            }
+            for (index, (expected, found)) in
+                expected.elements.iter().zip(&found.elements).enumerate()
+            {
+                compare(
+                    child(&path, &index.to_string()),
+                    expected,
+                    found,
+                    refs,
+                    resolving,
+                    mismatches,
+                );
+            }
+        }
+        (TypeDef::Array(expected), TypeDef::Array(found)) => compare(
+            path + "[]",
+            &expected.type_def,
+            &found.type_def,
+            refs,
+            resolving,
+            mismatches,
+        ),
+        (TypeDef::FixedArray(expected), TypeDef::FixedArray(found)) => {
+            if expected.size != found.size {
+                push(
+                    mismatches,
+                    path.clone(),
+                    format!("{} elements", expected.size),
+                    format!("{} elements", found.size),
+                );
+            }
+            compare(
+                path + "[]",
+                &expected.type_def,
+                &found.type_def,
+                refs,
+                resolving,
+                mismatches,
+            )
+        }
+        (TypeDef::Option(expected), TypeDef::Option(found)) => compare(
+            path + "?",
+            &expected.type_def,
+            &found.type_def,
+            refs,
+            resolving,
+            mismatches,
+        ),
+        (TypeDef::Nullable(expected), TypeDef::Nullable(found)) => compare(
+            path + "?",
+            &expected.type_def,
+            &found.type_def,
+            refs,
+            resolving,
+            mismatches,
+        ),
+        (TypeDef::Felt252Dict(expected), TypeDef::Felt252Dict(found)) => compare(
+            path + "[]",
+            &expected.type_def,
+            &found.type_def,
+            refs,
+            resolving,
+            mismatches,
+        ),
+        (TypeDef::Result(expected), TypeDef::Result(found)) => {
+            compare(
+                child(&path, "ok"),
+                &expected.ok,
+                &found.ok,
+                refs,
+                resolving,
+                mismatches,
+            );
+            compare(
+                child(&path, "err"),
+                &expected.err,
+                &found.err,
+                refs,
+                resolving,
+                mismatches,
+            );
+        }
+        (expected, found) if expected != found => {
+            push(mismatches, path, expected.type_name(), found.type_name())
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EnumDef, Introspect, MemberDef, StructDef, VariantDef};
+    use starknet::core::utils::get_selector_from_name;
+    use std::collections::HashMap;
+
+    #[allow(dead_code)]
+    #[derive(Introspect)]
+    enum Direction {
+        Up,
+        #[name("Jump")]
+        Leap(u8),
+        #[selector("0x7")]
+        Slide(u16, bool),
+    }
+
+    #[allow(dead_code)]
+    #[derive(Introspect)]
+    #[name("game::Position")]
+    struct Position<T> {
+        #[type_def(ContractAddress)]
+        owner: Felt,
+        #[raw]
+        label: String,
+        #[encoded("ascii")]
+        code: String,
+        moves: Vec<(T, Option<Direction>)>,
+        grid: [u8; 2],
+    }
+
+    fn direction() -> TypeDef {
+        EnumDef::new_type_def(
+            "Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    get_selector_from_name("Up").unwrap(),
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    get_selector_from_name("Leap").unwrap(),
+                    VariantDef::new("Jump".to_string(), vec![], TypeDef::U8),
+                ),
+                (
+                    Felt::from(7),
+                    VariantDef::new(
+                        "Slide".to_string(),
+                        vec![],
+                        TupleDef::new_type_def(vec![TypeDef::U16, TypeDef::Bool]),
+                    ),
+                ),
+            ],
+        )
+    }
+
+    fn position(moves: TypeDef) -> TypeDef {
+        StructDef::new_type_def(
+            "game::Position".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("owner".to_string(), vec![], TypeDef::ContractAddress),
+                MemberDef::new("label".to_string(), vec![], TypeDef::ByteArray),
+                MemberDef::new(
+                    "code".to_string(),
+                    vec![],
+                    ByteArrayEncodedDef::new_type_def("ascii".to_string()),
+                ),
+                MemberDef::new("moves".to_string(), vec![], moves),
+                MemberDef::new(
+                    "grid".to_string(),
+                    vec![],
+                    FixedArrayDef::new_type_def(TypeDef::U8, 2),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn derives_type_defs() {
+        let moves = ArrayDef::new_type_def(TupleDef::new_type_def(vec![
+            TypeDef::U32,
+            OptionDef::new_type_def(direction()),
+        ]));
+        assert_eq!(Position::<u32>::to_type_def(), position(moves.clone()));
+        assert_eq!(Direction::to_type_def(), direction());
+        assert!(Position::<u32>::type_def_mismatches(&position(moves), None).is_empty());
+    }
+
+    #[test]
+    fn reports_mismatches_and_resolves_refs() {
+        let refs = HashMap::from([(Felt::ONE, direction())]);
+        let moves = ArrayDef::new_type_def(TupleDef::new_type_def(vec![
+            TypeDef::U64,
+            OptionDef::new_type_def(crate::RefDef::new_type_def(Felt::ONE)),
+        ]));
+        let found = position(moves);
+        assert_eq!(
+            Position::<u64>::type_def_mismatches(&found, Some(&refs)),
+            vec![]
+        );
+        let mismatches = type_def_mismatches(&Position::<u32>::to_type_def(), &found, None);
+        assert_eq!(
+            mismatches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "moves[].0: expected U32, found U64".to_string(),
+                format!("moves[].1?: expected Direction, found {:#x}", Felt::ONE),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_recursive_refs_and_reports_lengths() {
+        let node = |value: TypeDef, next: Felt| {
+            StructDef::new_type_def(
+                "Node".to_string(),
+                vec![],
+                vec![
+                    MemberDef::new("value".to_string(), vec![], value),
+                    MemberDef::new(
+                        "next".to_string(),
+                        vec![],
+                        OptionDef::new_type_def(crate::RefDef::new_type_def(next)),
+                    ),
+                ],
+            )
+        };
+        let refs = HashMap::from([
+            (Felt::ONE, node(TypeDef::U8, Felt::ONE)),
+            (Felt::TWO, node(TypeDef::U16, Felt::TWO)),
+        ]);
+        let mismatches = type_def_mismatches(
+            &crate::RefDef::new_type_def(Felt::ONE),
+            &crate::RefDef::new_type_def(Felt::TWO),
+            Some(&refs),
+        );
+        assert_eq!(
+            mismatches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["value: expected U8, found U16".to_string()]
+        );
+
+        let mismatches = type_def_mismatches(
+            &TupleDef::new_type_def(vec![
+                TypeDef::U8,
+                FixedArrayDef::new_type_def(TypeDef::U8, 2),
+            ]),
+            &TupleDef::new_type_def(vec![
+                TypeDef::U8,
+                FixedArrayDef::new_type_def(TypeDef::U16, 3),
+                TypeDef::Bool,
+            ]),
+            None,
+        );
+        assert_eq!(
+            mismatches
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "expected 2 elements, found 3 elements",
+                "1: expected 2 elements, found 3 elements",
+                "1[]: expected U8, found U16",
+            ]
+        );
+    }
+}
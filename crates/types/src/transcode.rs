@@ -1,5 +1,6 @@
 use std::io;
 
+use crate::path::{Locate, Location};
 use crate::{CairoDeserializer, DecodeError};
 use starknet_types_core::felt::Felt;
 
@@ -20,6 +21,22 @@ impl<D, S> TranscodeError<D, S> {
     }
 }
 
+impl<D: Locate, S> Locate for TranscodeError<D, S> {
+    fn is_located(&self) -> bool {
+        match self {
+            Self::Deserialize(err) => err.is_located(),
+            Self::Serialize(_) => true,
+        }
+    }
+
+    fn with_location(self, location: Location) -> Self {
+        match self {
+            Self::Deserialize(err) => Self::Deserialize(err.with_location(location)),
+            err => err,
+        }
+    }
+}
+
 pub trait TranscodeResult<T, D, S> {
     fn map_de(self) -> Result<T, TranscodeError<D, S>>;
     fn and_then_tc<U, F>(self, op: F) -> Result<(), TranscodeError<D, S>>
@@ -1,5 +1,6 @@
 use std::ops::Deref;
 
+use crate::path::{Locate, PathSegment, with_segment};
 use crate::transcode::{
     CairoWrite, Transcode, TranscodeError, TranscodeResult, TranscodeSerializeResult,
     TranscodeWriter,
@@ -78,61 +79,76 @@ where
         input: &mut In,
         output: &mut Out,
     ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
-        match self {
-            TypeDef::None => Ok(()),
-            TypeDef::Felt252
-            | TypeDef::ClassHash
-            | TypeDef::ContractAddress
-            | TypeDef::StorageAddress
-            | TypeDef::StorageBaseAddress => output.transcode_felt(input),
-            TypeDef::ShortUtf8 | TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => {
-                output.transcode_bytes::<31>(input)
-            }
-            TypeDef::Bool => output.transcode_bytes::<1>(input),
-            TypeDef::U8 => output.transcode_bytes::<1>(input),
-            TypeDef::U16 => output.transcode_bytes::<2>(input),
-            TypeDef::U32 => output.transcode_bytes::<4>(input),
-            TypeDef::U64 => output.transcode_bytes::<8>(input),
-            TypeDef::U128 => output.transcode_bytes::<16>(input),
-            TypeDef::U256 => input
-                .next_u256()
-                .and_then_tc(|v| output.write_bytes(&v.to_big_endian())),
-            TypeDef::U512 => input
-                .next_u512()
-                .and_then_tc(|v| output.write_bytes(&v.to_big_endian())),
-            TypeDef::I8 => input.next_i8().and_then_tc(|v| output.write_byte(v as u8)),
-            TypeDef::I16 => input
-                .next_i16()
-                .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
-            TypeDef::I32 => input
-                .next_i32()
-                .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
-            TypeDef::I64 => input
-                .next_i64()
-                .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
-            TypeDef::I128 => input
-                .next_i128()
-                .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
-            TypeDef::EthAddress => output.transcode_bytes::<20>(input),
-            TypeDef::ByteArray
-            | TypeDef::Utf8String
-            | TypeDef::ByteArrayEncoded(_)
-            | TypeDef::Custom(_) => input
-                .next_byte_array_bytes()
-                .and_then_tc(|v| output.write_variable_bytes(&v)),
-            TypeDef::Tuple(tuple) => tuple.transcode(input, output),
-            TypeDef::Array(a) => a.transcode(input, output),
-            TypeDef::FixedArray(fa) => fa.transcode(input, output),
-            TypeDef::Felt252Dict(_ty) => unimplemented!(), // TODO: implement Felt252Dict parsing
-            TypeDef::Struct(s) => s.transcode(input, output),
-            TypeDef::Enum(e) => e.transcode(input, output),
-            TypeDef::Ref(_) => Err(TranscodeError::de(DecodeError::message(
-                "TypeDef Ref needs to be expanded before transoding",
-            ))),
-            TypeDef::Option(option) => option.transcode(input, output),
-            TypeDef::Result(result) => result.transcode(input, output),
-            TypeDef::Nullable(nullable) => nullable.transcode(input, output),
+        input.enter_nested().map_de()?;
+        let result = transcode_type_def(self, input, output);
+        input.exit_nested();
+        result.map_err(|e| e.locate(input))
+    }
+}
+
+fn transcode_type_def<In, Out>(
+    type_def: &TypeDef,
+    input: &mut In,
+    output: &mut Out,
+) -> Result<(), TranscodeError<DecodeError, <Out as TranscodeWriter<In>>::SerializeError>>
+where
+    In: CairoDeserializer,
+    Out: CairoWrite,
+{
+    match type_def {
+        TypeDef::None => Ok(()),
+        TypeDef::Felt252
+        | TypeDef::ClassHash
+        | TypeDef::ContractAddress
+        | TypeDef::StorageAddress
+        | TypeDef::StorageBaseAddress => output.transcode_felt(input),
+        TypeDef::ShortUtf8 | TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => {
+            output.transcode_bytes::<31>(input)
         }
+        TypeDef::Bool => output.transcode_bytes::<1>(input),
+        TypeDef::U8 => output.transcode_bytes::<1>(input),
+        TypeDef::U16 => output.transcode_bytes::<2>(input),
+        TypeDef::U32 => output.transcode_bytes::<4>(input),
+        TypeDef::U64 => output.transcode_bytes::<8>(input),
+        TypeDef::U128 => output.transcode_bytes::<16>(input),
+        TypeDef::U256 => input
+            .next_u256()
+            .and_then_tc(|v| output.write_bytes(&v.to_big_endian())),
+        TypeDef::U512 => input
+            .next_u512()
+            .and_then_tc(|v| output.write_bytes(&v.to_big_endian())),
+        TypeDef::I8 => input.next_i8().and_then_tc(|v| output.write_byte(v as u8)),
+        TypeDef::I16 => input
+            .next_i16()
+            .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
+        TypeDef::I32 => input
+            .next_i32()
+            .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
+        TypeDef::I64 => input
+            .next_i64()
+            .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
+        TypeDef::I128 => input
+            .next_i128()
+            .and_then_tc(|v| output.write_bytes(&v.to_be_bytes())),
+        TypeDef::EthAddress => output.transcode_bytes::<20>(input),
+        TypeDef::ByteArray
+        | TypeDef::Utf8String
+        | TypeDef::ByteArrayEncoded(_)
+        | TypeDef::Custom(_) => input
+            .next_byte_array_bytes()
+            .and_then_tc(|v| output.write_variable_bytes(&v)),
+        TypeDef::Tuple(tuple) => tuple.transcode(input, output),
+        TypeDef::Array(a) => a.transcode(input, output),
+        TypeDef::FixedArray(fa) => fa.transcode(input, output),
+        TypeDef::Felt252Dict(_ty) => unimplemented!(), // TODO: implement Felt252Dict parsing
+        TypeDef::Struct(s) => s.transcode(input, output),
+        TypeDef::Enum(e) => e.transcode(input, output),
+        TypeDef::Ref(_) => Err(TranscodeError::de(DecodeError::message(
+            "TypeDef Ref needs to be expanded before transoding",
+        ))),
+        TypeDef::Option(option) => option.transcode(input, output),
+        TypeDef::Result(result) => result.transcode(input, output),
+        TypeDef::Nullable(nullable) => nullable.transcode(input, output),
     }
 }
 
@@ -149,8 +165,12 @@ where
         input: &mut In,
         output: &mut Out,
     ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
-        for element in &self.elements {
-            element.transcode(input, &mut *output)?;
+        for (index, element) in self.elements.iter().enumerate() {
+            with_segment(
+                input,
+                || PathSegment::Index(index),
+                |input| element.transcode(input, &mut *output),
+            )?;
         }
         Ok(())
     }
@@ -170,9 +190,14 @@ where
         output: &mut Out,
     ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
         let len = input.next_u32().map_de()?;
+        input.check_array_len("array", len as usize).map_de()?;
         output.write_bytes(&len.to_be_bytes()).map_se()?;
-        for _ in 0..len {
-            self.type_def.transcode(input, &mut *output)?;
+        for index in 0..len as usize {
+            with_segment(
+                input,
+                || PathSegment::Index(index),
+                |input| self.type_def.transcode(input, &mut *output),
+            )?;
         }
         Ok(())
     }
@@ -191,8 +216,15 @@ where
         input: &mut In,
         output: &mut Out,
     ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
-        for _ in 0..self.size {
-            self.type_def.transcode(input, &mut *output)?;
+        input
+            .check_array_len("fixed size array", self.size as usize)
+            .map_de()?;
+        for index in 0..self.size as usize {
+            with_segment(
+                input,
+                || PathSegment::Index(index),
+                |input| self.type_def.transcode(input, &mut *output),
+            )?;
         }
         Ok(())
     }
@@ -211,10 +243,20 @@ where
         input: &mut In,
         output: &mut Out,
     ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
-        for member in &self.members {
-            member.type_def.transcode(input, &mut *output)?;
-        }
-        Ok(())
+        with_segment(
+            input,
+            || PathSegment::Type(self.name.clone()),
+            |input| {
+                for member in &self.members {
+                    with_segment(
+                        input,
+                        || PathSegment::Member(member.name.clone()),
+                        |input| member.type_def.transcode(input, &mut *output),
+                    )?;
+                }
+                Ok(())
+            },
+        )
     }
 }
 
@@ -231,12 +273,19 @@ where
         input: &mut In,
         output: &mut Out,
     ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
-        let selector = input.next_enum_variant().map_de()?;
-        output.write_felt(selector).map_se()?;
-        self.get_variant(&selector)
-            .map_de()?
-            .type_def
-            .transcode(input, output)
+        with_segment(
+            input,
+            || PathSegment::Type(self.name.clone()),
+            |input| {
+                let (selector, variant) = input.next_enum_variant_def(self).map_de()?;
+                output.write_felt(selector).map_se()?;
+                with_segment(
+                    input,
+                    || PathSegment::Variant(variant.name.clone()),
+                    |input| variant.type_def.transcode(input, output),
+                )
+            },
+        )
     }
 }
 
@@ -319,7 +368,11 @@ where
         input: &mut In,
         output: &mut Out,
     ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
-        self.type_def.transcode(input, output)
+        with_segment(
+            input,
+            || PathSegment::Member(self.name.clone()),
+            |input| self.type_def.transcode(input, output),
+        )
     }
 }
 
@@ -336,6 +389,10 @@ where
         input: &mut In,
         output: &mut Out,
     ) -> Result<(), TranscodeError<Self::DeserializeError, Self::SerializeError>> {
-        self.type_def.transcode(input, output)
+        with_segment(
+            input,
+            || PathSegment::Member(self.name.clone()),
+            |input| self.type_def.transcode(input, output),
+        )
     }
 }
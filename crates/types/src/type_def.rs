@@ -1,4 +1,4 @@
-use crate::{Attribute, Attributes, DecodeError, DecodeResult, ascii_str_to_limbs};
+use crate::{Attribute, Attributes, DecodeError, DecodeResult, ascii_str_to_be_digits};
 use serde::{Deserialize, Serialize};
 use starknet_types_core::felt::Felt;
 use std::collections::HashMap;
@@ -20,9 +20,9 @@ macro_rules! define_type_variants {
 
         #[allow(non_upper_case_globals)]
         pub mod selectors {
-            use super::ascii_str_to_limbs;
+            use super::ascii_str_to_be_digits;
             pub const None: [u64; 4] = [0; 4];
-            $(pub const $variant: [u64; 4] = ascii_str_to_limbs($selector);)*
+            $(pub const $variant: [u64; 4] = ascii_str_to_be_digits($selector);)*
         }
 
 
@@ -367,6 +367,16 @@ impl EnumDef {
                 *selector,
             ))
     }
+
+    pub fn get_variant_by_index(&self, index: Felt) -> DecodeResult<(Felt, &VariantDef)> {
+        let selector = usize::try_from(index)
+            .ok()
+            .and_then(|i| self.order.get(i))
+            .ok_or_else(|| {
+                DecodeError::enum_index_out_of_range(self.name.clone(), index, self.order.len())
+            })?;
+        Ok((*selector, self.get_variant(selector)?))
+    }
 }
 
 impl PartialEq for EnumDef {
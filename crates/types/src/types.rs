@@ -10,8 +10,11 @@ const MAX_L1_ADDRESS: Felt = Felt::from_raw([
     18406070939574861858,
 ]);
 
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct EthAddress(pub [u8; 20]);
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Bytes31(pub [u8; 31]);
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ByteArray(pub Vec<u8>);
 
 impl TryFrom<Felt> for Bytes31 {
@@ -0,0 +1,577 @@
+use crate::{EnumDef, GetRefTypeDef, StructDef, TableSchema, TypeDef, camel_case, type_name};
+use starknet_types_core::felt::Felt;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use thiserror::Error;
+
+/// Reader and helpers the generated decoders call, emitted once per module.
+/// They mirror `CairoSerde` and `CairoISerde`, and `IndexedEnums` when
+/// `indexedEnums` is set.
+pub const TS_RUNTIME: &str = r#"export type Layout = "serde" | "iserde";
+
+const PRIME = 2n ** 251n + 17n * 2n ** 192n + 1n;
+
+export class FeltReader {
+  private position = 0;
+
+  constructor(
+    private readonly felts: readonly bigint[],
+    readonly layout: Layout = "serde",
+    readonly indexedEnums = false,
+  ) {}
+
+  get remaining(): number {
+    return this.felts.length - this.position;
+  }
+
+  felt(): bigint {
+    if (this.position >= this.felts.length) {
+      throw new Error("unexpected end of felts");
+    }
+    return this.felts[this.position++];
+  }
+
+  bytes(size: number): bigint {
+    const value = this.felt();
+    if (value >> BigInt(size * 8) !== 0n) {
+      throw new Error(`felt does not fit in ${size} bytes`);
+    }
+    return value;
+  }
+
+  tag(what: string): boolean {
+    const value = this.felt();
+    if (value > 1n) {
+      throw new Error(`invalid ${what} tag ${value}`);
+    }
+    return value === 1n;
+  }
+}
+
+export function hex(value: bigint, size: number): string {
+  return `0x${value.toString(16).padStart(size * 2, "0")}`;
+}
+
+function toBytes(value: bigint, size: number): Uint8Array {
+  const bytes = new Uint8Array(size);
+  for (let i = size - 1; i >= 0; i--) {
+    bytes[i] = Number(value & 0xffn);
+    value >>= 8n;
+  }
+  return bytes;
+}
+
+export function readInt(r: FeltReader, bits: number): bigint {
+  const felt = r.felt();
+  const value = felt > PRIME / 2n ? felt - PRIME : felt;
+  const limit = 1n << BigInt(bits - 1);
+  if (value < -limit || value >= limit) {
+    throw new Error(`felt out of range for i${bits}`);
+  }
+  return value;
+}
+
+export function readU256(r: FeltReader): bigint {
+  const low = r.bytes(16);
+  return low | (r.bytes(16) << 128n);
+}
+
+export function readU512(r: FeltReader): bigint {
+  let value = 0n;
+  for (let i = 0; i < 4; i++) {
+    value |= r.bytes(16) << BigInt(128 * i);
+  }
+  return value;
+}
+
+export function readShortString(r: FeltReader): string {
+  const bytes = toBytes(r.bytes(31), 31);
+  const first = bytes.findIndex((byte) => byte !== 0);
+  return new TextDecoder().decode(first < 0 ? new Uint8Array() : bytes.subarray(first));
+}
+
+export function readByteArray(r: FeltReader): Uint8Array {
+  const bytes: number[] = [];
+  if (r.layout === "iserde") {
+    for (;;) {
+      const [info, ...rest] = toBytes(r.felt(), 32);
+      bytes.push(...(info & 2 ? rest.slice(31 - rest[1], 31) : rest));
+      if (info & 1) {
+        return Uint8Array.from(bytes);
+      }
+    }
+  }
+  const len = Number(r.bytes(4));
+  for (let i = 0; i < len; i++) {
+    bytes.push(...toBytes(r.bytes(31), 31));
+  }
+  const pending = toBytes(r.bytes(31), 31);
+  const pendingLen = Number(r.bytes(1));
+  bytes.push(...pending.subarray(31 - pendingLen));
+  return Uint8Array.from(bytes);
+}
+
+export function readString(r: FeltReader): string {
+  return new TextDecoder().decode(readByteArray(r));
+}
+
+export function readFixedArray<T>(r: FeltReader, size: number, read: (r: FeltReader) => T): T[] {
+  const items: T[] = [];
+  for (let i = 0; i < size; i++) {
+    items.push(read(r));
+  }
+  return items;
+}
+
+export function readArray<T>(r: FeltReader, read: (r: FeltReader) => T): T[] {
+  return readFixedArray(r, Number(r.bytes(4)), read);
+}
+
+export function readOptionIsSome(r: FeltReader): boolean {
+  const tag = r.tag("option");
+  return r.layout === "iserde" ? tag : !tag;
+}
+
+export function readNullableIsNull(r: FeltReader): boolean {
+  return !r.tag("nullable");
+}
+
+export function readResultIsOk(r: FeltReader): boolean {
+  return !r.tag("result");
+}
+
+export function readVariant(r: FeltReader, name: string, selectors: readonly bigint[]): number {
+  const variant = r.felt();
+  const index = r.indexedEnums ? Number(variant) : selectors.indexOf(variant);
+  if (index < 0 || index >= selectors.length) {
+    throw new Error(`invalid variant ${variant} of ${name}`);
+  }
+  return index;
+}
+"#;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum TsCodegenError {
+    #[error("unknown type reference: {0:#x}")]
+    UnknownRef(Felt),
+
+    #[error("conflicting definitions for TypeScript type {0}")]
+    ConflictingType(String),
+
+    #[error("{0} has no TypeScript mapping")]
+    Unsupported(&'static str),
+}
+
+pub type TsCodegenResult<T> = Result<T, TsCodegenError>;
+
+/// Generated TypeScript: the runtime followed by one item per named type.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TsModule {
+    pub items: BTreeMap<String, String>,
+}
+
+impl Display for TsModule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(TS_RUNTIME)?;
+        for item in self.items.values() {
+            write!(f, "\n{item}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How a value of a type is typed and read by the generated decoders, `read`
+/// being an expression of the reader `r`.
+struct Mapped {
+    ty: String,
+    read: String,
+}
+
+fn mapped(ty: &str, read: &str) -> Mapped {
+    Mapped {
+        ty: ty.to_string(),
+        read: read.to_string(),
+    }
+}
+
+/// Emits TypeScript types and decoders of `TypeDef`s and tables.
+///
+/// Structs become interfaces and enums unions discriminated by `variant`, with
+/// the payload under `value`. Each gets a `decodeX(r: FeltReader)` function
+/// reading the same felt layout as the Rust decoders. Felts, addresses and
+/// `bytes31` decode to padded hex strings, integers over 32 bits to `bigint`s
+/// and byte arrays to `Uint8Array`s, where `to_json` emits numbers and hex
+/// strings. Options and nullables are `T | null` and results `{ ok } | { err }`.
+#[derive(Default)]
+pub struct TsGenerator<'a> {
+    refs: Option<&'a dyn GetRefTypeDef>,
+    in_progress: HashSet<String>,
+    module: TsModule,
+}
+
+impl<'a> TsGenerator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_refs(refs: &'a dyn GetRefTypeDef) -> Self {
+        TsGenerator {
+            refs: Some(refs),
+            ..Default::default()
+        }
+    }
+
+    pub fn finish(self) -> TsModule {
+        self.module
+    }
+
+    /// TypeScript type of `type_def`, adding the items it needs to the module.
+    pub fn ts_type(&mut self, type_def: &TypeDef) -> TsCodegenResult<String> {
+        self.map(type_def).map(|mapped| mapped.ty)
+    }
+
+    /// Adds an interface named after the table holding the primary followed by
+    /// the columns, and its decoder.
+    pub fn table(&mut self, table: &TableSchema) -> TsCodegenResult<String> {
+        let name = type_name(&table.name);
+        let fields = std::iter::once((&table.primary.name, TypeDef::from(&table.primary.type_def)))
+            .chain(
+                table
+                    .columns
+                    .iter()
+                    .map(|column| (&column.name, column.type_def.clone())),
+            )
+            .map(|(field, type_def)| Ok((field.clone(), self.map(&type_def)?)))
+            .collect::<TsCodegenResult<Vec<_>>>()?;
+        self.add_interface(&name, &fields)?;
+        Ok(name)
+    }
+
+    fn map(&mut self, type_def: &TypeDef) -> TsCodegenResult<Mapped> {
+        Ok(match type_def {
+            TypeDef::None => mapped("null", "null"),
+            TypeDef::Felt252
+            | TypeDef::ClassHash
+            | TypeDef::ContractAddress
+            | TypeDef::StorageAddress
+            | TypeDef::StorageBaseAddress => mapped("string", "hex(r.felt(), 32)"),
+            TypeDef::EthAddress => mapped("string", "hex(r.bytes(20), 20)"),
+            TypeDef::Bytes31 | TypeDef::Bytes31Encoded(_) => {
+                mapped("string", "hex(r.bytes(31), 31)")
+            }
+            TypeDef::ShortUtf8 => mapped("string", "readShortString(r)"),
+            TypeDef::Utf8String => mapped("string", "readString(r)"),
+            TypeDef::ByteArray | TypeDef::ByteArrayEncoded(_) => {
+                mapped("Uint8Array", "readByteArray(r)")
+            }
+            TypeDef::Bool => mapped("boolean", "r.tag(\"bool\")"),
+            TypeDef::U8 => mapped("number", "Number(r.bytes(1))"),
+            TypeDef::U16 => mapped("number", "Number(r.bytes(2))"),
+            TypeDef::U32 => mapped("number", "Number(r.bytes(4))"),
+            TypeDef::U64 => mapped("bigint", "r.bytes(8)"),
+            TypeDef::U128 => mapped("bigint", "r.bytes(16)"),
+            TypeDef::I8 => mapped("number", "Number(readInt(r, 8))"),
+            TypeDef::I16 => mapped("number", "Number(readInt(r, 16))"),
+            TypeDef::I32 => mapped("number", "Number(readInt(r, 32))"),
+            TypeDef::I64 => mapped("bigint", "readInt(r, 64)"),
+            TypeDef::I128 => mapped("bigint", "readInt(r, 128)"),
+            TypeDef::U256 => mapped("bigint", "readU256(r)"),
+            TypeDef::U512 => mapped("bigint", "readU512(r)"),
+            TypeDef::Custom(_) => mapped("Array<string>", "readArray(r, (r) => hex(r.felt(), 32))"),
+            TypeDef::Array(array) => {
+                let item = self.map(&array.type_def)?;
+                Mapped {
+                    ty: format!("Array<{}>", item.ty),
+                    read: format!("readArray(r, (r) => {})", item.read),
+                }
+            }
+            TypeDef::FixedArray(fixed) => {
+                let item = self.map(&fixed.type_def)?;
+                Mapped {
+                    ty: format!("Array<{}>", item.ty),
+                    read: format!("readFixedArray(r, {}, (r) => {})", fixed.size, item.read),
+                }
+            }
+            TypeDef::Tuple(tuple) => {
+                let elements = tuple
+                    .elements
+                    .iter()
+                    .map(|element| self.map(element))
+                    .collect::<TsCodegenResult<Vec<_>>>()?;
+                let join =
+                    |f: fn(&Mapped) -> &str| elements.iter().map(f).collect::<Vec<_>>().join(", ");
+                Mapped {
+                    ty: format!("[{}]", join(|m| m.ty.as_str())),
+                    read: format!("[{}]", join(|m| m.read.as_str())),
+                }
+            }
+            TypeDef::Option(option) => {
+                let inner = self.map(&option.type_def)?;
+                Mapped {
+                    ty: format!("{} | null", inner.ty),
+                    read: format!("(readOptionIsSome(r) ? {} : null)", inner.read),
+                }
+            }
+            TypeDef::Nullable(nullable) => {
+                let inner = self.map(&nullable.type_def)?;
+                Mapped {
+                    ty: format!("{} | null", inner.ty),
+                    read: format!("(readNullableIsNull(r) ? null : {})", inner.read),
+                }
+            }
+            TypeDef::Result(result) => {
+                let (ok, err) = (self.map(&result.ok)?, self.map(&result.err)?);
+                Mapped {
+                    ty: format!("{{ ok: {} }} | {{ err: {} }}", ok.ty, err.ty),
+                    read: format!(
+                        "(readResultIsOk(r) ? {{ ok: {} }} : {{ err: {} }})",
+                        ok.read, err.read
+                    ),
+                }
+            }
+            TypeDef::Struct(struct_def) => self.struct_type(struct_def)?,
+            TypeDef::Enum(enum_def) => self.enum_type(enum_def)?,
+            TypeDef::Ref(reference) => {
+                let type_def = self
+                    .refs
+                    .and_then(|refs| refs.get_type_def(reference.id))
+                    .ok_or(TsCodegenError::UnknownRef(reference.id))?;
+                self.map(&type_def)?
+            }
+            TypeDef::Felt252Dict(_) => return Err(TsCodegenError::Unsupported("Felt252Dict")),
+        })
+    }
+
+    fn struct_type(&mut self, struct_def: &StructDef) -> TsCodegenResult<Mapped> {
+        let name = type_name(&struct_def.name);
+        let result = mapped(&name, &format!("decode{name}(r)"));
+        if !self.in_progress.insert(name.clone()) {
+            return Ok(result);
+        }
+        let fields = struct_def
+            .members
+            .iter()
+            .map(|member| Ok((member.name.clone(), self.map(&member.type_def)?)))
+            .collect::<TsCodegenResult<Vec<_>>>();
+        self.in_progress.remove(&name);
+        self.add_interface(&name, &fields?)?;
+        Ok(result)
+    }
+
+    fn enum_type(&mut self, enum_def: &EnumDef) -> TsCodegenResult<Mapped> {
+        let name = type_name(&enum_def.name);
+        let result = mapped(&name, &format!("decode{name}(r)"));
+        if !self.in_progress.insert(name.clone()) {
+            return Ok(result);
+        }
+        let variants = enum_def
+            .order
+            .iter()
+            .filter_map(|selector| Some((selector, enum_def.variants.get(selector)?)))
+            .map(|(selector, variant)| {
+                let payload = match variant.type_def {
+                    TypeDef::None => None,
+                    ref type_def => Some(self.map(type_def)?),
+                };
+                Ok((*selector, type_name(&variant.name), payload))
+            })
+            .collect::<TsCodegenResult<Vec<_>>>();
+        self.in_progress.remove(&name);
+        let variants = variants?;
+
+        let prefix = camel_case(&name);
+        let mut union: String = variants
+            .iter()
+            .map(|(_, variant, payload)| match payload {
+                Some(payload) => {
+                    format!("\n  | {{ variant: \"{variant}\"; value: {} }}", payload.ty)
+                }
+                None => format!("\n  | {{ variant: \"{variant}\" }}"),
+            })
+            .collect();
+        if union.is_empty() {
+            union = " never".to_string();
+        }
+        let selectors: Vec<String> = variants
+            .iter()
+            .map(|(selector, _, _)| format!("{selector:#x}n"))
+            .collect();
+        let readers: String = variants
+            .iter()
+            .map(|(_, variant, payload)| match payload {
+                Some(payload) => format!(
+                    "  (r) => ({{ variant: \"{variant}\", value: {} }}),\n",
+                    payload.read
+                ),
+                None => format!("  () => ({{ variant: \"{variant}\" }}),\n"),
+            })
+            .collect();
+        let item = format!(
+            "export type {name} ={union};\n\n\
+             export const {prefix}Selectors: readonly bigint[] = [{selectors}];\n\n\
+             const {prefix}Variants: ReadonlyArray<(r: FeltReader) => {name}> = [\n{readers}];\n\n\
+             export function decode{name}(r: FeltReader): {name} {{\n\
+             \x20 return {prefix}Variants[readVariant(r, \"{name}\", {prefix}Selectors)](r);\n\
+             }}\n",
+            selectors = selectors.join(", "),
+        );
+        self.add_item(&name, item)?;
+        Ok(result)
+    }
+
+    fn add_interface(&mut self, name: &str, fields: &[(String, Mapped)]) -> TsCodegenResult<()> {
+        let definitions: String = fields
+            .iter()
+            .map(|(field, mapped)| format!("  {field}: {};\n", mapped.ty))
+            .collect();
+        let reads: String = fields
+            .iter()
+            .map(|(field, mapped)| format!("    {field}: {},\n", mapped.read))
+            .collect();
+        let item = format!(
+            "export interface {name} {{\n{definitions}}}\n\n\
+             export function decode{name}(r: FeltReader): {name} {{\n\
+             \x20 return {{\n{reads}  }};\n\
+             }}\n"
+        );
+        self.add_item(name, item)
+    }
+
+    fn add_item(&mut self, name: &str, item: String) -> TsCodegenResult<()> {
+        match self.module.items.get(name) {
+            Some(existing) if *existing != item => {
+                Err(TsCodegenError::ConflictingType(name.to_string()))
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.module.items.insert(name.to_string(), item);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl TypeDef {
+    /// TypeScript types and decoders of the type, see `TsGenerator`.
+    pub fn ts_module(&self) -> TsCodegenResult<TsModule> {
+        let mut generator = TsGenerator::new();
+        generator.ts_type(self)?;
+        Ok(generator.finish())
+    }
+}
+
+impl TableSchema {
+    /// TypeScript record type and decoder of the table, see `TsGenerator::table`.
+    pub fn ts_module(&self) -> TsCodegenResult<TsModule> {
+        let mut generator = TsGenerator::new();
+        generator.table(self)?;
+        Ok(generator.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArrayDef, ColumnDef, MemberDef, NullableDef, OptionDef, PrimaryDef, PrimaryTypeDef,
+        ResultDef, TupleDef, VariantDef,
+    };
+
+    fn direction() -> TypeDef {
+        EnumDef::new_type_def(
+            "game::Direction".to_string(),
+            vec![],
+            vec![
+                (
+                    Felt::from(0x10),
+                    VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+                ),
+                (
+                    Felt::from(0x20),
+                    VariantDef::new("jump".to_string(), vec![], TypeDef::U8),
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn generates_struct_and_enum_decoders() {
+        let position = StructDef::new_type_def(
+            "game::Position".to_string(),
+            vec![],
+            vec![
+                MemberDef::new("gold".to_string(), vec![], TypeDef::U256),
+                MemberDef::new(
+                    "moves".to_string(),
+                    vec![],
+                    ArrayDef::new_type_def(OptionDef::new_type_def(TupleDef::new_type_def(vec![
+                        TypeDef::Bool,
+                        TypeDef::ShortUtf8,
+                    ]))),
+                ),
+                MemberDef::new(
+                    "last".to_string(),
+                    vec![],
+                    NullableDef::new_type_def(ResultDef::new_type_def(direction(), TypeDef::I8)),
+                ),
+            ],
+        );
+        let module = position.ts_module().unwrap();
+        assert_eq!(
+            module.items["Position"],
+            "export interface Position {\n  \
+             gold: bigint;\n  \
+             moves: Array<[boolean, string] | null>;\n  \
+             last: { ok: Direction } | { err: number } | null;\n}\n\n\
+             export function decodePosition(r: FeltReader): Position {\n  \
+             return {\n    \
+             gold: readU256(r),\n    \
+             moves: readArray(r, (r) => (readOptionIsSome(r) ? \
+             [r.tag(\"bool\"), readShortString(r)] : null)),\n    \
+             last: (readNullableIsNull(r) ? null : (readResultIsOk(r) ? \
+             { ok: decodeDirection(r) } : { err: Number(readInt(r, 8)) })),\n  \
+             };\n}\n"
+        );
+        assert_eq!(
+            module.items["Direction"],
+            "export type Direction =\n  \
+             | { variant: \"Up\" }\n  \
+             | { variant: \"Jump\"; value: number };\n\n\
+             export const directionSelectors: readonly bigint[] = [0x10n, 0x20n];\n\n\
+             const directionVariants: ReadonlyArray<(r: FeltReader) => Direction> = [\n  \
+             () => ({ variant: \"Up\" }),\n  \
+             (r) => ({ variant: \"Jump\", value: Number(r.bytes(1)) }),\n];\n\n\
+             export function decodeDirection(r: FeltReader): Direction {\n  \
+             return directionVariants[readVariant(r, \"Direction\", directionSelectors)](r);\n}\n"
+        );
+        assert!(module.to_string().starts_with(TS_RUNTIME));
+    }
+
+    #[test]
+    fn generates_table_decoders() {
+        let table = TableSchema {
+            id: Felt::ONE,
+            name: "player_state".to_string(),
+            attributes: vec![],
+            primary: PrimaryDef::new("owner".to_string(), vec![], PrimaryTypeDef::EthAddress),
+            columns: vec![ColumnDef::new(
+                Felt::ONE,
+                "name".to_string(),
+                vec![],
+                TypeDef::ByteArray,
+            )],
+        };
+        let module = table.ts_module().unwrap();
+        assert_eq!(
+            module.items["PlayerState"],
+            "export interface PlayerState {\n  owner: string;\n  name: Uint8Array;\n}\n\n\
+             export function decodePlayerState(r: FeltReader): PlayerState {\n  \
+             return {\n    owner: hex(r.bytes(20), 20),\n    name: readByteArray(r),\n  };\n}\n"
+        );
+        let dict = ArrayDef::new_type_def(crate::Felt252DictDef::new_type_def(TypeDef::U8));
+        assert_eq!(
+            dict.ts_module(),
+            Err(TsCodegenError::Unsupported("Felt252Dict"))
+        );
+    }
+}
@@ -17,14 +17,123 @@ pub fn felt_to_hex_string(value: &Felt) -> String {
     format!("0x{:064x}", value)
 }
 
+/// Converts a `snake_case` name to `PascalCase`, any character outside
+/// `[A-Za-z0-9]` separating words.
+pub fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |first| {
+                first.to_ascii_uppercase().to_string() + chars.as_str()
+            })
+        })
+        .collect()
+}
+
+/// Last path segment of a Cairo name, with characters outside `[A-Za-z0-9]`
+/// replaced by `_`.
+fn sanitize(name: &str) -> String {
+    name.rsplit("::")
+        .next()
+        .unwrap_or(name)
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
+
+/// Turns a Cairo name into an identifier of `[A-Za-z0-9_]`, keeping the last
+/// path segment and prefixing `_` when it would start with a digit.
+pub fn identifier(name: &str) -> String {
+    let mut out = sanitize(name);
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+/// `PascalCase` type name of a Cairo type, keeping the last path segment.
+pub fn type_name(name: &str) -> String {
+    let name = pascal_case(name.rsplit("::").next().unwrap_or(name));
+    match name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+        true => format!("_{name}"),
+        false => name,
+    }
+}
+
+/// Lowercases the first character of a `PascalCase` name.
+pub fn camel_case(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_ascii_lowercase().to_string() + chars.as_str()
+    })
+}
+
+/// Turns a Cairo name into a protobuf identifier, which must start with a
+/// letter.
+pub fn proto_name(name: &str) -> String {
+    let out = sanitize(name);
+    match out.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        true => out,
+        false => format!("x{out}"),
+    }
+}
+
+const CAIRO_KEYWORDS: [&str; 34] = [
+    "as",
+    "break",
+    "const",
+    "continue",
+    "else",
+    "enum",
+    "extern",
+    "false",
+    "fn",
+    "for",
+    "if",
+    "impl",
+    "implicits",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "mut",
+    "nopanic",
+    "of",
+    "pub",
+    "ref",
+    "return",
+    "self",
+    "Self",
+    "static",
+    "struct",
+    "super",
+    "trait",
+    "true",
+    "type",
+    "use",
+    "while",
+    "macro",
+];
+
+/// Cairo identifier for a name, replacing invalid characters and suffixing
+/// keywords with `_`.
+pub fn cairo_ident(name: &str) -> String {
+    let mut ident = identifier(name);
+    if CAIRO_KEYWORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
 pub const fn ascii_str_to_felt(s: &str) -> Felt {
     Felt::from_raw(ascii_str_to_limbs(s))
 }
 
-pub const fn ascii_str_to_limbs(s: &str) -> [u64; 4] {
-    pub const FELT_MODULUS: UnsignedInteger<4> = UnsignedInteger::<4>::from_hex_unchecked(
-        "800000000000011000000000000000000000000000000000000000000000001",
-    );
+pub const fn ascii_str_to_be_digits(s: &str) -> [u64; 4] {
     const fn shift_u64_char(value: &mut u64, c: u64) -> u64 {
         let carry = *value >> 56;
         *value = (*value << 8) + c;
@@ -43,6 +152,14 @@ pub const fn ascii_str_to_limbs(s: &str) -> [u64; 4] {
         n += 1;
     }
     assert!(n < 32, "String too long to convert to Felt");
+    limbs
+}
+
+pub const fn ascii_str_to_limbs(s: &str) -> [u64; 4] {
+    pub const FELT_MODULUS: UnsignedInteger<4> = UnsignedInteger::<4>::from_hex_unchecked(
+        "800000000000011000000000000000000000000000000000000000000000001",
+    );
+    let limbs = ascii_str_to_be_digits(s);
 
     MontgomeryAlgorithms::cios(
         &UnsignedInteger { limbs },
@@ -212,4 +329,20 @@ mod tests {
     fn test_len_32_string_serialization() {
         test_string_to_cairo_serialize_bytearray("this is a string with length 32!");
     }
+
+    #[test]
+    fn test_type_selectors_are_short_string_digits() {
+        use crate::type_def::selectors;
+        for (name, selector) in [
+            ("u8", selectors::U8),
+            ("felt252", selectors::Felt252),
+            ("byte_array_encoded", selectors::ByteArrayEncoded),
+        ] {
+            assert_eq!(
+                Felt::from_bytes_be_slice(name.as_bytes()).to_be_digits(),
+                selector
+            );
+            assert_eq!(ascii_str_to_felt(name).to_be_digits(), selector);
+        }
+    }
 }
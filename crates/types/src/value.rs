@@ -113,6 +113,33 @@ impl PrimaryValue {
     }
 }
 
+impl From<PrimaryValue> for Value {
+    fn from(value: PrimaryValue) -> Self {
+        match value {
+            PrimaryValue::Felt252(value) => Value::Felt252(value),
+            PrimaryValue::ShortUtf8(value) => Value::ShortUtf8(value),
+            PrimaryValue::Bytes31(value) => Value::Bytes31(value),
+            PrimaryValue::Bytes31Encoded(value) => Value::Bytes31Encoded(value),
+            PrimaryValue::Bool(value) => Value::Bool(value),
+            PrimaryValue::U8(value) => Value::U8(value),
+            PrimaryValue::U16(value) => Value::U16(value),
+            PrimaryValue::U32(value) => Value::U32(value),
+            PrimaryValue::U64(value) => Value::U64(value),
+            PrimaryValue::U128(value) => Value::U128(value),
+            PrimaryValue::I8(value) => Value::I8(value),
+            PrimaryValue::I16(value) => Value::I16(value),
+            PrimaryValue::I32(value) => Value::I32(value),
+            PrimaryValue::I64(value) => Value::I64(value),
+            PrimaryValue::I128(value) => Value::I128(value),
+            PrimaryValue::ClassHash(value) => Value::ClassHash(value),
+            PrimaryValue::ContractAddress(value) => Value::ContractAddress(value),
+            PrimaryValue::EthAddress(value) => Value::EthAddress(value),
+            PrimaryValue::StorageAddress(value) => Value::StorageAddress(value),
+            PrimaryValue::StorageBaseAddress(value) => Value::StorageBaseAddress(value),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Struct {
     pub name: String,
@@ -0,0 +1,63 @@
+use introspect_types::{CairoDeserialize, CairoDeserializer, DecodeError, DecodeResult, EnumVariantEncoding};
+use starknet_types_core::felt::Felt;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Direction {
+    Up,
+    Jump(u8),
+}
+
+impl Direction {
+    pub const SELECTORS: [Felt; 2] = [Felt::from_hex_unchecked("0x10"), Felt::from_hex_unchecked("0x20")];
+}
+
+impl<D: CairoDeserializer> CairoDeserialize<D> for Direction {
+    fn deserialize(deserializer: &mut D) -> DecodeResult<Self> {
+        let variant = deserializer.next_enum_variant()?;
+        let encoding = deserializer.enum_variant_encoding();
+        let index = match encoding {
+            EnumVariantEncoding::Selector => Self::SELECTORS.iter().position(|selector| *selector == variant),
+            EnumVariantEncoding::Index => u64::try_from(variant).ok().map(|index| index as usize),
+        };
+        match index {
+            Some(0) => Ok(Direction::Up),
+            Some(1) => Ok(Direction::Jump(deserializer.next_u8()?)),
+            _ => Err(match encoding {
+                EnumVariantEncoding::Selector => DecodeError::invalid_enum_selector("Direction", variant),
+                EnumVariantEncoding::Index => DecodeError::enum_index_out_of_range("Direction", variant, 2),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Node {
+    pub value: u8,
+    pub next: Box<Option<Node>>,
+}
+
+impl<D: CairoDeserializer> CairoDeserialize<D> for Node {
+    fn deserialize(deserializer: &mut D) -> DecodeResult<Self> {
+        Ok(Node {
+            value: deserializer.next_u8()?,
+            next: Box::new(deserializer.next_option()?),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Player {
+    pub owner: Felt,
+    pub moves: Vec<Direction>,
+    pub path: Node,
+}
+
+impl<D: CairoDeserializer> CairoDeserialize<D> for Player {
+    fn deserialize(deserializer: &mut D) -> DecodeResult<Self> {
+        Ok(Player {
+            owner: deserializer.next_felt()?,
+            moves: deserializer.next_array()?,
+            path: Node::deserialize(deserializer)?,
+        })
+    }
+}
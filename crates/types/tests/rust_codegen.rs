@@ -0,0 +1,121 @@
+use introspect_types::{
+    ArrayDef, CairoDeserialize, CairoSerde, ColumnDef, DecodeError, EnumDef, IndexedEnums,
+    IntoFeltSource, MemberDef, OptionDef, PrimaryDef, PrimaryTypeDef, RefDef, RustGenerator,
+    StructDef, TableSchema, TypeDef, VariantDef,
+};
+use starknet_types_core::felt::Felt;
+use std::collections::HashMap;
+
+mod generated {
+    include!("fixtures/rust_codegen.rs");
+}
+
+const FIXTURE: &str = include_str!("fixtures/rust_codegen.rs");
+
+fn node() -> TypeDef {
+    StructDef::new_type_def(
+        "game::Node".to_string(),
+        vec![],
+        vec![
+            MemberDef::new("value".to_string(), vec![], TypeDef::U8),
+            MemberDef::new(
+                "next".to_string(),
+                vec![],
+                OptionDef::new_type_def(RefDef::new_type_def(Felt::ONE)),
+            ),
+        ],
+    )
+}
+
+fn direction() -> TypeDef {
+    EnumDef::new_type_def(
+        "game::Direction".to_string(),
+        vec![],
+        vec![
+            (
+                Felt::from(0x10),
+                VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+            ),
+            (
+                Felt::from(0x20),
+                VariantDef::new("Jump".to_string(), vec![], TypeDef::U8),
+            ),
+        ],
+    )
+}
+
+fn player() -> TableSchema {
+    TableSchema {
+        id: Felt::ONE,
+        name: "player".to_string(),
+        attributes: vec![],
+        primary: PrimaryDef::new("owner".to_string(), vec![], PrimaryTypeDef::ContractAddress),
+        columns: vec![
+            ColumnDef::new(
+                Felt::ONE,
+                "moves".to_string(),
+                vec![],
+                ArrayDef::new_type_def(direction()),
+            ),
+            ColumnDef::new(
+                Felt::TWO,
+                "path".to_string(),
+                vec![],
+                RefDef::new_type_def(Felt::ONE),
+            ),
+        ],
+    }
+}
+
+#[test]
+fn generated_code_matches_the_fixture() {
+    let refs = HashMap::from([(Felt::ONE, node())]);
+    let mut generator = RustGenerator::with_refs(&refs);
+    generator.table(&player()).unwrap();
+    assert_eq!(generator.finish().to_string(), FIXTURE);
+}
+
+#[test]
+fn generated_code_compiles_and_decodes() {
+    let felts = vec![
+        Felt::from(0x1234),
+        Felt::TWO,
+        Felt::from(0x20),
+        Felt::THREE,
+        Felt::from(0x10),
+        Felt::from(5),
+        Felt::ZERO,
+        Felt::from(6),
+        Felt::ONE,
+    ];
+    let mut deserializer = CairoSerde(felts.into_source());
+    let player = generated::Player::deserialize(&mut deserializer).unwrap();
+    assert_eq!(
+        player,
+        generated::Player {
+            owner: Felt::from(0x1234),
+            moves: vec![generated::Direction::Jump(3), generated::Direction::Up],
+            path: generated::Node {
+                value: 5,
+                next: Box::new(Some(generated::Node {
+                    value: 6,
+                    next: Box::new(None),
+                })),
+            },
+        }
+    );
+}
+
+#[test]
+fn generated_enums_report_bad_selectors_and_indexes() {
+    let mut deserializer = CairoSerde(vec![Felt::from(0x30)].into_source());
+    assert!(matches!(
+        generated::Direction::deserialize(&mut deserializer),
+        Err(DecodeError::InvalidEnumSelector { .. })
+    ));
+    let mut deserializer = IndexedEnums(CairoSerde(vec![Felt::TWO].into_source()));
+    assert!(matches!(
+        generated::Direction::deserialize(&mut deserializer),
+        Err(DecodeError::EnumIndexOutOfRange { len: 2, .. })
+    ));
+}
@@ -0,0 +1,133 @@
+use cairo_syntax_parser::str_to_token_stream;
+use introspect_macros::utils::string_to_keccak_felt;
+use introspect_macros::{IEnum, IStruct, IntrospectItem, TypeDefVariant};
+use introspect_types::{
+    ByteArrayEncodedDef, Bytes31EncodedDef, EnumDef, MemberDef, StructDef, TypeDef, VariantDef,
+};
+use std::collections::BTreeMap;
+
+/// `TypeDef` the derives describe for the parsed items, resolving the Cairo
+/// types the generator emits.
+struct Items(BTreeMap<String, IntrospectItem>);
+
+impl Items {
+    fn parse(source: &BTreeMap<String, String>) -> Self {
+        Items(
+            source
+                .iter()
+                .map(|(name, item)| {
+                    let parsed = IntrospectItem::from_token_stream(str_to_token_stream(item))
+                        .unwrap_or_else(|_| panic!("{name} does not parse"));
+                    (name.clone(), parsed)
+                })
+                .collect(),
+        )
+    }
+
+    fn type_def(&self, name: &str) -> TypeDef {
+        match &self.0[name] {
+            IntrospectItem::Struct(s) => self.struct_def(s),
+            IntrospectItem::Enum(e) => self.enum_def(e),
+        }
+    }
+
+    fn struct_def(&self, item: &IStruct) -> TypeDef {
+        let members = item
+            .members
+            .iter()
+            .map(|member| {
+                MemberDef::new(
+                    member.name.clone(),
+                    vec![],
+                    self.field(Some(&member.ty), &member.type_def),
+                )
+            })
+            .collect();
+        StructDef::new_type_def(item.name.clone(), vec![], members)
+    }
+
+    fn enum_def(&self, item: &IEnum) -> TypeDef {
+        let variants = item
+            .variants
+            .iter()
+            .map(|variant| {
+                let type_def = self.field(variant.ty.as_deref(), &variant.type_def);
+                (
+                    variant.selector,
+                    VariantDef::new(variant.name.clone(), vec![], type_def),
+                )
+            })
+            .collect();
+        EnumDef::new_type_def(item.name.clone(), vec![], variants)
+    }
+
+    fn field(&self, ty: Option<&str>, type_def: &TypeDefVariant) -> TypeDef {
+        match (ty, type_def) {
+            (_, TypeDefVariant::TypeDef(type_def)) => type_def.clone(),
+            (None, _) => TypeDef::None,
+            (Some(ty), _) => match ty {
+                "felt252" => TypeDef::Felt252,
+                "u32" => TypeDef::U32,
+                "core::integer::u512" => TypeDef::U512,
+                "bytes31" => TypeDef::ShortUtf8,
+                "ByteArray" => TypeDef::Utf8String,
+                name => self.type_def(name),
+            },
+        }
+    }
+}
+
+fn direction() -> TypeDef {
+    EnumDef::new_type_def(
+        "Direction".to_string(),
+        vec![],
+        vec![
+            (
+                string_to_keccak_felt("Up"),
+                VariantDef::new("Up".to_string(), vec![], TypeDef::None),
+            ),
+            (
+                string_to_keccak_felt("type_"),
+                VariantDef::new("type".to_string(), vec![], TypeDef::U512),
+            ),
+            (
+                string_to_keccak_felt("Jump"),
+                VariantDef::new("Jump".to_string(), vec![], TypeDef::Bytes31),
+            ),
+            (
+                string_to_keccak_felt("Tag"),
+                VariantDef::new(
+                    "Tag".to_string(),
+                    vec![],
+                    Bytes31EncodedDef::new_type_def("ascii".to_string()),
+                ),
+            ),
+        ],
+    )
+}
+
+#[test]
+fn generated_items_parse_back_to_their_type_defs() {
+    let position = StructDef::new_type_def(
+        "Position".to_string(),
+        vec![],
+        vec![
+            MemberDef::new("type".to_string(), vec![], TypeDef::U512),
+            MemberDef::new("x".to_string(), vec![], TypeDef::U32),
+            MemberDef::new("id".to_string(), vec![], TypeDef::Felt252),
+            MemberDef::new("raw".to_string(), vec![], TypeDef::ByteArray),
+            MemberDef::new(
+                "label".to_string(),
+                vec![],
+                ByteArrayEncodedDef::new_type_def("ascii".to_string()),
+            ),
+            MemberDef::new("short".to_string(), vec![], TypeDef::ShortUtf8),
+            MemberDef::new("note".to_string(), vec![], TypeDef::Utf8String),
+            MemberDef::new("facing".to_string(), vec![], direction()),
+        ],
+    );
+    let module = position.cairo_module().unwrap();
+    let items = Items::parse(&module.items);
+    assert_eq!(items.type_def("Direction"), direction());
+    assert_eq!(items.type_def("Position"), position);
+}